@@ -0,0 +1,237 @@
+use std::fmt;
+use thiserror::Error;
+
+use crate::frame_source::{self, FrameSource, FrameSourceError, SourceFrame};
+use crate::gst_camera::{GstCamera, GstCameraError};
+use crate::playback_camera::Pacing;
+
+#[derive(Error, Debug)]
+pub enum BayerSimError {
+    #[error("GStreamer Fehler: {0}")]
+    GstError(#[from] GstCameraError),
+    #[error("Auflösung {0}x{1} nicht möglich: Breite muss durch 4, Höhe durch 2 teilbar sein")]
+    Resolution(u32, u32),
+    #[error("Demosaik lieferte {0} Bytes, erwartet {1}")]
+    FrameSize(usize, usize),
+}
+
+pub type Result<T> = std::result::Result<T, BayerSimError>;
+
+/// Anordnung des Farbfilters (Color Filter Array), benannt nach dem 2x2-Block oben links
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CfaPattern {
+    Rggb,
+    Bggr,
+    Grbg,
+    Gbrg,
+}
+
+impl CfaPattern {
+    /// Farbkanal (0 = R, 1 = G, 2 = B) des Pixels (x, y)
+    fn channel(self, x: usize, y: usize) -> usize {
+        let block = match self {
+            CfaPattern::Rggb => [0, 1, 1, 2],
+            CfaPattern::Bggr => [2, 1, 1, 0],
+            CfaPattern::Grbg => [1, 0, 2, 1],
+            CfaPattern::Gbrg => [1, 2, 0, 1],
+        };
+        block[(y % 2) * 2 + x % 2]
+    }
+}
+
+impl fmt::Display for CfaPattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            CfaPattern::Rggb => "rggb",
+            CfaPattern::Bggr => "bggr",
+            CfaPattern::Grbg => "grbg",
+            CfaPattern::Gbrg => "gbrg",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Muster und Bittiefe des simulierten Sensors, z.B. `rggb` oder `bggr16`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BayerFormat {
+    pub pattern: CfaPattern,
+    /// 8 oder 16 Bit pro Pixel (16 Bit little endian, wie RG16 der DFK-Kameras)
+    pub bits: u8,
+}
+
+impl BayerFormat {
+    /// Alle Kombinationen, die `test-bayer` ohne `--format` prüft
+    pub const ALL: [BayerFormat; 8] = [
+        BayerFormat { pattern: CfaPattern::Rggb, bits: 8 },
+        BayerFormat { pattern: CfaPattern::Bggr, bits: 8 },
+        BayerFormat { pattern: CfaPattern::Grbg, bits: 8 },
+        BayerFormat { pattern: CfaPattern::Gbrg, bits: 8 },
+        BayerFormat { pattern: CfaPattern::Rggb, bits: 16 },
+        BayerFormat { pattern: CfaPattern::Bggr, bits: 16 },
+        BayerFormat { pattern: CfaPattern::Grbg, bits: 16 },
+        BayerFormat { pattern: CfaPattern::Gbrg, bits: 16 },
+    ];
+
+    /// Format-Feld der `video/x-bayer`-Caps
+    pub fn caps_format(&self) -> String {
+        match self.bits {
+            16 => format!("{}16le", self.pattern),
+            _ => self.pattern.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for BayerFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.bits {
+            16 => write!(f, "{}16", self.pattern),
+            _ => write!(f, "{}", self.pattern),
+        }
+    }
+}
+
+impl std::str::FromStr for BayerFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let lower = s.to_lowercase();
+        let (pattern, bits) = match lower.strip_suffix("16le").or_else(|| lower.strip_suffix("16")) {
+            Some(pattern) => (pattern, 16),
+            None => (lower.strip_suffix('8').unwrap_or(&lower), 8),
+        };
+        let pattern = match pattern {
+            "rggb" => CfaPattern::Rggb,
+            "bggr" => CfaPattern::Bggr,
+            "grbg" => CfaPattern::Grbg,
+            "gbrg" => CfaPattern::Gbrg,
+            _ => {
+                return Err(format!(
+                    "Unbekanntes Bayer-Format: {} (rggb, bggr, grbg, gbrg, optional mit 8 oder 16)",
+                    s
+                ));
+            }
+        };
+        Ok(BayerFormat { pattern, bits })
+    }
+}
+
+/// Tastet ein RGB-Bild mit dem Farbfilter ab: ein Kanal pro Pixel, 16 Bit auf den
+/// vollen Wertebereich gestreckt
+pub fn mosaic(rgb: &[u8], width: u32, height: u32, format: BayerFormat) -> Vec<u8> {
+    let (w, h) = (width as usize, height as usize);
+    let bytes_per_pixel = if format.bits == 16 { 2 } else { 1 };
+    let mut out = Vec::with_capacity(w * h * bytes_per_pixel);
+
+    for y in 0..h {
+        for x in 0..w {
+            let value = rgb[(y * w + x) * 3 + format.pattern.channel(x, y)];
+            if format.bits == 16 {
+                out.extend_from_slice(&(value as u16 * 257).to_le_bytes());
+            } else {
+                out.push(value);
+            }
+        }
+    }
+
+    out
+}
+
+/// Spitzen-Signal-Rausch-Abstand in dB zwischen zwei RGB-Frames gleicher Größe
+pub fn psnr(a: &[u8], b: &[u8]) -> f64 {
+    let sum: f64 = a
+        .iter()
+        .zip(b)
+        .map(|(&x, &y)| {
+            let d = x as f64 - y as f64;
+            d * d
+        })
+        .sum();
+    let mse = sum / a.len().max(1) as f64;
+    if mse == 0.0 {
+        return f64::INFINITY;
+    }
+    10.0 * (255.0 * 255.0 / mse).log10()
+}
+
+/// Simulierter Bayer-Sensor: Frames einer beliebigen Quelle werden mosaikiert und
+/// durch dieselbe `bayer2rgb`-Pipeline wie bei `GstCamera` zurück nach RGB gewandelt
+pub struct BayerSimSource {
+    inner: Box<dyn FrameSource>,
+    camera: GstCamera,
+    format: BayerFormat,
+    /// Letzter Frame der Quelle vor dem Mosaik, für `test-bayer`
+    last_original: Vec<u8>,
+}
+
+impl BayerSimSource {
+    pub fn new(inner: Box<dyn FrameSource>, format: BayerFormat) -> Result<Self> {
+        let (width, height) = inner.resolution();
+        // bayer2rgb und RGB-Ausgabe rechnen mit auf 4 Byte gerundeten Zeilen
+        if !width.is_multiple_of(4) || !height.is_multiple_of(2) {
+            return Err(BayerSimError::Resolution(width, height));
+        }
+
+        let fps = inner.fps().round().max(1.0) as u32;
+        let camera = GstCamera::new_simulated(
+            &format!("{} ({})", inner.name(), format),
+            width,
+            height,
+            fps,
+            &format.caps_format(),
+        )?;
+        camera.start()?;
+
+        Ok(Self {
+            inner,
+            camera,
+            format,
+            last_original: Vec::new(),
+        })
+    }
+
+    /// Quell-Frame zum zuletzt gelieferten Ergebnis von `next_frame`
+    pub fn last_original(&self) -> &[u8] {
+        &self.last_original
+    }
+
+    fn demosaic(&mut self, rgb: &[u8]) -> Result<Vec<u8>> {
+        let (width, height) = self.inner.resolution();
+        self.camera.push_raw(mosaic(rgb, width, height, self.format))?;
+        let data = self.camera.get_frame()?;
+        let expected = (width * height * 3) as usize;
+        if data.len() != expected {
+            return Err(BayerSimError::FrameSize(data.len(), expected));
+        }
+        Ok(data)
+    }
+}
+
+impl FrameSource for BayerSimSource {
+    fn name(&self) -> String {
+        self.inner.name()
+    }
+
+    fn resolution(&self) -> (u32, u32) {
+        self.inner.resolution()
+    }
+
+    fn fps(&self) -> f64 {
+        self.inner.fps()
+    }
+
+    fn next_frame(&mut self) -> frame_source::Result<SourceFrame> {
+        let frame = self.inner.next_frame()?;
+        let data = self
+            .demosaic(&frame.data)
+            .map_err(|e| FrameSourceError::ReadError(e.to_string()))?;
+        self.last_original = frame.data;
+        Ok(SourceFrame {
+            data,
+            pts: frame.pts,
+        })
+    }
+
+    fn set_pacing(&mut self, pacing: Pacing) {
+        self.inner.set_pacing(pacing);
+    }
+}
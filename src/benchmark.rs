@@ -0,0 +1,191 @@
+use std::path::Path;
+use std::time::{Duration, Instant};
+use thiserror::Error;
+
+use crate::camera::CameraDevice;
+use crate::direct_recorder::DirectRecorder;
+use crate::recorder::VideoRecorder;
+
+#[derive(Error, Debug)]
+pub enum BenchmarkError {
+    #[error("Camera error: {0}")]
+    CameraError(String),
+    #[error("Recorder error: {0}")]
+    RecorderError(String),
+}
+
+pub type Result<T> = std::result::Result<T, BenchmarkError>;
+
+/// Ergebnis eines Aufnahme-Benchmarks
+#[derive(Debug)]
+pub struct BenchmarkResult {
+    pub label: String,
+    pub frames: u64,
+    pub wall_time: Duration,
+    /// CPU-Zeit des Prozesses (user + system) relativ zur Laufzeit, 100% = ein Kern
+    pub cpu_percent: f64,
+    /// Was die Latenz dieses Pfads misst; die Werte der Pfade sind nicht vergleichbar
+    pub latency_kind: &'static str,
+    pub mean_latency: Duration,
+    pub max_latency: Duration,
+}
+
+impl BenchmarkResult {
+    pub fn achieved_fps(&self) -> f64 {
+        self.frames as f64 / self.wall_time.as_secs_f64().max(f64::EPSILON)
+    }
+}
+
+/// CPU-Zeit des eigenen Prozesses aus /proc/self/stat (utime + stime)
+fn process_cpu_time() -> Option<Duration> {
+    // Felder 14 und 15 in Clock-Ticks, unter Linux praktisch immer 100 Hz
+    const CLOCK_TICKS_PER_SEC: u64 = 100;
+
+    let stat = std::fs::read_to_string("/proc/self/stat").ok()?;
+    // Der Prozessname in Klammern kann Leerzeichen enthalten
+    let after_comm = &stat[stat.rfind(')')? + 2..];
+    let fields: Vec<&str> = after_comm.split_whitespace().collect();
+    let utime: u64 = fields.get(11)?.parse().ok()?;
+    let stime: u64 = fields.get(12)?.parse().ok()?;
+
+    Some(Duration::from_millis(
+        (utime + stime) * 1000 / CLOCK_TICKS_PER_SEC,
+    ))
+}
+
+struct Measurement {
+    start: Instant,
+    cpu_start: Duration,
+    latencies: Vec<Duration>,
+}
+
+impl Measurement {
+    fn start() -> Self {
+        Self {
+            start: Instant::now(),
+            cpu_start: process_cpu_time().unwrap_or_default(),
+            latencies: Vec::new(),
+        }
+    }
+
+    fn finish(self, label: &str, latency_kind: &'static str) -> BenchmarkResult {
+        let wall_time = self.start.elapsed();
+        let cpu_time = process_cpu_time()
+            .unwrap_or_default()
+            .saturating_sub(self.cpu_start);
+
+        let frames = self.latencies.len() as u64;
+        let mean_latency = if frames > 0 {
+            self.latencies.iter().sum::<Duration>() / frames as u32
+        } else {
+            Duration::ZERO
+        };
+        let max_latency = self.latencies.iter().copied().max().unwrap_or_default();
+
+        BenchmarkResult {
+            label: label.to_string(),
+            frames,
+            wall_time,
+            cpu_percent: cpu_time.as_secs_f64() / wall_time.as_secs_f64().max(f64::EPSILON) * 100.0,
+            latency_kind,
+            mean_latency,
+            max_latency,
+        }
+    }
+}
+
+/// Bisheriger Pfad: CameraDevice -> Vec<u8> -> VideoRecorder (appsrc).
+/// Latenz = Zeit von der Ankunft des Frames im Prozess bis `push_buffer` zurückkehrt.
+pub fn bench_appsrc(
+    camera: u32,
+    width: u32,
+    height: u32,
+    fps: f64,
+    duration: Duration,
+    output_dir: &Path,
+) -> Result<BenchmarkResult> {
+    let mut cam = CameraDevice::new_with_resolution(camera, width, height)
+        .map_err(|e| BenchmarkError::CameraError(e.to_string()))?;
+    cam.start()
+        .map_err(|e| BenchmarkError::CameraError(e.to_string()))?;
+
+    let mut recorder = VideoRecorder::new(camera, width as i32, height as i32, fps, output_dir)
+        .map_err(|e| BenchmarkError::RecorderError(e.to_string()))?;
+
+    let mut measurement = Measurement::start();
+
+    while measurement.start.elapsed() < duration {
+        if let Ok(frame) = cam.get_frame() {
+            let arrived = Instant::now();
+            if recorder.write_frame(&frame).is_ok() {
+                measurement.latencies.push(arrived.elapsed());
+            }
+        }
+    }
+
+    let result = measurement.finish("appsrc", "Ankunft bis push_buffer zurückkehrt");
+
+    let _ = cam.stop();
+    recorder
+        .finalize()
+        .map_err(|e| BenchmarkError::RecorderError(e.to_string()))?;
+
+    Ok(result)
+}
+
+/// Direkter Pfad: v4l2src ! tee ! encoder ! mux ! filesink in einer Pipeline.
+/// Latenz = Differenz zwischen Geräte-Zeitstempel (PTS) und Ankunft in der Vorschau.
+pub fn bench_direct(
+    camera: u32,
+    width: u32,
+    height: u32,
+    fps: f64,
+    duration: Duration,
+    output_dir: &Path,
+) -> Result<BenchmarkResult> {
+    let recorder = DirectRecorder::new(camera, width as i32, height as i32, fps, output_dir)
+        .map_err(|e| BenchmarkError::RecorderError(e.to_string()))?;
+
+    let mut measurement = Measurement::start();
+
+    while measurement.start.elapsed() < duration {
+        if let Some((_, latency)) = recorder.get_preview_frame_with_latency() {
+            measurement.latencies.push(latency);
+        }
+    }
+
+    let result = measurement.finish("direct", "Geräte-PTS bis Ankunft in der Vorschau");
+
+    recorder
+        .finalize()
+        .map_err(|e| BenchmarkError::RecorderError(e.to_string()))?;
+
+    Ok(result)
+}
+
+/// Frames, FPS und CPU als Tabelle; die Latenz je Pfad getrennt, da jeder Pfad eine
+/// andere Strecke misst
+pub fn print_results(results: &[BenchmarkResult]) {
+    println!("{:<8} {:>8} {:>10} {:>8}", "Pfad", "Frames", "FPS", "CPU %");
+    for r in results {
+        println!(
+            "{:<8} {:>8} {:>10.2} {:>8.1}",
+            r.label,
+            r.frames,
+            r.achieved_fps(),
+            r.cpu_percent
+        );
+    }
+
+    println!();
+    println!("Latenz (nicht zwischen den Pfaden vergleichbar):");
+    for r in results {
+        println!(
+            "  {:<8} {}: Ø {:.2} ms, max {:.2} ms",
+            r.label,
+            r.latency_kind,
+            r.mean_latency.as_secs_f64() * 1000.0,
+            r.max_latency.as_secs_f64() * 1000.0
+        );
+    }
+}
@@ -135,10 +135,28 @@ impl CameraDevice {
         }
     }
 
+    /// Maximum wait for a frame (GStreamer backend only; nokhwa returns errors on its own)
+    pub fn set_frame_timeout(&mut self, timeout: std::time::Duration) {
+        if let CameraBackend::GStreamer(gst_cam) = &self.backend {
+            gst_cam.lock().unwrap().set_frame_timeout(timeout);
+        }
+    }
+
     pub fn get_index(&self) -> u32 {
         self.index
     }
 
+    /// Resolution the camera actually delivers (may differ from the requested one)
+    pub fn get_resolution(&self) -> (u32, u32) {
+        match &self.backend {
+            CameraBackend::Nokhwa(camera) => {
+                let resolution = camera.lock().unwrap().resolution();
+                (resolution.width(), resolution.height())
+            }
+            CameraBackend::GStreamer(gst_cam) => gst_cam.lock().unwrap().get_resolution(),
+        }
+    }
+
     pub fn stop(&mut self) -> Result<()> {
         match &self.backend {
             CameraBackend::Nokhwa(camera) => {
@@ -0,0 +1,255 @@
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use crate::camera::{self, CameraDevice};
+
+/// Wie nach einem Wiederverbinden weiter aufgenommen wird
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReconnectMode {
+    NewSegment, // Aktuelle Datei abschließen, neue Datei beginnen
+    SameFile,   // In dieselbe Datei weiterschreiben (Lücke steht nur in den Metadaten)
+}
+
+impl std::str::FromStr for ReconnectMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "new-segment" | "segment" => Ok(ReconnectMode::NewSegment),
+            "same-file" | "same" => Ok(ReconnectMode::SameFile),
+            other => Err(format!(
+                "Unbekannter Reconnect-Modus: {} (new-segment oder same-file)",
+                other
+            )),
+        }
+    }
+}
+
+/// Watchdog-Einstellungen für echte Kameras
+#[derive(Debug, Clone, Copy)]
+pub struct ReconnectConfig {
+    /// Ohne Frame länger als das gilt die Kamera als ausgefallen
+    pub frame_timeout: Duration,
+    pub mode: ReconnectMode,
+}
+
+impl Default for ReconnectConfig {
+    fn default() -> Self {
+        Self {
+            frame_timeout: Duration::from_secs(2),
+            mode: ReconnectMode::NewSegment,
+        }
+    }
+}
+
+/// Zeitraum, in dem eine Kamera keine Frames geliefert hat (für die Metadaten)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CameraOutage {
+    pub start: String,
+    /// None, wenn die Kamera bis zum Ende der Aufnahme nicht zurückkam
+    pub end: Option<String>,
+    pub duration_secs: f64,
+    pub reason: String,
+}
+
+/// Seriennummer des USB-Geräts hinter /dev/videoN (aus sysfs)
+pub fn camera_serial(index: u32) -> Option<String> {
+    let device = fs::canonicalize(format!("/sys/class/video4linux/video{}/device", index)).ok()?;
+
+    // `device` zeigt auf das USB-Interface, die Seriennummer steht beim Gerät darüber
+    device
+        .ancestors()
+        .take(2)
+        .find_map(|dir| fs::read_to_string(dir.join("serial")).ok())
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+}
+
+/// Sucht die Kamera mit dieser Seriennummer; nach dem Wiedereinstecken kann sie
+/// eine andere /dev/videoN-Nummer bekommen haben
+pub fn find_camera_by_serial(serial: &str) -> Option<u32> {
+    let entries = fs::read_dir("/sys/class/video4linux").ok()?;
+
+    // Eine Kamera legt oft mehrere Knoten an (Capture + Metadaten), der Capture-Knoten
+    // hat die kleinste Nummer
+    entries
+        .flatten()
+        .filter_map(|entry| {
+            entry
+                .file_name()
+                .to_string_lossy()
+                .strip_prefix("video")
+                .and_then(|n| n.parse::<u32>().ok())
+        })
+        .filter(|&index| camera_serial(index).as_deref() == Some(serial))
+        .min()
+}
+
+pub fn device_present(index: u32) -> bool {
+    Path::new(&format!("/dev/video{}", index)).exists()
+}
+
+/// Ergebnis von `ReconnectingCamera::next`
+pub enum CaptureEvent {
+    Frame(Vec<u8>),
+    /// Einzelner fehlgeschlagener Frame, die Kamera ist noch da
+    Error(String),
+    /// Kamera entfernt oder Frame-Timeout überschritten
+    Lost(String),
+    /// Kamera ist wieder da, mit dem abgeschlossenen Ausfall
+    Reconnected(CameraOutage),
+    /// Kamera ist (noch) nicht wieder aufgetaucht
+    Waiting,
+}
+
+struct OpenOutage {
+    started: Instant,
+    start: DateTime<Local>,
+    reason: String,
+}
+
+impl OpenOutage {
+    fn close(self, end: Option<DateTime<Local>>) -> CameraOutage {
+        CameraOutage {
+            start: self.start.to_rfc3339(),
+            end: end.map(|e| e.to_rfc3339()),
+            duration_secs: self.started.elapsed().as_secs_f64(),
+            reason: self.reason,
+        }
+    }
+}
+
+/// `CameraDevice` mit Watchdog: erkennt Frame-Timeouts und entfernte Geräte und
+/// öffnet dieselbe physische Kamera (per Seriennummer) wieder, sobald sie zurück ist.
+pub struct ReconnectingCamera {
+    cam: Option<CameraDevice>,
+    index: u32,
+    serial: Option<String>,
+    width: u32,
+    height: u32,
+    frame_timeout: Duration,
+    last_frame: Instant,
+    outage: Option<OpenOutage>,
+}
+
+impl ReconnectingCamera {
+    pub fn open(index: u32, width: u32, height: u32, frame_timeout: Duration) -> camera::Result<Self> {
+        let mut cam = CameraDevice::new_with_resolution(index, width, height)?;
+        cam.set_frame_timeout(frame_timeout);
+        cam.start()?;
+
+        let serial = camera_serial(index);
+        match &serial {
+            Some(serial) => eprintln!("Kamera {}: Seriennummer {}", index, serial),
+            None => eprintln!("Kamera {}: keine Seriennummer, Wiederverbinden nur über den Index", index),
+        }
+
+        Ok(Self {
+            cam: Some(cam),
+            index,
+            serial,
+            width,
+            height,
+            frame_timeout,
+            last_frame: Instant::now(),
+            outage: None,
+        })
+    }
+
+    pub fn next(&mut self) -> CaptureEvent {
+        let Some(cam) = &self.cam else {
+            return self.try_reconnect();
+        };
+
+        match cam.get_frame() {
+            Ok(frame) => {
+                self.last_frame = Instant::now();
+                CaptureEvent::Frame(frame)
+            }
+            Err(e) => {
+                if !device_present(self.index) {
+                    self.lose(format!("/dev/video{} entfernt ({})", self.index, e))
+                } else if self.last_frame.elapsed() > self.frame_timeout {
+                    self.lose(format!(
+                        "kein Frame seit {:.1}s ({})",
+                        self.last_frame.elapsed().as_secs_f64(),
+                        e
+                    ))
+                } else {
+                    CaptureEvent::Error(e.to_string())
+                }
+            }
+        }
+    }
+
+    fn lose(&mut self, reason: String) -> CaptureEvent {
+        eprintln!("Kamera {} ausgefallen: {}", self.index, reason);
+
+        if let Some(mut cam) = self.cam.take() {
+            let _ = cam.stop();
+        }
+        self.outage = Some(OpenOutage {
+            started: self.last_frame,
+            start: Local::now() - chrono::Duration::from_std(self.last_frame.elapsed()).unwrap_or_default(),
+            reason: reason.clone(),
+        });
+
+        CaptureEvent::Lost(reason)
+    }
+
+    fn try_reconnect(&mut self) -> CaptureEvent {
+        std::thread::sleep(Duration::from_millis(500));
+
+        let index = match &self.serial {
+            Some(serial) => find_camera_by_serial(serial),
+            None => Some(self.index).filter(|&i| device_present(i)),
+        };
+        let Some(index) = index else {
+            return CaptureEvent::Waiting;
+        };
+
+        let cam = CameraDevice::new_with_resolution(index, self.width, self.height).and_then(|mut cam| {
+            cam.set_frame_timeout(self.frame_timeout);
+            cam.start()?;
+            Ok(cam)
+        });
+
+        match cam {
+            Ok(cam) => {
+                if index != self.index {
+                    eprintln!("Kamera wieder verbunden: /dev/video{} -> /dev/video{}", self.index, index);
+                }
+                self.cam = Some(cam);
+                self.index = index;
+                self.last_frame = Instant::now();
+
+                // Ohne offenen Ausfall (z.B. nach stop()) gibt es nichts zu melden;
+                // der nächste Aufruf liefert wieder Frames
+                let Some(outage) = self.outage.take().map(|o| o.close(Some(Local::now()))) else {
+                    eprintln!("Kamera {} wieder da (kein offener Ausfall)", index);
+                    return CaptureEvent::Waiting;
+                };
+                eprintln!("Kamera {} wieder da nach {:.1}s", index, outage.duration_secs);
+                CaptureEvent::Reconnected(outage)
+            }
+            Err(e) => {
+                eprintln!("Kamera {} noch nicht bereit: {}", index, e);
+                CaptureEvent::Waiting
+            }
+        }
+    }
+
+    /// Schließt einen noch offenen Ausfall ab (Kamera kam bis zum Ende nicht zurück)
+    pub fn end_outage(&mut self) -> Option<CameraOutage> {
+        Some(self.outage.take()?.close(None))
+    }
+
+    pub fn stop(&mut self) {
+        if let Some(mut cam) = self.cam.take() {
+            let _ = cam.stop();
+        }
+    }
+}
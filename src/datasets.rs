@@ -0,0 +1,247 @@
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::image_sequence::ImageSequenceSpec;
+use crate::recording_sessions::{Recording, RecordingSession};
+
+/// Öffentliche Stereo-Datensätze, deren Verzeichnis-Layout erkannt wird
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DatasetKind {
+    KittiOdometry, // sequences/NN/image_{0,1,2,3}/ + times.txt + calib.txt
+    KittiRaw,      // <datum>/<drive>_sync/image_0X/data/ + timestamps.txt, calib_*.txt im Datum
+    Euroc,         // mav0/cam{0,1}/data/ + data.csv + sensor.yaml
+    TumRgbd,       // rgb/ + rgb.txt, depth/ + depth.txt
+}
+
+impl fmt::Display for DatasetKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            DatasetKind::KittiOdometry => "KITTI odometry",
+            DatasetKind::KittiRaw => "KITTI raw",
+            DatasetKind::Euroc => "EuRoC",
+            DatasetKind::TumRgbd => "TUM RGB-D",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Wie tief unterhalb des gewählten Ordners nach Datensätzen gesucht wird
+/// (z.B. `dataset/sequences/00`)
+const MAX_SEARCH_DEPTH: usize = 2;
+
+/// Kalibrier-Dateien der KITTI-raw-Aufnahmen, liegen im Datums-Verzeichnis
+const KITTI_RAW_CALIBRATION: [&str; 3] = [
+    "calib_cam_to_cam.txt",
+    "calib_imu_to_velo.txt",
+    "calib_velo_to_cam.txt",
+];
+
+/// Erkennt einen Datensatz direkt in `dir` und bildet ihn auf eine Session ab
+/// (links/rechts mit den Original-Zeitstempeln, Kalibrier-Dateien in `calibration`)
+pub fn detect_dataset(dir: &Path) -> Option<RecordingSession> {
+    kitti_odometry(dir)
+        .or_else(|| kitti_raw(dir))
+        .or_else(|| euroc(dir))
+        .or_else(|| tum_rgbd(dir))
+}
+
+/// Datensätze in den Unterverzeichnissen von `dir`, nach Pfad sortiert
+pub fn find_datasets(dir: &Path) -> Vec<RecordingSession> {
+    let mut sessions = Vec::new();
+    for child in subdirectories(dir) {
+        collect(&child, 1, &mut sessions);
+    }
+    sessions
+}
+
+fn collect(dir: &Path, depth: usize, sessions: &mut Vec<RecordingSession>) {
+    if let Some(session) = detect_dataset(dir) {
+        sessions.push(session);
+        return;
+    }
+    if depth < MAX_SEARCH_DEPTH {
+        for child in subdirectories(dir) {
+            collect(&child, depth + 1, sessions);
+        }
+    }
+}
+
+fn subdirectories(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut dirs: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.is_dir())
+        .collect();
+    dirs.sort();
+    dirs
+}
+
+fn dir_name(dir: &Path) -> String {
+    dir.file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_string()
+}
+
+/// Bild-Verzeichnis mit Zeitstempel-Datei als Aufnahme einer Kamera
+fn sequence(camera_id: u32, images: PathBuf, timestamps: PathBuf) -> Recording {
+    Recording {
+        path: images,
+        camera_id: Some(camera_id),
+        started_at: None,
+        duration_secs: None,
+        sequence: Some(ImageSequenceSpec {
+            timestamps_file: Some(timestamps),
+            ..ImageSequenceSpec::default()
+        }),
+    }
+}
+
+fn existing(paths: impl IntoIterator<Item = PathBuf>) -> Vec<PathBuf> {
+    paths.into_iter().filter(|path| path.is_file()).collect()
+}
+
+/// Farbpaar (Kamera 2/3), sonst Graustufenpaar (Kamera 0/1)
+fn kitti_pair(has_camera: impl Fn(u32) -> bool) -> Option<(u32, u32)> {
+    [(2, 3), (0, 1)]
+        .into_iter()
+        .find(|&(left, right)| has_camera(left) && has_camera(right))
+}
+
+fn kitti_odometry(dir: &Path) -> Option<RecordingSession> {
+    let times = dir.join("times.txt");
+    if !times.is_file() {
+        return None;
+    }
+    let (left, right) = kitti_pair(|camera| dir.join(format!("image_{}", camera)).is_dir())?;
+
+    Some(RecordingSession {
+        recordings: [left, right]
+            .into_iter()
+            .map(|camera| sequence(camera, dir.join(format!("image_{}", camera)), times.clone()))
+            .collect(),
+        dataset: Some(format!(
+            "{} {} (image_{} + image_{})",
+            DatasetKind::KittiOdometry,
+            dir_name(dir),
+            left,
+            right
+        )),
+        calibration: existing([dir.join("calib.txt")]),
+    })
+}
+
+fn kitti_raw(dir: &Path) -> Option<RecordingSession> {
+    let camera_dir = |camera: u32| dir.join(format!("image_{:02}", camera));
+    let (left, right) = kitti_pair(|camera| {
+        camera_dir(camera).join("data").is_dir() && camera_dir(camera).join("timestamps.txt").is_file()
+    })?;
+
+    // Kalibrierung liegt neben den Drives im Datums-Verzeichnis, manchmal im Drive selbst
+    let calibration = existing(
+        [Some(dir), dir.parent()]
+            .into_iter()
+            .flatten()
+            .flat_map(|base| KITTI_RAW_CALIBRATION.map(|name| base.join(name))),
+    );
+
+    Some(RecordingSession {
+        recordings: [left, right]
+            .into_iter()
+            .map(|camera| {
+                sequence(
+                    camera,
+                    camera_dir(camera).join("data"),
+                    camera_dir(camera).join("timestamps.txt"),
+                )
+            })
+            .collect(),
+        dataset: Some(format!(
+            "{} {} (image_{:02} + image_{:02})",
+            DatasetKind::KittiRaw,
+            dir_name(dir),
+            left,
+            right
+        )),
+        calibration,
+    })
+}
+
+fn euroc(dir: &Path) -> Option<RecordingSession> {
+    // Gewählt werden kann der Datensatz (MH_01_easy) oder sein mav0
+    let (name, mav0) = if dir.join("mav0").is_dir() {
+        (dir_name(dir), dir.join("mav0"))
+    } else if dir_name(dir) == "mav0" {
+        (dir.parent().map(dir_name).unwrap_or_default(), dir.to_path_buf())
+    } else {
+        return None;
+    };
+
+    let cameras: Vec<PathBuf> = ["cam0", "cam1"].map(|cam| mav0.join(cam)).to_vec();
+    if !cameras
+        .iter()
+        .all(|cam| cam.join("data").is_dir() && cam.join("data.csv").is_file())
+    {
+        return None;
+    }
+
+    let calibration = existing(
+        cameras
+            .iter()
+            .map(|cam| cam.join("sensor.yaml"))
+            .chain([mav0.join("body.yaml")]),
+    );
+
+    Some(RecordingSession {
+        recordings: cameras
+            .iter()
+            .zip(0..)
+            .map(|(cam, id)| sequence(id, cam.join("data"), cam.join("data.csv")))
+            .collect(),
+        dataset: Some(format!("{} {} (cam0 + cam1)", DatasetKind::Euroc, name)),
+        calibration,
+    })
+}
+
+/// Links das Farbbild, rechts das Tiefenbild (16-Bit-PNG, als Graustufen angezeigt);
+/// die Zeitstempel beider Ströme sind nicht synchron, gepaart wird über die Startzeiten
+fn tum_rgbd(dir: &Path) -> Option<RecordingSession> {
+    let streams = ["rgb", "depth"];
+    if !streams
+        .iter()
+        .all(|stream| dir.join(stream).is_dir() && dir.join(format!("{}.txt", stream)).is_file())
+    {
+        return None;
+    }
+
+    // TUM liefert keine Kalibrier-Dateien mit, übernommen werden eigene daneben
+    let calibration = fs::read_dir(dir)
+        .map(|entries| {
+            let mut files: Vec<PathBuf> = entries
+                .filter_map(|entry| entry.ok().map(|e| e.path()))
+                .filter(|path| {
+                    let name = dir_name(path).to_lowercase();
+                    path.is_file()
+                        && (name.starts_with("calib")
+                            || name.ends_with(".yaml")
+                            || name.ends_with(".yml"))
+                })
+                .collect();
+            files.sort();
+            files
+        })
+        .unwrap_or_default();
+
+    Some(RecordingSession {
+        recordings: streams
+            .iter()
+            .zip(0..)
+            .map(|(stream, id)| sequence(id, dir.join(stream), dir.join(format!("{}.txt", stream))))
+            .collect(),
+        dataset: Some(format!("{} {} (rgb + depth)", DatasetKind::TumRgbd, dir_name(dir))),
+        calibration,
+    })
+}
@@ -0,0 +1,223 @@
+use chrono::Local;
+use gstreamer as gst;
+use gstreamer::prelude::*;
+use gstreamer_app as gst_app;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::gst_camera::detect_bayer_format;
+use crate::passthrough_recorder::{PREVIEW_HEIGHT, PREVIEW_WIDTH};
+use crate::pipeline_health::{BusWatcher, PipelineIssue};
+use crate::recorder::{
+    RecorderError, RecordingMetadata, Result, unique_output_path, wait_for_eos, write_metadata,
+};
+
+/// Nimmt eine echte Kamera in einer einzigen GStreamer-Pipeline auf.
+///
+/// Im Gegensatz zu `CameraDevice` + `VideoRecorder` werden die Frames nicht über
+/// appsink/appsrc in den Prozess kopiert:
+///
+/// ```text
+/// v4l2src ! [bayer2rgb] ! tee ! queue ! videoconvert ! openh264enc ! h264parse ! mp4mux ! filesink
+///                             tee ! queue ! videoscale ! videoconvert ! appsink (Vorschau)
+/// ```
+///
+/// Die Buffer behalten die Zeitstempel von v4l2src (Treiber-Zeitstempel der Kamera),
+/// es wird nicht neu gestempelt.
+pub struct DirectRecorder {
+    pipeline: gst::Pipeline,
+    preview_sink: gst_app::AppSink,
+    camera_id: u32,
+    start_time: std::time::Instant,
+    fps: f64,
+    width: i32,
+    height: i32,
+    output_path: PathBuf,
+    health: BusWatcher,
+    finalized: bool,
+}
+
+impl DirectRecorder {
+    pub fn new(camera_id: u32, width: i32, height: i32, fps: f64, output_dir: &Path) -> Result<Self> {
+        gst::init().map_err(|e| RecorderError::GStreamerError(e.to_string()))?;
+
+        let framerate = gst::Fraction::approximate_f64(fps)
+            .filter(|rate| rate.numer() > 0)
+            .ok_or_else(|| RecorderError::PipelineError(format!("Ungültige Framerate: {}", fps)))?;
+
+        fs::create_dir_all(output_dir)?;
+
+        let timestamp = Local::now().format("%Y%m%d_%H%M%S");
+        let output_path =
+            unique_output_path(output_dir, &format!("camera_{}__{}", camera_id, timestamp), "mp4");
+
+        // Bayer-Kameras brauchen wie in GstCamera einen Demosaic-Schritt vor dem tee
+        let source = match detect_bayer_format(camera_id) {
+            Some(bayer_format) => format!(
+                "v4l2src device=/dev/video{} ! \
+                 video/x-bayer,format={},width={},height={},framerate={}/{} ! \
+                 bayer2rgb",
+                camera_id,
+                bayer_format,
+                width,
+                height,
+                framerate.numer(),
+                framerate.denom()
+            ),
+            None => format!(
+                "v4l2src device=/dev/video{} ! \
+                 video/x-raw,width={},height={},framerate={}/{}",
+                camera_id,
+                width,
+                height,
+                framerate.numer(),
+                framerate.denom()
+            ),
+        };
+
+        let pipeline_str = format!(
+            "{} ! tee name=t \
+             t. ! queue ! videoconvert ! video/x-raw,format=I420 ! \
+             openh264enc bitrate=2000000 rate-control=bitrate complexity=low multi-thread=0 ! \
+             h264parse ! video/x-h264,stream-format=avc ! mp4mux ! filesink location={} \
+             t. ! queue leaky=downstream max-size-buffers=2 ! videoscale ! videoconvert ! \
+             video/x-raw,format=RGB,width={},height={} ! \
+             appsink name=preview sync=false max-buffers=1 drop=true",
+            source,
+            output_path.to_str().unwrap(),
+            PREVIEW_WIDTH,
+            PREVIEW_HEIGHT
+        );
+
+        eprintln!("Creating direct capture pipeline: {}", pipeline_str);
+
+        let pipeline = gst::parse::launch(&pipeline_str)
+            .map_err(|e| RecorderError::PipelineError(e.to_string()))?
+            .downcast::<gst::Pipeline>()
+            .map_err(|_| RecorderError::PipelineError("Kein Pipeline Element".to_string()))?;
+
+        let preview_sink = pipeline
+            .by_name("preview")
+            .ok_or_else(|| RecorderError::PipelineError("appsink nicht gefunden".to_string()))?
+            .downcast::<gst_app::AppSink>()
+            .map_err(|_| RecorderError::PipelineError("Kein AppSink Element".to_string()))?;
+
+        pipeline
+            .set_state(gst::State::Playing)
+            .map_err(|e| RecorderError::PipelineError(e.to_string()))?;
+
+        let health = BusWatcher::watch(&format!("direct camera {}", camera_id), &pipeline);
+
+        Ok(Self {
+            pipeline,
+            preview_sink,
+            camera_id,
+            start_time: std::time::Instant::now(),
+            fps,
+            width,
+            height,
+            output_path,
+            health,
+            finalized: false,
+        })
+    }
+
+    /// Holt den neuesten Vorschau-Frame (RGB, PREVIEW_WIDTH x PREVIEW_HEIGHT)
+    pub fn get_preview_frame(&self) -> Option<Vec<u8>> {
+        self.get_preview_frame_with_latency().map(|(frame, _)| frame)
+    }
+
+    /// Wie `get_preview_frame`, zusätzlich mit der Latenz zwischen Aufnahme-Zeitstempel
+    /// (PTS) und dem Zeitpunkt, an dem der Frame im Prozess ankommt
+    pub fn get_preview_frame_with_latency(&self) -> Option<(Vec<u8>, Duration)> {
+        let sample = self
+            .preview_sink
+            .try_pull_sample(gst::ClockTime::from_mseconds(100))?;
+        let buffer = sample.buffer()?;
+
+        let latency = match (buffer.pts(), self.pipeline.current_running_time()) {
+            (Some(pts), Some(running_time)) => running_time
+                .checked_sub(pts)
+                .map(|l| Duration::from_nanos(l.nseconds()))
+                .unwrap_or_default(),
+            _ => Duration::ZERO,
+        };
+
+        let map = buffer.map_readable().ok()?;
+        Some((map.as_slice().to_vec(), latency))
+    }
+
+    /// Fehler vom Pipeline-Bus (z.B. Kamera getrennt) als `PipelineFailed`
+    pub fn check_health(&self) -> Result<()> {
+        match self.health.error() {
+            Some(issue) => Err(RecorderError::PipelineFailed(issue.to_string())),
+            None => Ok(()),
+        }
+    }
+
+    /// Warnungen vom Pipeline-Bus seit dem letzten Aufruf
+    pub fn take_warnings(&self) -> Vec<PipelineIssue> {
+        self.health.take_warnings()
+    }
+
+    pub fn finalize(mut self) -> Result<RecordingMetadata> {
+        self.finish()
+    }
+
+    fn finish(&mut self) -> Result<RecordingMetadata> {
+        self.finalized = true;
+        // Der Watcher darf die EOS-Nachricht nicht vom Bus nehmen
+        self.health.stop();
+
+        let duration = self.start_time.elapsed().as_secs_f64();
+
+        self.pipeline.send_event(gst::event::Eos::new());
+
+        wait_for_eos(&self.pipeline, &self.health)?;
+
+        let metadata = RecordingMetadata {
+            camera_id: self.camera_id,
+            timestamp: Local::now().to_rfc3339(),
+            duration_secs: duration,
+            fps: self.fps,
+            width: self.width,
+            height: self.height,
+            filename: self
+                .output_path
+                .file_name()
+                .unwrap()
+                .to_string_lossy()
+                .to_string(),
+            codec: Some("h264".to_string()),
+            queue_policy: None,
+            dropped_frames: None,
+            timing: None,
+            outages: None,
+            timestamps_file: None,
+            first_frame_at: None,
+        };
+
+        write_metadata(&self.output_path, &metadata)?;
+
+        eprintln!(
+            "Direkte Aufnahme gespeichert: {} ({:.2}s)",
+            self.output_path.display(),
+            duration
+        );
+
+        Ok(metadata)
+    }
+}
+
+impl Drop for DirectRecorder {
+    /// Beim Aufräumen ohne `finalize` (z.B. Panic) trotzdem EOS senden
+    fn drop(&mut self) {
+        if !self.finalized {
+            eprintln!("Aufnahme von Kamera {} nicht abgeschlossen, schließe beim Aufräumen ab", self.camera_id);
+            if let Err(e) = self.finish() {
+                eprintln!("Abschließen fehlgeschlagen: {}", e);
+            }
+        }
+    }
+}
@@ -1,13 +1,25 @@
-use crate::camera::CameraDevice;
-use crate::recorder::VideoRecorder;
+use crate::camera_watchdog::{
+    CameraOutage, CaptureEvent, ReconnectConfig, ReconnectMode, ReconnectingCamera,
+};
+use crate::direct_recorder::DirectRecorder;
+use crate::frame_clock::{FrameClock, FrameClockStats, TimingSource};
+use crate::frame_queue::{DropPolicy, FrameQueue};
+use crate::passthrough_recorder::{
+    PREVIEW_HEIGHT, PREVIEW_WIDTH, PassthroughContainer, PassthroughRecorder,
+};
+use crate::pipeline_health::{ErrorPolicy, PipelineIssue};
+use crate::recorder::{RecorderError, RecordingMetadata, VideoRecorder};
+use crate::recording_events::{CameraStats, EventSender, RecordingEvent, event_channel};
+use crate::shutdown::{FinalizeOutcome, ShutdownReport};
+use crate::test_patterns::PatternConfig;
 use crate::virtual_camera::VirtualCamera;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::{
     atomic::{AtomicBool, Ordering},
-    Arc, Mutex,
+    mpsc, Arc, Mutex,
 };
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -20,28 +32,296 @@ pub enum DualRecorderError {
 
 pub type Result<T> = std::result::Result<T, DualRecorderError>;
 
+#[derive(Debug, Clone, Copy)]
 pub enum CameraSource {
-    Single(u32),              // One real camera
-    Dual(u32, u32),           // (camera_0_id, camera_1_id)
-    Virtual,                  // Two virtual test cameras (for internal testing only)
-    Mixed(u32, bool),         // (real_camera_id, is_left) + virtual
+    Single(u32),                 // One real camera
+    Dual(u32, u32),              // (camera_0_id, camera_1_id)
+    Virtual([PatternConfig; 2]), // Two virtual test cameras (left, right test pattern)
+    Mixed(u32, bool),            // (real_camera_id, is_left) + virtual
+}
+
+/// Wie echte Kameras aufgenommen werden
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CaptureMode {
+    AppSrc,                             // CameraDevice -> VideoRecorder (Frames durch den Prozess)
+    Direct,                             // Eine GStreamer-Pipeline pro Kamera bis zur Datei
+    Passthrough(PassthroughContainer),  // MJPEG ohne Neu-Kodierung
+}
+
+/// Aufnahme, die komplett in einer GStreamer-Pipeline läuft
+enum PipelineRecorder {
+    Direct(DirectRecorder),
+    Passthrough(PassthroughRecorder),
+}
+
+impl PipelineRecorder {
+    fn open(cam_id: u32, ctx: &RecordingContext) -> crate::recorder::Result<Self> {
+        let (width, height) = (ctx.width as i32, ctx.height as i32);
+        match ctx.capture_mode {
+            CaptureMode::Passthrough(container) => {
+                PassthroughRecorder::new(cam_id, width, height, ctx.fps, container, &ctx.output_dir)
+                    .map(PipelineRecorder::Passthrough)
+            }
+            _ => DirectRecorder::new(cam_id, width, height, ctx.fps, &ctx.output_dir)
+                .map(PipelineRecorder::Direct),
+        }
+    }
+
+    fn get_preview_frame(&self) -> Option<Vec<u8>> {
+        match self {
+            PipelineRecorder::Direct(r) => r.get_preview_frame(),
+            PipelineRecorder::Passthrough(r) => r.get_preview_frame(),
+        }
+    }
+
+    fn check_health(&self) -> crate::recorder::Result<()> {
+        match self {
+            PipelineRecorder::Direct(r) => r.check_health(),
+            PipelineRecorder::Passthrough(r) => r.check_health(),
+        }
+    }
+
+    fn take_warnings(&self) -> Vec<PipelineIssue> {
+        match self {
+            PipelineRecorder::Direct(r) => r.take_warnings(),
+            PipelineRecorder::Passthrough(r) => r.take_warnings(),
+        }
+    }
+
+    fn finalize(self) -> crate::recorder::Result<RecordingMetadata> {
+        match self {
+            PipelineRecorder::Direct(r) => r.finalize(),
+            PipelineRecorder::Passthrough(r) => r.finalize(),
+        }
+    }
+}
+
+/// Größe und Verhalten der Queue zwischen Capture- und Encoder-Thread
+#[derive(Debug, Clone, Copy)]
+pub struct QueueConfig {
+    pub capacity: usize,
+    pub policy: DropPolicy,
+}
+
+impl Default for QueueConfig {
+    fn default() -> Self {
+        Self {
+            capacity: 8,
+            policy: DropPolicy::Block,
+        }
+    }
+}
+
+/// Wie oft eine ausgefallene Pipeline mit `ErrorPolicy::Restart` neu gestartet wird
+const MAX_RESTARTS: u32 = 3;
+
+type FrameSlot = Arc<Mutex<Option<Vec<u8>>>>;
+/// Frame in der Encoder-Queue
+struct QueuedFrame {
+    /// Steigt nach jedem Wiederverbinden der Kamera
+    segment: u32,
+    captured_at: Instant,
+    data: Vec<u8>,
+}
+
+/// Was der Capture-Thread dem Encoder neben den Frames übergibt
+#[derive(Default)]
+struct StreamReport {
+    timing: Option<FrameClockStats>,
+    outages: Vec<CameraOutage>,
+}
+
+type ReportSlot = Arc<Mutex<StreamReport>>;
+type OutcomeList = Arc<Mutex<Vec<FinalizeOutcome>>>;
+
+/// Gemeinsamer Zustand für einen Aufnahme-Thread
+#[derive(Clone)]
+struct RecordingContext {
+    output_dir: PathBuf,
+    fps: f64,
+    width: u32,
+    height: u32,
+    duration_secs: u64,
+    capture_mode: CaptureMode,
+    queue_config: QueueConfig,
+    error_policy: ErrorPolicy,
+    reconnect: ReconnectConfig,
+    running: Arc<AtomicBool>,
+    left_frames: FrameSlot,
+    right_frames: FrameSlot,
+    events: EventSender,
+    outcomes: OutcomeList,
+}
+
+impl RecordingContext {
+    fn video_recorder(&self, cam_id: u32) -> crate::recorder::Result<VideoRecorder> {
+        VideoRecorder::new(cam_id, self.width as i32, self.height as i32, self.fps, &self.output_dir)
+    }
+}
+
+/// Quelle, die in ihrem eigenen Capture-Thread geöffnet und gelesen wird
+trait CaptureSource {
+    fn grab(&mut self) -> CaptureEvent;
+    fn timing_stats(&self) -> FrameClockStats;
+    /// Noch offener Kamera-Ausfall beim Ende der Aufnahme
+    fn end_outage(&mut self) -> Option<CameraOutage> {
+        None
+    }
+    fn close(&mut self) {}
+}
+
+struct RealCapture {
+    cam: ReconnectingCamera,
+    clock: FrameClock,
+}
+
+impl CaptureSource for RealCapture {
+    fn grab(&mut self) -> CaptureEvent {
+        // get_frame blockiert bis zum nächsten Kamera-Frame, die Kamera gibt den Takt vor
+        let event = self.cam.next();
+        match event {
+            CaptureEvent::Frame(_) => self.clock.wait(),
+            CaptureEvent::Error(_) => thread::sleep(self.clock.interval()),
+            _ => (),
+        }
+        event
+    }
+
+    fn timing_stats(&self) -> FrameClockStats {
+        self.clock.stats()
+    }
+
+    fn end_outage(&mut self) -> Option<CameraOutage> {
+        self.cam.end_outage()
+    }
+
+    fn close(&mut self) {
+        self.cam.stop();
+    }
+}
+
+struct VirtualCapture {
+    vcam: VirtualCamera,
+}
+
+impl CaptureSource for VirtualCapture {
+    fn grab(&mut self) -> CaptureEvent {
+        let event = match self.vcam.get_frame() {
+            Ok(frame) => CaptureEvent::Frame(frame),
+            Err(e) => CaptureEvent::Error(e.to_string()),
+        };
+        self.vcam.wait_for_next_frame();
+        event
+    }
+
+    fn timing_stats(&self) -> FrameClockStats {
+        self.vcam.get_timing_stats()
+    }
+}
+
+type SourceOpener = Box<dyn FnOnce() -> Result<Box<dyn CaptureSource>> + Send>;
+
+fn open_real_camera(cam_id: u32, ctx: &RecordingContext) -> SourceOpener {
+    let fps = ctx.fps;
+    let frame_timeout = ctx.reconnect.frame_timeout;
+    let (width, height) = (ctx.width, ctx.height);
+
+    Box::new(move || {
+        let cam = ReconnectingCamera::open(cam_id, width, height, frame_timeout)
+            .map_err(|e| DualRecorderError::CameraError(format!("Kamera {}: {}", cam_id, e)))?;
+
+        Ok(Box::new(RealCapture {
+            cam,
+            clock: FrameClock::new(fps, TimingSource::Camera),
+        }) as Box<dyn CaptureSource>)
+    })
+}
+
+fn open_virtual_camera(cam_id: u32, pattern: PatternConfig, ctx: &RecordingContext) -> SourceOpener {
+    let (fps, width, height) = (ctx.fps, ctx.width, ctx.height);
+
+    Box::new(move || {
+        Ok(Box::new(VirtualCapture {
+            vcam: VirtualCamera::new(cam_id, width, height, fps).with_pattern(pattern),
+        }) as Box<dyn CaptureSource>)
+    })
 }
 
 pub struct DualCameraRecorder {
     running: Arc<AtomicBool>,
-    left_frames: Arc<Mutex<Option<Vec<u8>>>>,
-    right_frames: Arc<Mutex<Option<Vec<u8>>>>,
+    left_frames: FrameSlot,
+    right_frames: FrameSlot,
+    capture_mode: CaptureMode,
+    queue_config: QueueConfig,
+    error_policy: ErrorPolicy,
+    reconnect: ReconnectConfig,
+    resolution: (u32, u32),
+    /// Größe der Vorschau-Frames der laufenden Aufnahme
+    preview_resolution: (u32, u32),
+    events_tx: EventSender,
+    events_rx: async_channel::Receiver<RecordingEvent>,
+    outcomes: OutcomeList,
+    thread: Option<thread::JoinHandle<()>>,
 }
 
 impl DualCameraRecorder {
     pub fn new() -> Self {
+        let (events_tx, events_rx) = event_channel();
+
         Self {
             running: Arc::new(AtomicBool::new(false)),
             left_frames: Arc::new(Mutex::new(None)),
             right_frames: Arc::new(Mutex::new(None)),
+            capture_mode: CaptureMode::AppSrc,
+            queue_config: QueueConfig::default(),
+            error_policy: ErrorPolicy::Abort,
+            reconnect: ReconnectConfig::default(),
+            resolution: (640, 480),
+            preview_resolution: (640, 480),
+            events_tx,
+            events_rx,
+            outcomes: Arc::new(Mutex::new(Vec::new())),
+            thread: None,
         }
     }
 
+    /// Event-Stream aller Aufnahmen dieses Recorders (Start, Kameras, Stats, Fehler, Ende)
+    pub fn events(&self) -> async_channel::Receiver<RecordingEvent> {
+        self.events_rx.clone()
+    }
+
+    /// Aufnahmepfad für echte Kameras (virtuelle Kameras nutzen immer appsrc)
+    pub fn set_capture_mode(&mut self, mode: CaptureMode) {
+        self.capture_mode = mode;
+    }
+
+    /// Queue-Tiefe und Drop-Policy zwischen Capture und Encoder
+    pub fn set_queue_config(&mut self, config: QueueConfig) {
+        self.queue_config = config;
+    }
+
+    /// Verhalten, wenn eine Aufnahme-Pipeline während der Aufnahme ausfällt
+    pub fn set_error_policy(&mut self, policy: ErrorPolicy) {
+        self.error_policy = policy;
+    }
+
+    /// Frame-Timeout und Verhalten nach dem Wiederverbinden einer getrennten Kamera
+    pub fn set_reconnect_config(&mut self, config: ReconnectConfig) {
+        self.reconnect = config;
+    }
+
+    /// Auflösung, mit der echte und virtuelle Kameras geöffnet und aufgenommen werden
+    /// (Standard 640x480)
+    pub fn set_resolution(&mut self, width: u32, height: u32) {
+        self.resolution = (width, height);
+    }
+
+    /// Größe der Frames von `get_left_frame`/`get_right_frame`: die Aufnahme-Auflösung,
+    /// bei Direct/Passthrough die skalierte Vorschau der Pipeline
+    pub fn preview_resolution(&self) -> (u32, u32) {
+        self.preview_resolution
+    }
+
     pub fn start_recording(
         &mut self,
         source: CameraSource,
@@ -49,79 +329,107 @@ impl DualCameraRecorder {
         fps: f64,
         duration_secs: u64,
     ) -> Result<()> {
-        if self.running.load(Ordering::SeqCst) {
+        if self.is_busy() {
             return Err(DualRecorderError::RecorderError(
                 "Recording already in progress".to_string(),
             ));
         }
 
         self.running.store(true, Ordering::SeqCst);
+        self.outcomes.lock().unwrap().clear();
+
+        let (width, height) = self.resolution;
+        self.preview_resolution = match (source, self.capture_mode) {
+            (
+                CameraSource::Single(_) | CameraSource::Dual(_, _),
+                CaptureMode::Direct | CaptureMode::Passthrough(_),
+            ) => (PREVIEW_WIDTH, PREVIEW_HEIGHT),
+            _ => self.resolution,
+        };
+        let ctx = RecordingContext {
+            output_dir: output_dir.to_path_buf(),
+            fps,
+            width,
+            height,
+            duration_secs,
+            capture_mode: self.capture_mode,
+            queue_config: self.queue_config,
+            error_policy: self.error_policy,
+            reconnect: self.reconnect,
+            running: self.running.clone(),
+            left_frames: self.left_frames.clone(),
+            right_frames: self.right_frames.clone(),
+            events: self.events_tx.clone(),
+            outcomes: self.outcomes.clone(),
+        };
+
+        self.thread = Some(thread::spawn(move || {
+            ctx.events.send(RecordingEvent::Started {
+                source: format!("{:?}", source),
+                output_dir: ctx.output_dir.clone(),
+            });
+
+            // Auch nach einem Panic running zurücksetzen und Finished senden; die
+            // Recorder werden beim Unwinding über Drop abgeschlossen
+            let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                Self::recording_thread(source, &ctx)
+            }))
+            .unwrap_or_else(|_| {
+                Err(DualRecorderError::RecorderError(
+                    "Aufnahme-Thread abgestürzt".to_string(),
+                ))
+            });
 
-        let running = self.running.clone();
-        let left_frames = self.left_frames.clone();
-        let right_frames = self.right_frames.clone();
-        let output_dir = output_dir.to_path_buf();
-
-        thread::spawn(move || {
-            if let Err(e) = Self::recording_thread(
-                source,
-                &output_dir,
-                fps,
-                duration_secs,
-                running,
-                left_frames,
-                right_frames,
-            ) {
+            if let Err(e) = result {
                 eprintln!("Recording error: {}", e);
+                ctx.events.send(RecordingEvent::Error {
+                    camera_id: None,
+                    message: e.to_string(),
+                });
             }
-        });
+            ctx.running.store(false, Ordering::SeqCst);
+            ctx.events.send(RecordingEvent::Finished);
+        }));
 
         Ok(())
     }
 
-    fn recording_thread(
-        source: CameraSource,
-        output_dir: &Path,
-        fps: f64,
-        duration_secs: u64,
-        running: Arc<AtomicBool>,
-        left_frames: Arc<Mutex<Option<Vec<u8>>>>,
-        right_frames: Arc<Mutex<Option<Vec<u8>>>>,
-    ) -> Result<()> {
-        match source {
-            CameraSource::Single(cam_id) => {
-                Self::record_single_camera(
-                    cam_id,
-                    output_dir,
-                    fps,
-                    duration_secs,
-                    running,
-                    left_frames,
+    fn recording_thread(source: CameraSource, ctx: &RecordingContext) -> Result<()> {
+        match (source, ctx.capture_mode) {
+            (CameraSource::Single(cam_id), CaptureMode::Direct | CaptureMode::Passthrough(_)) => {
+                Self::record_pipeline_cameras(&[cam_id], ctx)
+            }
+            (CameraSource::Dual(cam0_id, cam1_id), CaptureMode::Direct | CaptureMode::Passthrough(_)) => {
+                Self::record_pipeline_cameras(&[cam0_id, cam1_id], ctx)
+            }
+            (CameraSource::Single(cam_id), CaptureMode::AppSrc) => {
+                eprintln!("Starte Aufnahme von Kamera {}", cam_id);
+                Self::record_queued_streams(
+                    vec![(cam_id, open_real_camera(cam_id, ctx), ctx.left_frames.clone())],
+                    ctx,
                 )
             }
-            CameraSource::Dual(cam0_id, cam1_id) => {
-                Self::record_real_cameras(
-                    cam0_id,
-                    cam1_id,
-                    output_dir,
-                    fps,
-                    duration_secs,
-                    running,
-                    left_frames,
-                    right_frames,
+            (CameraSource::Dual(cam0_id, cam1_id), CaptureMode::AppSrc) => {
+                eprintln!("Starte Aufnahme von Kameras {} und {}", cam0_id, cam1_id);
+                Self::record_queued_streams(
+                    vec![
+                        (cam0_id, open_real_camera(cam0_id, ctx), ctx.left_frames.clone()),
+                        (cam1_id, open_real_camera(cam1_id, ctx), ctx.right_frames.clone()),
+                    ],
+                    ctx,
                 )
             }
-            CameraSource::Virtual => {
-                Self::record_virtual_cameras(
-                    output_dir,
-                    fps,
-                    duration_secs,
-                    running,
-                    left_frames,
-                    right_frames,
+            (CameraSource::Virtual([left, right]), _) => {
+                eprintln!("Starte Aufnahme von virtuellen Kameras");
+                Self::record_queued_streams(
+                    vec![
+                        (0, open_virtual_camera(0, left, ctx), ctx.left_frames.clone()),
+                        (1, open_virtual_camera(1, right, ctx), ctx.right_frames.clone()),
+                    ],
+                    ctx,
                 )
             }
-            CameraSource::Mixed(_, _) => {
+            (CameraSource::Mixed(_, _), _) => {
                 Err(DualRecorderError::RecorderError(
                     "Mixed mode noch nicht implementiert".to_string(),
                 ))
@@ -129,142 +437,406 @@ impl DualCameraRecorder {
         }
     }
 
-    fn record_single_camera(
-        cam_id: u32,
-        output_dir: &Path,
-        fps: f64,
-        duration_secs: u64,
-        running: Arc<AtomicBool>,
-        frames: Arc<Mutex<Option<Vec<u8>>>>,
+    /// Ein Capture-Thread und ein Encoder-Thread pro Kamera, verbunden über eine
+    /// begrenzte FrameQueue. Ein langsames `push_buffer` bremst so nicht die Aufnahme.
+    fn record_queued_streams(
+        streams: Vec<(u32, SourceOpener, FrameSlot)>,
+        ctx: &RecordingContext,
     ) -> Result<()> {
-        println!("Starte Aufnahme von Kamera {}", cam_id);
-
-        // Öffne Kamera
-        let mut cam = CameraDevice::new(cam_id)
-            .map_err(|e| DualRecorderError::CameraError(format!("Kamera {}: {}", cam_id, e)))?;
-
-        cam.start()
-            .map_err(|e| DualRecorderError::CameraError(e.to_string()))?;
-
-        // Erstelle Recorder
-        let mut recorder = VideoRecorder::new(cam_id, 640, 480, fps, output_dir)
-            .map_err(|e| DualRecorderError::RecorderError(e.to_string()))?;
-
-        let start = std::time::Instant::now();
-        let frame_delay = Duration::from_millis((1000.0 / fps) as u64);
-
-        while running.load(Ordering::SeqCst) && start.elapsed().as_secs() < duration_secs {
-            if let Ok(frame) = cam.get_frame() {
-                *frames.lock().unwrap() = Some(frame.clone());
-                let _ = recorder.write_frame(&frame);
+        let deadline = Instant::now() + Duration::from_secs(ctx.duration_secs);
+        let mut queues = Vec::new();
+        let mut handles = Vec::new();
+
+        let abort = |queues: &[FrameQueue<QueuedFrame>], handles: Vec<thread::JoinHandle<()>>| {
+            ctx.running.store(false, Ordering::SeqCst);
+            for queue in queues {
+                queue.close();
+            }
+            for handle in handles {
+                let _ = handle.join();
             }
+        };
+
+        let mut cam_ids = Vec::new();
+
+        for (cam_id, open, preview) in streams {
+            let queue = FrameQueue::new(ctx.queue_config.capacity, ctx.queue_config.policy);
+            queues.push(queue.clone());
+            cam_ids.push(cam_id);
+            let report: ReportSlot = Arc::new(Mutex::new(StreamReport::default()));
+
+            let capture = match Self::spawn_capture(
+                cam_id,
+                open,
+                queue.clone(),
+                preview,
+                report.clone(),
+                ctx,
+                deadline,
+            ) {
+                Ok(handle) => handle,
+                Err(e) => {
+                    abort(&queues, handles);
+                    return Err(e);
+                }
+            };
+            handles.push(capture);
+
+            let recorder = match ctx.video_recorder(cam_id) {
+                Ok(recorder) => recorder,
+                Err(e) => {
+                    abort(&queues, handles);
+                    return Err(DualRecorderError::RecorderError(e.to_string()));
+                }
+            };
+            handles.push(Self::spawn_encoder(cam_id, recorder, queue, report, ctx));
+        }
 
-            thread::sleep(frame_delay);
+        let start = Instant::now();
+        let mut last_stats = Instant::now();
+
+        while !handles.iter().all(|h| h.is_finished()) {
+            thread::sleep(Duration::from_millis(100));
+
+            if last_stats.elapsed() >= Duration::from_secs(1) {
+                last_stats = Instant::now();
+                ctx.events.send(RecordingEvent::Stats {
+                    elapsed_secs: start.elapsed().as_secs_f64(),
+                    cameras: cam_ids
+                        .iter()
+                        .zip(&queues)
+                        .map(|(&camera_id, queue)| {
+                            let stats = queue.stats();
+                            CameraStats {
+                                camera_id,
+                                frames: stats.captured,
+                                queue_depth: stats.depth,
+                                queue_capacity: stats.capacity,
+                                dropped: stats.dropped,
+                            }
+                        })
+                        .collect(),
+                });
+            }
         }
 
-        let _ = cam.stop();
-        let _ = recorder.finalize();
+        for handle in handles {
+            let _ = handle.join();
+        }
 
-        running.store(false, Ordering::SeqCst);
-        println!("Aufnahme beendet");
+        eprintln!("Aufnahme beendet");
 
         Ok(())
     }
 
-    fn record_real_cameras(
-        cam0_id: u32,
-        cam1_id: u32,
-        output_dir: &Path,
-        fps: f64,
-        duration_secs: u64,
-        running: Arc<AtomicBool>,
-        left_frames: Arc<Mutex<Option<Vec<u8>>>>,
-        right_frames: Arc<Mutex<Option<Vec<u8>>>>,
-    ) -> Result<()> {
-        println!("Starte Aufnahme von Kameras {} und {}", cam0_id, cam1_id);
+    fn spawn_capture(
+        cam_id: u32,
+        open: SourceOpener,
+        queue: FrameQueue<QueuedFrame>,
+        preview: FrameSlot,
+        report: ReportSlot,
+        ctx: &RecordingContext,
+        deadline: Instant,
+    ) -> Result<thread::JoinHandle<()>> {
+        let (ready_tx, ready_rx) = mpsc::channel();
+        let running = ctx.running.clone();
+        let events = ctx.events.clone();
+
+        let handle = thread::spawn(move || {
+            let mut source = match open() {
+                Ok(source) => {
+                    let _ = ready_tx.send(Ok(()));
+                    events.send(RecordingEvent::CameraOpened { camera_id: cam_id });
+                    source
+                }
+                Err(e) => {
+                    let _ = ready_tx.send(Err(e));
+                    queue.close();
+                    return;
+                }
+            };
+
+            let mut failing = false;
+            let mut segment = 0;
+
+            // Eine geschlossene Queue heißt: der Encoder hat den Stream abgebrochen
+            while running.load(Ordering::SeqCst) && Instant::now() < deadline && !queue.is_closed() {
+                match source.grab() {
+                    CaptureEvent::Frame(frame) => {
+                        failing = false;
+                        *preview.lock().unwrap() = Some(frame.clone());
+                        queue.push(QueuedFrame {
+                            segment,
+                            captured_at: Instant::now(),
+                            data: frame,
+                        });
+                    }
+                    CaptureEvent::Error(message) => {
+                        // Nur beim Übergang melden, nicht bei jedem fehlgeschlagenen Frame
+                        if !failing {
+                            events.send(RecordingEvent::Warning {
+                                camera_id: Some(cam_id),
+                                message,
+                            });
+                        }
+                        failing = true;
+                    }
+                    CaptureEvent::Lost(reason) => {
+                        events.send(RecordingEvent::Warning {
+                            camera_id: Some(cam_id),
+                            message: format!("Kamera getrennt: {}", reason),
+                        });
+                    }
+                    CaptureEvent::Reconnected(outage) => {
+                        events.send(RecordingEvent::Warning {
+                            camera_id: Some(cam_id),
+                            message: format!(
+                                "Kamera wieder verbunden nach {:.1}s",
+                                outage.duration_secs
+                            ),
+                        });
+                        // Vor dem ersten Frame des neuen Segments ablegen, der Encoder
+                        // holt den Ausfall beim Segmentwechsel ab
+                        report.lock().unwrap().outages.push(outage);
+                        segment += 1;
+                    }
+                    CaptureEvent::Waiting => (),
+                }
+            }
 
-        let mut cam0 = CameraDevice::new(cam0_id)
-            .map_err(|e| DualRecorderError::CameraError(format!("Kamera {}: {}", cam0_id, e)))?;
-        let mut cam1 = CameraDevice::new(cam1_id)
-            .map_err(|e| DualRecorderError::CameraError(format!("Kamera {}: {}", cam1_id, e)))?;
+            let stats = source.timing_stats();
+            eprintln!("Capture-Timing: {}", stats.summary());
+            {
+                let mut report = report.lock().unwrap();
+                report.timing = Some(stats);
+                report.outages.extend(source.end_outage());
+            }
 
-        cam0.start()
-            .map_err(|e| DualRecorderError::CameraError(e.to_string()))?;
-        cam1.start()
-            .map_err(|e| DualRecorderError::CameraError(e.to_string()))?;
+            source.close();
+            queue.close();
+        });
 
-        let mut recorder0 = VideoRecorder::new(cam0_id, 640, 480, fps, output_dir)
-            .map_err(|e| DualRecorderError::RecorderError(e.to_string()))?;
-        let mut recorder1 = VideoRecorder::new(cam1_id, 640, 480, fps, output_dir)
-            .map_err(|e| DualRecorderError::RecorderError(e.to_string()))?;
+        ready_rx.recv().unwrap_or_else(|_| {
+            Err(DualRecorderError::CameraError(
+                "Capture-Thread vorzeitig beendet".to_string(),
+            ))
+        })?;
 
-        let start = std::time::Instant::now();
-        let frame_delay = Duration::from_millis((1000.0 / fps) as u64);
+        Ok(handle)
+    }
 
-        while running.load(Ordering::SeqCst) && start.elapsed().as_secs() < duration_secs {
-            if let Ok(frame0) = cam0.get_frame() {
-                *left_frames.lock().unwrap() = Some(frame0.clone());
-                let _ = recorder0.write_frame(&frame0);
-            }
+    fn spawn_encoder(
+        cam_id: u32,
+        mut recorder: VideoRecorder,
+        queue: FrameQueue<QueuedFrame>,
+        report: ReportSlot,
+        ctx: &RecordingContext,
+    ) -> thread::JoinHandle<()> {
+        let ctx = ctx.clone();
 
-            if let Ok(frame1) = cam1.get_frame() {
-                *right_frames.lock().unwrap() = Some(frame1.clone());
-                let _ = recorder1.write_frame(&frame1);
+        thread::spawn(move || {
+            let events = &ctx.events;
+            let mut write_failing = false;
+            let mut restarts = 0;
+            let mut current_segment = 0;
+
+            while !queue.is_finished() {
+                if let Some(frame) = queue.pop_timeout(Duration::from_millis(100)) {
+                    let segment = frame.segment;
+                    if segment != current_segment {
+                        current_segment = segment;
+                        // Der Ausfall gehört zur Datei, in der er passiert ist
+                        for outage in std::mem::take(&mut report.lock().unwrap().outages) {
+                            recorder.add_outage(outage);
+                        }
+
+                        if ctx.reconnect.mode == ReconnectMode::NewSegment {
+                            match ctx.video_recorder(cam_id) {
+                                Ok(new_recorder) => {
+                                    let previous = std::mem::replace(&mut recorder, new_recorder);
+                                    Self::report_finalize(cam_id, previous.finalize(), &ctx);
+                                }
+                                Err(e) => events.send(RecordingEvent::Error {
+                                    camera_id: Some(cam_id),
+                                    message: format!("Neues Segment fehlgeschlagen: {}", e),
+                                }),
+                            }
+                        }
+                    }
+
+                    let result = recorder.write_frame_at(&frame.data, frame.captured_at);
+
+                    for warning in recorder.take_warnings() {
+                        events.send(RecordingEvent::Warning {
+                            camera_id: Some(cam_id),
+                            message: warning.to_string(),
+                        });
+                    }
+
+                    match result {
+                        Ok(()) => write_failing = false,
+                        Err(RecorderError::PipelineFailed(message)) => {
+                            eprintln!("Pipeline error (camera {}): {}", cam_id, message);
+
+                            if ctx.error_policy == ErrorPolicy::Restart && restarts < MAX_RESTARTS {
+                                restarts += 1;
+                                match ctx.video_recorder(cam_id) {
+                                    Ok(new_recorder) => {
+                                        events.send(RecordingEvent::Warning {
+                                            camera_id: Some(cam_id),
+                                            message: format!(
+                                                "Pipeline neu gestartet ({}/{}) nach Fehler: {}",
+                                                restarts, MAX_RESTARTS, message
+                                            ),
+                                        });
+                                        // Die ausgefallene Datei so gut wie möglich abschließen
+                                        let failed = std::mem::replace(&mut recorder, new_recorder);
+                                        Self::report_finalize(cam_id, failed.finalize(), &ctx);
+                                        continue;
+                                    }
+                                    Err(e) => {
+                                        events.send(RecordingEvent::Error {
+                                            camera_id: Some(cam_id),
+                                            message: format!("Neustart fehlgeschlagen: {}", e),
+                                        });
+                                    }
+                                }
+                            }
+
+                            events.send(RecordingEvent::Error {
+                                camera_id: Some(cam_id),
+                                message: format!("Stream abgebrochen: {}", message),
+                            });
+                            // Capture-Thread beenden; verbleibende Frames werden verworfen
+                            queue.close();
+                            break;
+                        }
+                        Err(e) => {
+                            eprintln!("Write error: {}", e);
+                            if !write_failing {
+                                events.send(RecordingEvent::Warning {
+                                    camera_id: Some(cam_id),
+                                    message: e.to_string(),
+                                });
+                            }
+                            write_failing = true;
+                        }
+                    }
+                }
             }
 
-            thread::sleep(frame_delay);
-        }
-
-        let _ = cam0.stop();
-        let _ = cam1.stop();
-        let _ = recorder0.finalize();
-        let _ = recorder1.finalize();
-
-        running.store(false, Ordering::SeqCst);
-        println!("Aufnahme beendet");
-
-        Ok(())
+            recorder.set_queue_stats(queue.policy(), &queue.stats());
+            {
+                let mut report = report.lock().unwrap();
+                if let Some(stats) = report.timing {
+                    recorder.set_timing_stats(stats);
+                }
+                for outage in std::mem::take(&mut report.outages) {
+                    recorder.add_outage(outage);
+                }
+            }
+            Self::report_finalize(cam_id, recorder.finalize(), &ctx);
+        })
     }
 
-    fn record_virtual_cameras(
-        output_dir: &Path,
-        fps: f64,
-        duration_secs: u64,
-        running: Arc<AtomicBool>,
-        left_frames: Arc<Mutex<Option<Vec<u8>>>>,
-        right_frames: Arc<Mutex<Option<Vec<u8>>>>,
-    ) -> Result<()> {
-        println!("Starte Aufnahme von virtuellen Kameras");
+    fn report_finalize(
+        cam_id: u32,
+        result: crate::recorder::Result<RecordingMetadata>,
+        ctx: &RecordingContext,
+    ) {
+        let outcome = match result {
+            Ok(metadata) => {
+                let metadata_path = ctx.output_dir.join(&metadata.filename).with_extension("json");
+                ctx.events.send(RecordingEvent::Finalized {
+                    camera_id: cam_id,
+                    metadata_path: metadata_path.clone(),
+                });
+                Ok(metadata_path)
+            }
+            Err(e) => {
+                eprintln!("Finalize error: {}", e);
+                ctx.events.send(RecordingEvent::Error {
+                    camera_id: Some(cam_id),
+                    message: format!("Finalize fehlgeschlagen: {}", e),
+                });
+                Err(e.to_string())
+            }
+        };
 
-        let vcam0 = VirtualCamera::new(0, 640, 480, fps as u32);
-        let vcam1 = VirtualCamera::new(1, 640, 480, fps as u32);
+        ctx.outcomes.lock().unwrap().push(FinalizeOutcome {
+            camera_id: cam_id,
+            result: outcome,
+        });
+    }
 
-        let mut recorder0 = VideoRecorder::new(0, 640, 480, fps, output_dir)
-            .map_err(|e| DualRecorderError::RecorderError(e.to_string()))?;
-        let mut recorder1 = VideoRecorder::new(1, 640, 480, fps, output_dir)
-            .map_err(|e| DualRecorderError::RecorderError(e.to_string()))?;
+    fn record_pipeline_cameras(cam_ids: &[u32], ctx: &RecordingContext) -> Result<()> {
+        eprintln!("Starte Aufnahme ({:?}) von Kamera(s) {:?}", ctx.capture_mode, cam_ids);
+
+        let slots = [&ctx.left_frames, &ctx.right_frames];
+        let mut streams = Vec::new();
+
+        for (&cam_id, slot) in cam_ids.iter().zip(slots) {
+            match PipelineRecorder::open(cam_id, ctx) {
+                Ok(recorder) => {
+                    ctx.events.send(RecordingEvent::CameraOpened { camera_id: cam_id });
+                    streams.push(PipelineStream {
+                        cam_id,
+                        slot,
+                        recorder: Some(recorder),
+                        preview_count: 0,
+                        restarts: 0,
+                    });
+                }
+                Err(e) => {
+                    // Bereits geöffnete Pipelines sauber abschließen
+                    for stream in streams {
+                        stream.finalize(ctx);
+                    }
+                    return Err(DualRecorderError::RecorderError(format!("Kamera {}: {}", cam_id, e)));
+                }
+            }
+        }
 
         let start = std::time::Instant::now();
-
-        while running.load(Ordering::SeqCst) && start.elapsed().as_secs() < duration_secs {
-            if let Ok(frame0) = vcam0.get_frame() {
-                *left_frames.lock().unwrap() = Some(frame0.clone());
-                let _ = recorder0.write_frame(&frame0);
+        let mut last_stats = Instant::now();
+
+        while ctx.running.load(Ordering::SeqCst)
+            && start.elapsed().as_secs() < ctx.duration_secs
+            && streams.iter().any(|s| s.recorder.is_some())
+        {
+            // Die Aufnahme läuft komplett in GStreamer, hier wird nur die Vorschau abgeholt
+            for stream in streams.iter_mut() {
+                stream.check_health(ctx);
+
+                if let Some(frame) = stream.recorder.as_ref().and_then(|r| r.get_preview_frame()) {
+                    *stream.slot.lock().unwrap() = Some(frame);
+                    stream.preview_count += 1;
+                }
             }
 
-            if let Ok(frame1) = vcam1.get_frame() {
-                *right_frames.lock().unwrap() = Some(frame1.clone());
-                let _ = recorder1.write_frame(&frame1);
+            if last_stats.elapsed() >= Duration::from_secs(1) {
+                last_stats = Instant::now();
+                ctx.events.send(RecordingEvent::Stats {
+                    elapsed_secs: start.elapsed().as_secs_f64(),
+                    cameras: streams
+                        .iter()
+                        .map(|stream| CameraStats {
+                            camera_id: stream.cam_id,
+                            frames: stream.preview_count,
+                            queue_depth: 0,
+                            queue_capacity: 0,
+                            dropped: 0,
+                        })
+                        .collect(),
+                });
             }
-
-            vcam0.wait_for_next_frame();
         }
 
-        let _ = recorder0.finalize();
-        let _ = recorder1.finalize();
+        for stream in streams {
+            stream.finalize(ctx);
+        }
 
-        running.store(false, Ordering::SeqCst);
-        println!("Aufnahme beendet");
+        eprintln!("Aufnahme beendet");
 
         Ok(())
     }
@@ -273,10 +845,41 @@ impl DualCameraRecorder {
         self.running.store(false, Ordering::SeqCst);
     }
 
+    /// Stoppt die Aufnahme und wartet höchstens `timeout`, bis alle Dateien
+    /// abgeschlossen sind (EOS, Metadaten)
+    pub fn shutdown(&mut self, timeout: Duration) -> ShutdownReport {
+        self.stop_recording();
+
+        // running ist sofort false, fertig ist die Aufnahme erst mit dem Thread
+        let deadline = Instant::now() + timeout;
+        let mut timed_out = false;
+        if let Some(handle) = self.thread.take() {
+            while !handle.is_finished() && Instant::now() < deadline {
+                thread::sleep(Duration::from_millis(50));
+            }
+            if handle.is_finished() {
+                let _ = handle.join();
+            } else {
+                timed_out = true;
+            }
+        }
+
+        ShutdownReport {
+            outcomes: self.outcomes.lock().unwrap().clone(),
+            timed_out,
+            timeout,
+        }
+    }
+
     pub fn is_recording(&self) -> bool {
         self.running.load(Ordering::SeqCst)
     }
 
+    /// Aufnahme läuft oder wird nach `stop_recording` noch abgeschlossen
+    pub fn is_busy(&self) -> bool {
+        self.is_recording() || self.thread.as_ref().is_some_and(|h| !h.is_finished())
+    }
+
     pub fn get_left_frame(&self) -> Option<Vec<u8>> {
         self.left_frames.lock().unwrap().clone()
     }
@@ -285,3 +888,72 @@ impl DualCameraRecorder {
         self.right_frames.lock().unwrap().clone()
     }
 }
+
+/// Eine Kamera im Direct-/Passthrough-Modus; `recorder` ist None nach Abbruch
+struct PipelineStream<'a> {
+    cam_id: u32,
+    slot: &'a FrameSlot,
+    recorder: Option<PipelineRecorder>,
+    preview_count: u64,
+    restarts: u32,
+}
+
+impl PipelineStream<'_> {
+    /// Meldet Bus-Warnungen und reagiert per `ErrorPolicy` auf einen Pipeline-Ausfall
+    fn check_health(&mut self, ctx: &RecordingContext) {
+        let Some(recorder) = &self.recorder else {
+            return;
+        };
+
+        for warning in recorder.take_warnings() {
+            ctx.events.send(RecordingEvent::Warning {
+                camera_id: Some(self.cam_id),
+                message: warning.to_string(),
+            });
+        }
+
+        let Err(e) = recorder.check_health() else {
+            return;
+        };
+        eprintln!("Pipeline error (camera {}): {}", self.cam_id, e);
+
+        // Die ausgefallene Datei so gut wie möglich abschließen
+        self.finalize_current(ctx);
+
+        if ctx.error_policy == ErrorPolicy::Restart && self.restarts < MAX_RESTARTS {
+            self.restarts += 1;
+            match PipelineRecorder::open(self.cam_id, ctx) {
+                Ok(recorder) => {
+                    ctx.events.send(RecordingEvent::Warning {
+                        camera_id: Some(self.cam_id),
+                        message: format!(
+                            "Pipeline neu gestartet ({}/{}) nach Fehler: {}",
+                            self.restarts, MAX_RESTARTS, e
+                        ),
+                    });
+                    self.recorder = Some(recorder);
+                    return;
+                }
+                Err(restart_err) => ctx.events.send(RecordingEvent::Error {
+                    camera_id: Some(self.cam_id),
+                    message: format!("Neustart fehlgeschlagen: {}", restart_err),
+                }),
+            }
+        }
+
+        ctx.events.send(RecordingEvent::Error {
+            camera_id: Some(self.cam_id),
+            message: format!("Stream abgebrochen: {}", e),
+        });
+    }
+
+    fn finalize_current(&mut self, ctx: &RecordingContext) {
+        if let Some(recorder) = self.recorder.take() {
+            DualCameraRecorder::report_finalize(self.cam_id, recorder.finalize(), ctx);
+        }
+    }
+
+    fn finalize(mut self, ctx: &RecordingContext) {
+        self.finalize_current(ctx);
+    }
+}
@@ -0,0 +1,513 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt;
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use thiserror::Error;
+
+use crate::frame_source::{self, FrameSource, SourceFrame};
+use crate::playback_camera::Pacing;
+use crate::seeded_rng::SeededRng;
+
+#[derive(Error, Debug)]
+pub enum FaultInjectionError {
+    #[error("IO Fehler: {0}")]
+    IoError(#[from] std::io::Error),
+    #[error("Ungültige Fehler-Konfiguration: {0}")]
+    ParseError(String),
+}
+
+pub type Result<T> = std::result::Result<T, FaultInjectionError>;
+
+/// Verteilung der zusätzlichen Latenz pro Frame
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default)]
+#[serde(tag = "distribution", rename_all = "kebab-case")]
+pub enum Jitter {
+    #[default]
+    None,
+    Uniform { max_ms: f64 },     // Gleichverteilt in 0..max_ms
+    Normal { std_ms: f64 },      // Betrag einer Normalverteilung um 0
+    Exponential { mean_ms: f64 }, // Seltene lange Ausreißer
+}
+
+impl Jitter {
+    /// Latenz in ms aus einer gleichverteilten Zufallszahl in [0, 1) und einer
+    /// standardnormalverteilten
+    fn sample(&self, uniform: f64, normal: f64) -> f64 {
+        match *self {
+            Jitter::None => 0.0,
+            Jitter::Uniform { max_ms } => uniform * max_ms,
+            Jitter::Normal { std_ms } => normal.abs() * std_ms,
+            Jitter::Exponential { mean_ms } => -(1.0 - uniform).ln() * mean_ms,
+        }
+    }
+}
+
+/// Fehlerbild einer simulierten Kamera; Wahrscheinlichkeiten gelten pro Frame der Quelle
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FaultConfig {
+    pub drop_probability: f64,
+    /// Beginn eines Hängers: Frames stauen sich und kommen danach als Schwall
+    pub stall_probability: f64,
+    pub stall_ms: f64,
+    pub jitter: Jitter,
+    /// Feste zusätzliche Latenz
+    pub delay_ms: f64,
+    pub black_probability: f64,
+    /// Beginn eines eingefrorenen Bilds, das `freeze_frames` Frames lang stehen bleibt
+    pub freeze_probability: f64,
+    pub freeze_frames: u32,
+    /// Kamera verschwindet für `disconnect_ms` und meldet sich dann wieder
+    pub disconnect_probability: f64,
+    pub disconnect_ms: f64,
+}
+
+impl Default for FaultConfig {
+    fn default() -> Self {
+        Self {
+            drop_probability: 0.0,
+            stall_probability: 0.0,
+            stall_ms: 500.0,
+            jitter: Jitter::None,
+            delay_ms: 0.0,
+            black_probability: 0.0,
+            freeze_probability: 0.0,
+            freeze_frames: 15,
+            disconnect_probability: 0.0,
+            disconnect_ms: 2000.0,
+        }
+    }
+}
+
+impl FaultConfig {
+    /// Liest eine Konfiguration als JSON
+    pub fn load(path: &Path) -> Result<Self> {
+        let json = fs::read_to_string(path)?;
+        let config: Self = serde_json::from_str(&json)
+            .map_err(|e| FaultInjectionError::ParseError(format!("{}: {}", path.display(), e)))?;
+        config.validate().map_err(FaultInjectionError::ParseError)?;
+        Ok(config)
+    }
+
+    pub fn validate(&self) -> std::result::Result<(), String> {
+        let probabilities = [
+            ("drop_probability", self.drop_probability),
+            ("stall_probability", self.stall_probability),
+            ("black_probability", self.black_probability),
+            ("freeze_probability", self.freeze_probability),
+            ("disconnect_probability", self.disconnect_probability),
+        ];
+        for (name, value) in probabilities {
+            if !(0.0..=1.0).contains(&value) {
+                return Err(format!("{} = {} außerhalb von 0..1", name, value));
+            }
+        }
+        let durations = [
+            ("stall_ms", self.stall_ms),
+            ("delay_ms", self.delay_ms),
+            ("disconnect_ms", self.disconnect_ms),
+        ];
+        for (name, value) in durations {
+            if value < 0.0 {
+                return Err(format!("{} = {} ist negativ", name, value));
+            }
+        }
+        let jitter = match self.jitter {
+            Jitter::None => None,
+            Jitter::Uniform { max_ms } => Some(("jitter.max_ms", max_ms)),
+            Jitter::Normal { std_ms } => Some(("jitter.std_ms", std_ms)),
+            Jitter::Exponential { mean_ms } => Some(("jitter.mean_ms", mean_ms)),
+        };
+        if let Some((name, value)) = jitter
+            && value < 0.0
+        {
+            return Err(format!("{} = {} ist negativ", name, value));
+        }
+        Ok(())
+    }
+}
+
+/// Fehler beider Seiten der Stereo-Simulation (`faults` in `simulation.json`)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct StereoFaultConfig {
+    /// Gleicher Seed und gleiche Frames ergeben dieselben Fehler
+    pub seed: u64,
+    pub left: FaultConfig,
+    pub right: FaultConfig,
+    /// Rechte Kamera liefert so viel später (negativ: früher) als die linke
+    pub desync_ms: f64,
+}
+
+impl StereoFaultConfig {
+    pub fn validate(&self) -> std::result::Result<(), String> {
+        self.left.validate().map_err(|e| format!("left: {}", e))?;
+        self.right.validate().map_err(|e| format!("right: {}", e))
+    }
+
+    /// Injektoren für links und rechts, der Versatz geht auf die verspätete Seite
+    pub fn injectors(&self, log: &FaultLog) -> [FaultInjector; 2] {
+        let mut left = self.left;
+        let mut right = self.right;
+        if self.desync_ms >= 0.0 {
+            right.delay_ms += self.desync_ms;
+        } else {
+            left.delay_ms -= self.desync_ms;
+        }
+        [
+            FaultInjector::new("left", left, self.seed, log.clone()),
+            FaultInjector::new("right", right, self.seed.wrapping_add(1), log.clone()),
+        ]
+    }
+}
+
+/// Art eines injizierten Fehlers
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "fault", rename_all = "kebab-case")]
+pub enum FaultKind {
+    Dropped,
+    StallStarted { ms: f64 },
+    Delayed { ms: f64 },
+    Black,
+    FreezeStarted { frames: u32 },
+    Disconnected { ms: f64 },
+    Reconnected,
+}
+
+impl FaultKind {
+    fn name(&self) -> &'static str {
+        match self {
+            FaultKind::Dropped => "dropped",
+            FaultKind::StallStarted { .. } => "stall",
+            FaultKind::Delayed { .. } => "delayed",
+            FaultKind::Black => "black",
+            FaultKind::FreezeStarted { .. } => "freeze",
+            FaultKind::Disconnected { .. } => "disconnect",
+            FaultKind::Reconnected => "reconnect",
+        }
+    }
+}
+
+impl fmt::Display for FaultKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FaultKind::Dropped => write!(f, "dropped"),
+            FaultKind::StallStarted { ms } => write!(f, "stall {:.0} ms", ms),
+            FaultKind::Delayed { ms } => write!(f, "delayed {:.1} ms", ms),
+            FaultKind::Black => write!(f, "black frame"),
+            FaultKind::FreezeStarted { frames } => write!(f, "frozen for {} frames", frames),
+            FaultKind::Disconnected { ms } => write!(f, "disconnected for {:.0} ms", ms),
+            FaultKind::Reconnected => write!(f, "reconnected"),
+        }
+    }
+}
+
+/// Eintrag im Fehler-Protokoll
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FaultEvent {
+    pub camera: String,
+    /// Frame der Quelle (ab 0 seit Start des Injektors)
+    pub frame: u64,
+    /// PTS dieses Frames
+    pub pts_ms: f64,
+    /// Zeit seit Start des Injektors
+    pub at_ms: f64,
+    #[serde(flatten)]
+    pub kind: FaultKind,
+}
+
+impl fmt::Display for FaultEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} frame {} (pts {:.0} ms): {}", self.camera, self.frame, self.pts_ms, self.kind)
+    }
+}
+
+/// Gemeinsames Protokoll aller Injektoren, z.B. für Assertions in Tests
+#[derive(Debug, Clone, Default)]
+pub struct FaultLog {
+    inner: Arc<Mutex<FaultLogInner>>,
+}
+
+#[derive(Debug, Default)]
+struct FaultLogInner {
+    events: Vec<FaultEvent>,
+    reported: usize,
+}
+
+impl FaultLog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn record(&self, event: FaultEvent) {
+        self.inner.lock().unwrap().events.push(event);
+    }
+
+    /// Anzahl je Fehlerart, z.B. `12 dropped, 1 stall`
+    pub fn summary(&self) -> String {
+        let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+        for event in self.inner.lock().unwrap().events.iter() {
+            *counts.entry(event.kind.name()).or_default() += 1;
+        }
+        if counts.is_empty() {
+            return "no faults".to_string();
+        }
+        counts
+            .iter()
+            .map(|(name, count)| format!("{} {}", count, name))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Einträge, die seit dem letzten Aufruf hinzugekommen sind (für laufende Anzeigen)
+    pub fn take_unreported(&self) -> Vec<FaultEvent> {
+        let mut inner = self.inner.lock().unwrap();
+        let new = inner.events[inner.reported..].to_vec();
+        inner.reported = inner.events.len();
+        new
+    }
+
+    /// Schreibt das Protokoll als JSON Lines (ein Fehler pro Zeile)
+    pub fn write_jsonl(&self, path: &Path) -> Result<()> {
+        let mut file = fs::File::create(path)?;
+        for event in self.inner.lock().unwrap().events.iter() {
+            let line = serde_json::to_string(event)
+                .map_err(|e| FaultInjectionError::ParseError(e.to_string()))?;
+            writeln!(file, "{}", line)?;
+        }
+        Ok(())
+    }
+}
+
+/// Zufallszahlen eines Frames; immer gleich viele, damit die Fehler nur vom Seed und
+/// der Frame-Nummer abhängen, nicht von Zustand oder Uhrzeit
+struct FrameDraws {
+    disconnect: f64,
+    stall: f64,
+    drop: f64,
+    black: f64,
+    freeze: f64,
+    jitter_uniform: f64,
+    jitter_normal: f64,
+}
+
+/// Verfälscht den Frame-Strom einer Kamera nach `FaultConfig`.
+///
+/// Frames gehen mit `push` hinein und kommen mit `poll` heraus, sobald ihre (verzögerte)
+/// Auslieferungszeit erreicht ist; so funktioniert er blockierend und im GUI-Timer.
+pub struct FaultInjector {
+    camera: String,
+    config: FaultConfig,
+    rng: SeededRng,
+    log: FaultLog,
+    started: Instant,
+    frame: u64,
+    stalled_until: Option<Instant>,
+    disconnected_until: Option<Instant>,
+    frozen: Option<(Vec<u8>, u32)>,
+    last_input: Option<Vec<u8>>,
+    pending: VecDeque<(Instant, SourceFrame)>,
+}
+
+impl FaultInjector {
+    pub fn new(camera: &str, config: FaultConfig, seed: u64, log: FaultLog) -> Self {
+        Self {
+            camera: camera.to_string(),
+            config,
+            rng: SeededRng::new(seed),
+            log,
+            started: Instant::now(),
+            frame: 0,
+            stalled_until: None,
+            disconnected_until: None,
+            frozen: None,
+            last_input: None,
+            pending: VecDeque::new(),
+        }
+    }
+
+    /// Nimmt einen Frame der Quelle entgegen; `false`, wenn er verloren geht
+    pub fn push(&mut self, mut frame: SourceFrame, now: Instant) -> bool {
+        let draws = FrameDraws {
+            disconnect: self.rng.uniform(),
+            stall: self.rng.uniform(),
+            drop: self.rng.uniform(),
+            black: self.rng.uniform(),
+            freeze: self.rng.uniform(),
+            jitter_uniform: self.rng.uniform(),
+            jitter_normal: self.rng.normal(),
+        };
+        let index = self.frame;
+        self.frame += 1;
+        let pts = frame.pts;
+        let config = self.config;
+
+        if let Some(until) = self.disconnected_until {
+            if now < until {
+                return false;
+            }
+            self.disconnected_until = None;
+            self.record(index, pts, now, FaultKind::Reconnected);
+        }
+        if draws.disconnect < config.disconnect_probability {
+            self.disconnected_until = Some(now + Duration::from_secs_f64(config.disconnect_ms / 1000.0));
+            // Was noch unterwegs war, geht mit der Verbindung verloren
+            self.pending.clear();
+            self.frozen = None;
+            self.record(index, pts, now, FaultKind::Disconnected { ms: config.disconnect_ms });
+            return false;
+        }
+
+        if self.stalled_until.is_none_or(|until| now >= until)
+            && draws.stall < config.stall_probability
+        {
+            self.stalled_until = Some(now + Duration::from_secs_f64(config.stall_ms / 1000.0));
+            self.record(index, pts, now, FaultKind::StallStarted { ms: config.stall_ms });
+        }
+
+        if draws.drop < config.drop_probability {
+            self.record(index, pts, now, FaultKind::Dropped);
+            return false;
+        }
+
+        // Eingefroren zählt vor schwarz: die Kamera liefert weiter dasselbe Bild
+        if self.frozen.is_none() && draws.freeze < config.freeze_probability && config.freeze_frames > 0 {
+            if let Some(last) = self.last_input.take() {
+                self.frozen = Some((last, config.freeze_frames));
+                self.record(index, pts, now, FaultKind::FreezeStarted { frames: config.freeze_frames });
+            }
+        }
+        self.last_input = Some(frame.data.clone());
+        // Ändert die Quelle die Bildgröße, endet das Einfrieren
+        if self
+            .frozen
+            .as_ref()
+            .is_some_and(|(data, _)| data.len() != frame.data.len())
+        {
+            self.frozen = None;
+        }
+        if let Some((data, remaining)) = &mut self.frozen {
+            frame.data.copy_from_slice(data);
+            *remaining -= 1;
+            if *remaining == 0 {
+                self.frozen = None;
+            }
+        } else if draws.black < config.black_probability {
+            frame.data.fill(0);
+            self.record(index, pts, now, FaultKind::Black);
+        }
+
+        let jitter_ms = config.jitter.sample(draws.jitter_uniform, draws.jitter_normal);
+        if jitter_ms > 0.0 {
+            self.record(index, pts, now, FaultKind::Delayed { ms: jitter_ms });
+        }
+        let mut due = now + Duration::from_secs_f64((config.delay_ms + jitter_ms).max(0.0) / 1000.0);
+        if let Some(until) = self.stalled_until {
+            due = due.max(until);
+        }
+        // Reihenfolge bleibt erhalten wie bei einer echten Übertragung
+        if let Some((last_due, _)) = self.pending.back() {
+            due = due.max(*last_due);
+        }
+        self.pending.push_back((due, frame));
+        true
+    }
+
+    /// Verwirft den zuletzt angenommenen Frame wieder, z.B. weil im Lock-Step der
+    /// Partner verloren ging
+    pub fn drop_last(&mut self, now: Instant) {
+        if let Some((_, frame)) = self.pending.pop_back() {
+            self.record(self.frame.saturating_sub(1), frame.pts, now, FaultKind::Dropped);
+        }
+    }
+
+    /// Nächster fälliger Frame
+    pub fn poll(&mut self, now: Instant) -> Option<SourceFrame> {
+        match self.pending.front() {
+            Some((due, _)) if *due <= now => self.pending.pop_front().map(|(_, frame)| frame),
+            _ => None,
+        }
+    }
+
+    /// `push` und `poll` in einem Schritt, für nicht blockierende Quellen
+    pub fn process(&mut self, frame: Option<SourceFrame>, now: Instant) -> Option<SourceFrame> {
+        if let Some(frame) = frame {
+            self.push(frame, now);
+        }
+        self.poll(now)
+    }
+
+    /// Auslieferungszeit des nächsten wartenden Frames
+    pub fn next_due(&self) -> Option<Instant> {
+        self.pending.front().map(|(due, _)| *due)
+    }
+
+    /// Während eines Hängers oder einer Trennung muss die Quelle weiter gelesen werden
+    pub fn is_holding(&self, now: Instant) -> bool {
+        self.stalled_until.is_some_and(|until| now < until)
+            || self.disconnected_until.is_some_and(|until| now < until)
+    }
+
+    fn record(&self, frame: u64, pts: Duration, now: Instant, kind: FaultKind) {
+        self.log.record(FaultEvent {
+            camera: self.camera.clone(),
+            frame,
+            pts_ms: pts.as_secs_f64() * 1000.0,
+            at_ms: now.saturating_duration_since(self.started).as_secs_f64() * 1000.0,
+            kind,
+        });
+    }
+}
+
+/// Beliebige `FrameSource` mit Fehler-Injektion (für `serve` und `stream`)
+pub struct FaultySource {
+    inner: Box<dyn FrameSource>,
+    injector: FaultInjector,
+}
+
+impl FaultySource {
+    pub fn new(inner: Box<dyn FrameSource>, config: FaultConfig, seed: u64, log: FaultLog) -> Self {
+        let injector = FaultInjector::new(&inner.name(), config, seed, log);
+        Self { inner, injector }
+    }
+}
+
+impl FrameSource for FaultySource {
+    fn name(&self) -> String {
+        self.inner.name()
+    }
+
+    fn resolution(&self) -> (u32, u32) {
+        self.inner.resolution()
+    }
+
+    fn fps(&self) -> f64 {
+        self.inner.fps()
+    }
+
+    fn next_frame(&mut self) -> frame_source::Result<SourceFrame> {
+        loop {
+            let now = Instant::now();
+            if let Some(frame) = self.injector.poll(now) {
+                return Ok(frame);
+            }
+            // Nur verzögert: warten statt die Quelle weiterzulesen
+            if let Some(due) = self.injector.next_due() {
+                if !self.injector.is_holding(now) {
+                    std::thread::sleep(due.saturating_duration_since(now));
+                    continue;
+                }
+            }
+            let frame = self.inner.next_frame()?;
+            self.injector.push(frame, Instant::now());
+        }
+    }
+
+    fn set_pacing(&mut self, pacing: Pacing) {
+        self.inner.set_pacing(pacing);
+    }
+}
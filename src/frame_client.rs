@@ -0,0 +1,198 @@
+use memmap2::Mmap;
+use std::fs::File;
+use std::io::{self, BufReader};
+use std::os::unix::net::UnixStream;
+use std::path::Path;
+use std::sync::atomic::{Ordering, fence};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use thiserror::Error;
+
+use crate::frame_server::{
+    FrameHeader, Message, PROTOCOL_VERSION, PixelFormat, SLOT_HEADER_SIZE, read_message,
+    slot_sequence, slot_size_field, write_message,
+};
+
+#[derive(Error, Debug)]
+pub enum FrameClientError {
+    #[error("IO Fehler: {0}")]
+    IoError(#[from] io::Error),
+    #[error("Protokollfehler: {0}")]
+    ProtocolError(String),
+    #[error("Vom Server abgelehnt: {0}")]
+    Rejected(String),
+    #[error("Verbindung vom Server geschlossen")]
+    Closed,
+}
+
+pub type Result<T> = std::result::Result<T, FrameClientError>;
+
+/// Eckdaten des abonnierten Streams aus `hello` und `ready`
+#[derive(Debug, Clone)]
+pub struct StreamInfo {
+    pub camera: String,
+    pub width: u32,
+    pub height: u32,
+    pub fps: f64,
+    pub format: PixelFormat,
+}
+
+/// Vom Frame-Server empfangener Frame (Kopie aus dem Shared Memory)
+pub struct Frame {
+    pub sequence: u64,
+    pub pts: Duration,
+    /// Veröffentlichungszeit beim Server
+    pub timestamp: SystemTime,
+    pub data: Vec<u8>,
+}
+
+/// Abonniert eine Kamera eines `FrameServer` (ohne GStreamer)
+pub struct FrameClient {
+    reader: BufReader<UnixStream>,
+    map: Mmap,
+    slots: usize,
+    slot_size: usize,
+    info: StreamInfo,
+    last_sequence: u64,
+    /// Lücken in der Sequenz und beim Lesen schon überschriebene Slots
+    dropped: u64,
+}
+
+impl FrameClient {
+    pub fn connect(socket_path: &Path, format: PixelFormat) -> Result<Self> {
+        let mut stream = UnixStream::connect(socket_path)?;
+        let mut reader = BufReader::new(stream.try_clone()?);
+
+        let (camera, width, height, fps) = match read_message(&mut reader)? {
+            Some(Message::Hello {
+                version,
+                camera,
+                width,
+                height,
+                fps,
+                formats,
+            }) => {
+                if version != PROTOCOL_VERSION {
+                    return Err(FrameClientError::ProtocolError(format!(
+                        "Protokollversion {} statt {}",
+                        version, PROTOCOL_VERSION
+                    )));
+                }
+                if !formats.contains(&format) {
+                    return Err(FrameClientError::Rejected(format!(
+                        "Format {} nicht angeboten",
+                        format
+                    )));
+                }
+                (camera, width, height, fps)
+            }
+            other => return Err(unexpected("hello", other)),
+        };
+
+        write_message(&mut stream, &Message::Subscribe { format })?;
+
+        let (shm, slots, slot_size) = match read_message(&mut reader)? {
+            Some(Message::Ready {
+                shm,
+                slots,
+                slot_size,
+                ..
+            }) => (shm, slots, slot_size),
+            Some(Message::Error { message }) => return Err(FrameClientError::Rejected(message)),
+            other => return Err(unexpected("ready", other)),
+        };
+
+        let file = File::open(&shm)?;
+        let map = unsafe { Mmap::map(&file)? };
+        if map.len() < slots * slot_size {
+            return Err(FrameClientError::ProtocolError(format!(
+                "{} kleiner als angekündigt",
+                shm.display()
+            )));
+        }
+
+        Ok(Self {
+            reader,
+            map,
+            slots,
+            slot_size,
+            info: StreamInfo {
+                camera,
+                width,
+                height,
+                fps,
+                format,
+            },
+            last_sequence: 0,
+            dropped: 0,
+        })
+    }
+
+    pub fn info(&self) -> &StreamInfo {
+        &self.info
+    }
+
+    /// Frames, die dieser Client verpasst hat
+    pub fn dropped_frames(&self) -> u64 {
+        self.dropped
+    }
+
+    /// Blockiert bis zum nächsten Frame; wurde sein Slot beim Lesen schon überschrieben,
+    /// zählt er als verworfen und es wird auf den nächsten gewartet
+    pub fn next_frame(&mut self) -> Result<Frame> {
+        loop {
+            let header = match read_message(&mut self.reader)? {
+                Some(Message::Frame(header)) => header,
+                Some(Message::Error { message }) => return Err(FrameClientError::Rejected(message)),
+                None => return Err(FrameClientError::Closed),
+                other => return Err(unexpected("frame", other)),
+            };
+
+            if self.last_sequence > 0 && header.sequence > self.last_sequence + 1 {
+                self.dropped += header.sequence - self.last_sequence - 1;
+            }
+            self.last_sequence = header.sequence;
+
+            match self.read_slot(&header) {
+                Some(data) => {
+                    return Ok(Frame {
+                        sequence: header.sequence,
+                        pts: Duration::from_nanos(header.pts_ns),
+                        timestamp: UNIX_EPOCH + Duration::from_nanos(header.timestamp_ns),
+                        data,
+                    });
+                }
+                None => self.dropped += 1,
+            }
+        }
+    }
+
+    /// Kopiert den Slot; None, wenn er nicht (mehr) den Frame `header.sequence` enthält
+    fn read_slot(&self, header: &FrameHeader) -> Option<Vec<u8>> {
+        if header.slot >= self.slots || header.size + SLOT_HEADER_SIZE > self.slot_size {
+            return None;
+        }
+
+        unsafe {
+            let base = self.map.as_ptr().add(header.slot * self.slot_size);
+            if slot_sequence(base).load(Ordering::Acquire) != header.sequence {
+                return None;
+            }
+            let size = slot_size_field(base).load(Ordering::Relaxed) as usize;
+            if size != header.size {
+                return None;
+            }
+            let data = std::slice::from_raw_parts(base.add(SLOT_HEADER_SIZE), size).to_vec();
+            fence(Ordering::Acquire);
+            (slot_sequence(base).load(Ordering::Relaxed) == header.sequence).then_some(data)
+        }
+    }
+}
+
+fn unexpected(expected: &str, got: Option<Message>) -> FrameClientError {
+    match got {
+        None => FrameClientError::Closed,
+        Some(message) => {
+            FrameClientError::ProtocolError(format!("{} erwartet, erhalten: {:?}", expected, message))
+        }
+    }
+}
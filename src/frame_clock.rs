@@ -0,0 +1,156 @@
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+/// Wer das Tempo der Aufnahme vorgibt
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimingSource {
+    /// Die Kamera blockiert bis zum nächsten Frame, es wird nicht zusätzlich gewartet
+    Camera,
+    /// Die FrameClock wartet bis zur nächsten absoluten Deadline (virtuelle Quellen)
+    Clock,
+}
+
+/// Erreichte gegenüber angeforderter Framerate und Jitter der Frame-Abstände
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct FrameClockStats {
+    pub requested_fps: f64,
+    pub achieved_fps: f64,
+    pub frames: u64,
+    pub late_frames: u64,
+    pub mean_interval_ms: f64,
+    pub jitter_ms: f64,
+    pub max_deviation_ms: f64,
+}
+
+/// Taktgeber für Frame-Schleifen.
+///
+/// Statt nach jedem Frame ein volles Intervall zu schlafen, wird gegen absolute
+/// Deadlines (`start + n * interval`) geplant. Die Zeit für Capture und Encoding
+/// geht so nicht zusätzlich zum Intervall verloren.
+pub struct FrameClock {
+    interval: Duration,
+    requested_fps: f64,
+    source: TimingSource,
+    start: Instant,
+    next_deadline: Instant,
+    last_tick: Option<Instant>,
+    frames: u64,
+    late_frames: u64,
+    // Welford-Statistik über die Frame-Abstände (Sekunden)
+    interval_mean: f64,
+    interval_m2: f64,
+    max_deviation: f64,
+}
+
+impl FrameClock {
+    /// `fps <= 0` bedeutet frei laufend (kein Warten)
+    pub fn new(fps: f64, source: TimingSource) -> Self {
+        let interval = if fps > 0.0 {
+            Duration::from_secs_f64(1.0 / fps)
+        } else {
+            Duration::ZERO
+        };
+        let now = Instant::now();
+
+        Self {
+            interval,
+            requested_fps: fps.max(0.0),
+            source,
+            start: now,
+            next_deadline: now + interval,
+            last_tick: None,
+            frames: 0,
+            late_frames: 0,
+            interval_mean: 0.0,
+            interval_m2: 0.0,
+            max_deviation: 0.0,
+        }
+    }
+
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    /// Registriert einen Frame und wartet (bei `TimingSource::Clock`) bis zur nächsten Deadline
+    pub fn wait(&mut self) {
+        self.tick();
+
+        if self.source == TimingSource::Camera || self.interval.is_zero() {
+            return;
+        }
+
+        let now = Instant::now();
+        if now < self.next_deadline {
+            std::thread::sleep(self.next_deadline - now);
+            self.next_deadline += self.interval;
+        } else if now - self.next_deadline > self.interval {
+            // Mehr als einen Frame zurück: neu aufsetzen statt in einem Schwall aufzuholen
+            self.late_frames += 1;
+            self.next_deadline = now + self.interval;
+        } else {
+            self.late_frames += 1;
+            self.next_deadline += self.interval;
+        }
+    }
+
+    /// Registriert einen Frame ohne zu warten
+    pub fn tick(&mut self) {
+        let now = Instant::now();
+
+        if let Some(last) = self.last_tick {
+            let dt = (now - last).as_secs_f64();
+            let n = self.frames as f64; // Anzahl Intervalle inkl. diesem
+            let delta = dt - self.interval_mean;
+            self.interval_mean += delta / n.max(1.0);
+            self.interval_m2 += delta * (dt - self.interval_mean);
+
+            if !self.interval.is_zero() {
+                self.max_deviation = self
+                    .max_deviation
+                    .max((dt - self.interval.as_secs_f64()).abs());
+            }
+        }
+
+        self.last_tick = Some(now);
+        self.frames += 1;
+    }
+
+    pub fn stats(&self) -> FrameClockStats {
+        let elapsed = match self.last_tick {
+            Some(last) => (last - self.start).as_secs_f64(),
+            None => 0.0,
+        };
+        let intervals = self.frames.saturating_sub(1);
+
+        FrameClockStats {
+            requested_fps: self.requested_fps,
+            achieved_fps: if elapsed > 0.0 {
+                intervals as f64 / elapsed
+            } else {
+                0.0
+            },
+            frames: self.frames,
+            late_frames: self.late_frames,
+            mean_interval_ms: self.interval_mean * 1000.0,
+            jitter_ms: if intervals > 1 {
+                (self.interval_m2 / (intervals - 1) as f64).sqrt() * 1000.0
+            } else {
+                0.0
+            },
+            max_deviation_ms: self.max_deviation * 1000.0,
+        }
+    }
+}
+
+impl FrameClockStats {
+    pub fn summary(&self) -> String {
+        format!(
+            "{:.2}/{:.2} FPS, Jitter {:.2} ms (max Abweichung {:.2} ms), {} verspätete Frames",
+            self.achieved_fps,
+            self.requested_fps,
+            self.jitter_ms,
+            self.max_deviation_ms,
+            self.late_frames
+        )
+    }
+}
@@ -0,0 +1,298 @@
+use serde::Serialize;
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Blöcke pro Zeile und Zeilen des Codes am unteren Bildrand
+const COLUMNS: usize = 64;
+const ROWS: usize = 2;
+const MAGIC: u8 = 0xA5;
+
+/// Inhalt eines Markers: wer hat den Frame wann als wievielten erzeugt
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FrameMarker {
+    pub camera: u8,
+    pub sequence: u32,
+    /// Erzeugungszeit, Mikrosekunden seit der Unix-Epoche
+    pub timestamp_us: u64,
+}
+
+impl FrameMarker {
+    pub fn now(camera: u8, sequence: u32) -> Self {
+        let timestamp_us = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_micros() as u64;
+        Self {
+            camera,
+            sequence,
+            timestamp_us,
+        }
+    }
+
+    /// Zeit von der Erzeugung bis `at` (0, falls die Uhren nicht passen)
+    pub fn age_at(&self, at: SystemTime) -> Duration {
+        let created = UNIX_EPOCH + Duration::from_micros(self.timestamp_us);
+        at.duration_since(created).unwrap_or_default()
+    }
+
+    /// Magic, Kamera, Sequenz und Zeitstempel (Big Endian), dahinter CRC-16
+    fn to_bytes(self) -> [u8; 16] {
+        let mut bytes = [0u8; 16];
+        bytes[0] = MAGIC;
+        bytes[1] = self.camera;
+        bytes[2..6].copy_from_slice(&self.sequence.to_be_bytes());
+        bytes[6..14].copy_from_slice(&self.timestamp_us.to_be_bytes());
+        let crc = crc16(&bytes[..14]);
+        bytes[14..].copy_from_slice(&crc.to_be_bytes());
+        bytes
+    }
+
+    fn from_bytes(bytes: &[u8; 16]) -> Option<Self> {
+        if bytes[0] != MAGIC || crc16(&bytes[..14]).to_be_bytes() != bytes[14..] {
+            return None;
+        }
+        Some(Self {
+            camera: bytes[1],
+            sequence: u32::from_be_bytes(bytes[2..6].try_into().ok()?),
+            timestamp_us: u64::from_be_bytes(bytes[6..14].try_into().ok()?),
+        })
+    }
+}
+
+/// CRC-16/CCITT-FALSE
+fn crc16(data: &[u8]) -> u16 {
+    let mut crc = 0xFFFFu16;
+    for &byte in data {
+        crc ^= (byte as u16) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 { (crc << 1) ^ 0x1021 } else { crc << 1 };
+        }
+    }
+    crc
+}
+
+/// Pixelbereich (x0, x1, y0, y1) von Bit `bit`; Blöcke sind quadratisch mit Kantenlänge
+/// Breite/64, damit der Code auch skaliert lesbar bleibt
+fn block_rect(bit: usize, width: usize, height: usize) -> (usize, usize, usize, usize) {
+    let size = width as f64 / COLUMNS as f64;
+    let top = height as f64 - ROWS as f64 * size;
+    let (column, row) = (bit % COLUMNS, bit / COLUMNS);
+    let x0 = (column as f64 * size) as usize;
+    let x1 = ((column + 1) as f64 * size) as usize;
+    let y0 = (top + row as f64 * size).max(0.0) as usize;
+    let y1 = ((top + (row + 1) as f64 * size) as usize).min(height);
+    (x0, x1, y0, y1)
+}
+
+/// Brennt den Marker als Schwarz-Weiß-Blöcke (1 = weiß) in den unteren Bildrand
+pub fn embed(rgb: &mut [u8], width: u32, height: u32, marker: FrameMarker) {
+    let (w, h) = (width as usize, height as usize);
+    let bytes = marker.to_bytes();
+
+    for bit in 0..COLUMNS * ROWS {
+        let value = if bytes[bit / 8] & (0x80 >> (bit % 8)) != 0 { 255 } else { 0 };
+        let (x0, x1, y0, y1) = block_rect(bit, w, h);
+        for y in y0..y1 {
+            rgb[(y * w + x0) * 3..(y * w + x1) * 3].fill(value);
+        }
+    }
+}
+
+/// Liest den Marker zurück; None, wenn kein Code vorhanden oder die Prüfsumme nicht stimmt.
+/// Gemittelt wird nur die innere Hälfte jedes Blocks (Kompressionsränder).
+pub fn decode(rgb: &[u8], width: u32, height: u32) -> Option<FrameMarker> {
+    let (w, h) = (width as usize, height as usize);
+    if w < COLUMNS || h < ROWS * w / COLUMNS || rgb.len() < w * h * 3 {
+        return None;
+    }
+
+    let mut bytes = [0u8; 16];
+    for bit in 0..COLUMNS * ROWS {
+        let (x0, x1, y0, y1) = block_rect(bit, w, h);
+        let (qx, qy) = ((x1 - x0) / 4, (y1 - y0) / 4);
+        let (mut sum, mut n) = (0u64, 0u64);
+        for y in (y0 + qy)..(y1 - qy).max(y0 + qy + 1) {
+            for x in (x0 + qx)..(x1 - qx).max(x0 + qx + 1) {
+                let i = (y * w + x) * 3;
+                sum += rgb[i] as u64 + rgb[i + 1] as u64 + rgb[i + 2] as u64;
+                n += 3;
+            }
+        }
+        if sum / n.max(1) >= 128 {
+            bytes[bit / 8] |= 0x80 >> (bit % 8);
+        }
+    }
+
+    FrameMarker::from_bytes(&bytes)
+}
+
+/// Latenz von der Erzeugung bis zum Empfang
+#[derive(Debug, Clone, Serialize)]
+pub struct LatencyStats {
+    pub min_ms: f64,
+    pub mean_ms: f64,
+    pub p95_ms: f64,
+    pub max_ms: f64,
+}
+
+/// Ergebnis je Kamera-ID
+#[derive(Debug, Clone, Serialize)]
+pub struct CameraReport {
+    pub camera: u8,
+    pub frames: u64,
+    pub first_sequence: u32,
+    pub last_sequence: u32,
+    /// Sequenznummern zwischen kleinster und größter, die nie ankamen
+    pub dropped: u64,
+    /// Sequenznummern, die mehrfach ankamen (z.B. wiederholte Frames)
+    pub duplicates: u64,
+    /// Frames, die nach einem späteren Frame ankamen
+    pub reordered: u64,
+    pub latency: Option<LatencyStats>,
+}
+
+impl CameraReport {
+    pub fn is_clean(&self) -> bool {
+        self.dropped == 0 && self.duplicates == 0 && self.reordered == 0
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MarkerReport {
+    pub frames: u64,
+    /// Frames ohne lesbaren Marker
+    pub undecodable: u64,
+    pub cameras: Vec<CameraReport>,
+}
+
+impl MarkerReport {
+    pub fn is_clean(&self) -> bool {
+        self.undecodable == 0 && self.cameras.iter().all(CameraReport::is_clean)
+    }
+}
+
+impl fmt::Display for MarkerReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} Frames, {} ohne lesbaren Marker", self.frames, self.undecodable)?;
+        for camera in &self.cameras {
+            write!(
+                f,
+                "  Kamera {}: {} Frames (#{} bis #{}), {} verloren, {} doppelt, {} umsortiert",
+                camera.camera,
+                camera.frames,
+                camera.first_sequence,
+                camera.last_sequence,
+                camera.dropped,
+                camera.duplicates,
+                camera.reordered
+            )?;
+            if let Some(latency) = &camera.latency {
+                write!(
+                    f,
+                    ", Latenz min {:.1} / Mittel {:.1} / p95 {:.1} / max {:.1} ms",
+                    latency.min_ms, latency.mean_ms, latency.p95_ms, latency.max_ms
+                )?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+struct CameraTrack {
+    frames: u64,
+    first: u32,
+    highest: u32,
+    last: u32,
+    seen: HashSet<u32>,
+    duplicates: u64,
+    reordered: u64,
+    latencies_ms: Vec<f64>,
+}
+
+/// Sammelt dekodierte Marker in Empfangsreihenfolge
+#[derive(Default)]
+pub struct MarkerAnalyzer {
+    frames: u64,
+    undecodable: u64,
+    cameras: BTreeMap<u8, CameraTrack>,
+}
+
+impl MarkerAnalyzer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Ein empfangener Frame; `received` nur bei Live-Quellen, deren Uhr zur
+    /// Erzeugung passt (Latenz)
+    pub fn push(&mut self, rgb: &[u8], width: u32, height: u32, received: Option<SystemTime>) {
+        self.frames += 1;
+        let Some(marker) = decode(rgb, width, height) else {
+            self.undecodable += 1;
+            return;
+        };
+
+        let track = self.cameras.entry(marker.camera).or_default();
+        if track.frames == 0 {
+            track.first = marker.sequence;
+            track.highest = marker.sequence;
+        }
+        track.frames += 1;
+        track.last = marker.sequence;
+
+        if !track.seen.insert(marker.sequence) {
+            track.duplicates += 1;
+        } else if marker.sequence < track.highest {
+            track.reordered += 1;
+        }
+        track.highest = track.highest.max(marker.sequence);
+
+        if let Some(at) = received {
+            track.latencies_ms.push(marker.age_at(at).as_secs_f64() * 1000.0);
+        }
+    }
+
+    pub fn report(&self) -> MarkerReport {
+        let cameras = self
+            .cameras
+            .iter()
+            .map(|(&camera, track)| {
+                let lowest = track.seen.iter().min().copied().unwrap_or(track.first);
+                let span = (track.highest - lowest) as u64 + 1;
+                CameraReport {
+                    camera,
+                    frames: track.frames,
+                    first_sequence: track.first,
+                    last_sequence: track.last,
+                    dropped: span - track.seen.len() as u64,
+                    duplicates: track.duplicates,
+                    reordered: track.reordered,
+                    latency: latency_stats(&track.latencies_ms),
+                }
+            })
+            .collect();
+
+        MarkerReport {
+            frames: self.frames,
+            undecodable: self.undecodable,
+            cameras,
+        }
+    }
+}
+
+fn latency_stats(values: &[f64]) -> Option<LatencyStats> {
+    if values.is_empty() {
+        return None;
+    }
+    let mut sorted = values.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    let p95 = sorted[((sorted.len() - 1) as f64 * 0.95).round() as usize];
+    Some(LatencyStats {
+        min_ms: sorted[0],
+        mean_ms: sorted.iter().sum::<f64>() / sorted.len() as f64,
+        p95_ms: p95,
+        max_ms: sorted[sorted.len() - 1],
+    })
+}
@@ -0,0 +1,189 @@
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::fmt;
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::Duration;
+
+/// Was passiert, wenn die Queue zwischen Capture und Encoder voll ist
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DropPolicy {
+    Block,      // Capture wartet, bis der Encoder aufholt
+    DropOldest, // ältesten Frame verwerfen, neuen einreihen
+    DropNewest, // neuen Frame verwerfen
+}
+
+impl fmt::Display for DropPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            DropPolicy::Block => "block",
+            DropPolicy::DropOldest => "drop-oldest",
+            DropPolicy::DropNewest => "drop-newest",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl std::str::FromStr for DropPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "block" => Ok(DropPolicy::Block),
+            "drop-oldest" => Ok(DropPolicy::DropOldest),
+            "drop-newest" => Ok(DropPolicy::DropNewest),
+            other => Err(format!(
+                "Unbekannte Drop-Policy: {} (block, drop-oldest, drop-newest)",
+                other
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct QueueStats {
+    pub depth: usize,
+    pub capacity: usize,
+    pub max_depth: usize,
+    /// Angebotene Frames, jeder genau einmal; es gilt `captured == pushed + dropped`
+    pub captured: u64,
+    /// Eingereihte Frames, die nicht wieder verdrängt wurden
+    pub pushed: u64,
+    /// Verworfene, verdrängte und beim Schließen noch wartende Frames (Block)
+    pub dropped: u64,
+}
+
+struct QueueState<T> {
+    items: VecDeque<T>,
+    closed: bool,
+    stats: QueueStats,
+}
+
+/// Begrenzte Queue zwischen Capture- und Encoder-Thread
+pub struct FrameQueue<T> {
+    inner: Arc<(Mutex<QueueState<T>>, Condvar, Condvar)>,
+    policy: DropPolicy,
+}
+
+impl<T> Clone for FrameQueue<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            policy: self.policy,
+        }
+    }
+}
+
+impl<T> FrameQueue<T> {
+    pub fn new(capacity: usize, policy: DropPolicy) -> Self {
+        let capacity = capacity.max(1);
+        Self {
+            inner: Arc::new((
+                Mutex::new(QueueState {
+                    items: VecDeque::with_capacity(capacity),
+                    closed: false,
+                    stats: QueueStats {
+                        capacity,
+                        ..Default::default()
+                    },
+                }),
+                Condvar::new(), // not_empty
+                Condvar::new(), // not_full
+            )),
+            policy,
+        }
+    }
+
+    pub fn policy(&self) -> DropPolicy {
+        self.policy
+    }
+
+    /// Reiht einen Frame ein. Gibt false zurück, wenn ein Frame verworfen wurde
+    /// oder die Queue geschlossen ist.
+    pub fn push(&self, item: T) -> bool {
+        let (lock, not_empty, not_full) = &*self.inner;
+        let mut state = lock.lock().unwrap();
+
+        if state.closed {
+            return false;
+        }
+        state.stats.captured += 1;
+
+        let mut accepted = true;
+
+        if state.items.len() >= state.stats.capacity {
+            match self.policy {
+                DropPolicy::Block => {
+                    while state.items.len() >= state.stats.capacity && !state.closed {
+                        state = not_full.wait(state).unwrap();
+                    }
+                    if state.closed {
+                        state.stats.dropped += 1;
+                        return false;
+                    }
+                }
+                DropPolicy::DropOldest => {
+                    state.items.pop_front();
+                    state.stats.pushed -= 1;
+                    state.stats.dropped += 1;
+                    accepted = false;
+                }
+                DropPolicy::DropNewest => {
+                    state.stats.dropped += 1;
+                    return false;
+                }
+            }
+        }
+
+        state.items.push_back(item);
+        state.stats.pushed += 1;
+        state.stats.depth = state.items.len();
+        state.stats.max_depth = state.stats.max_depth.max(state.stats.depth);
+        not_empty.notify_one();
+
+        accepted
+    }
+
+    /// Wartet bis zu `timeout` auf den nächsten Frame.
+    /// `None` bei Timeout oder wenn die Queue geschlossen und leer ist.
+    pub fn pop_timeout(&self, timeout: Duration) -> Option<T> {
+        let (lock, not_empty, not_full) = &*self.inner;
+        let mut state = lock.lock().unwrap();
+
+        if state.items.is_empty() && !state.closed {
+            state = not_empty
+                .wait_timeout_while(state, timeout, |s| s.items.is_empty() && !s.closed)
+                .unwrap()
+                .0;
+        }
+
+        let item = state.items.pop_front();
+        state.stats.depth = state.items.len();
+        if item.is_some() {
+            not_full.notify_one();
+        }
+        item
+    }
+
+    /// Keine weiteren Frames; der Encoder leert die Queue noch vollständig
+    pub fn close(&self) {
+        let (lock, not_empty, not_full) = &*self.inner;
+        lock.lock().unwrap().closed = true;
+        not_empty.notify_all();
+        not_full.notify_all();
+    }
+
+    /// Geschlossen, z.B. weil der Encoder abgebrochen hat; weitere `push` werden verworfen
+    pub fn is_closed(&self) -> bool {
+        self.inner.0.lock().unwrap().closed
+    }
+
+    pub fn is_finished(&self) -> bool {
+        let state = self.inner.0.lock().unwrap();
+        state.closed && state.items.is_empty()
+    }
+
+    pub fn stats(&self) -> QueueStats {
+        self.inner.0.lock().unwrap().stats
+    }
+}
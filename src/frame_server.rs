@@ -0,0 +1,488 @@
+use memmap2::MmapMut;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::fs::{self, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering, fence};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum FrameServerError {
+    #[error("IO Fehler: {0}")]
+    IoError(#[from] io::Error),
+    #[error("Socket wird bereits verwendet: {0}")]
+    AddressInUse(String),
+    #[error("Frame hat {0} Bytes, erwartet {1}")]
+    FrameSize(usize, usize),
+}
+
+pub type Result<T> = std::result::Result<T, FrameServerError>;
+
+/// Wird im `hello` mitgeschickt; Clients lehnen andere Versionen ab
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// Frames pro Format im Shared Memory (Ring), bevor ein Slot überschrieben wird
+pub const RING_SLOTS: usize = 4;
+
+/// Kopf jedes Slots: Sequenznummer und Länge als u64 (native Byte-Reihenfolge).
+/// Sequenz 0 heißt "wird gerade geschrieben".
+pub const SLOT_HEADER_SIZE: usize = 16;
+
+/// Zeit für einen Client, um nach `hello` sein Format zu wählen
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Länger blockierende Clients werden getrennt, statt die Quelle aufzuhalten
+const SEND_TIMEOUT: Duration = Duration::from_millis(200);
+
+/// Pixelformate, die ein Client anfordern kann (die Quelle liefert immer RGB)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PixelFormat {
+    Rgb,   // 3 Byte pro Pixel
+    Bgr,   // 3 Byte pro Pixel (OpenCV)
+    Gray8, // 1 Byte pro Pixel, BT.601-Luma
+}
+
+impl PixelFormat {
+    pub const ALL: [PixelFormat; 3] = [PixelFormat::Rgb, PixelFormat::Bgr, PixelFormat::Gray8];
+
+    pub fn bytes_per_pixel(self) -> usize {
+        match self {
+            PixelFormat::Rgb | PixelFormat::Bgr => 3,
+            PixelFormat::Gray8 => 1,
+        }
+    }
+
+    fn convert(self, rgb: &[u8]) -> Vec<u8> {
+        match self {
+            PixelFormat::Rgb => rgb.to_vec(),
+            PixelFormat::Bgr => rgb
+                .chunks_exact(3)
+                .flat_map(|px| [px[2], px[1], px[0]])
+                .collect(),
+            PixelFormat::Gray8 => rgb
+                .chunks_exact(3)
+                .map(|px| {
+                    ((77 * px[0] as u32 + 150 * px[1] as u32 + 29 * px[2] as u32) >> 8) as u8
+                })
+                .collect(),
+        }
+    }
+}
+
+impl fmt::Display for PixelFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            PixelFormat::Rgb => "rgb",
+            PixelFormat::Bgr => "bgr",
+            PixelFormat::Gray8 => "gray8",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl std::str::FromStr for PixelFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "rgb" => Ok(PixelFormat::Rgb),
+            "bgr" => Ok(PixelFormat::Bgr),
+            "gray8" | "gray" | "mono" => Ok(PixelFormat::Gray8),
+            other => Err(format!("Unbekanntes Pixelformat: {} (rgb, bgr, gray8)", other)),
+        }
+    }
+}
+
+/// Nachrichten auf dem Socket, je eine JSON-Zeile.
+///
+/// Ablauf: Server sendet `hello`, Client antwortet mit `subscribe`, Server bestätigt mit
+/// `ready` (Shared-Memory-Datei des Formats) oder `error`; danach folgt pro Frame ein
+/// `frame`. Die Pixel liegen im Slot `slot` der Datei, ab `slot * slot_size +
+/// SLOT_HEADER_SIZE`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum Message {
+    Hello {
+        version: u32,
+        camera: String,
+        width: u32,
+        height: u32,
+        fps: f64,
+        formats: Vec<PixelFormat>,
+    },
+    Subscribe {
+        format: PixelFormat,
+    },
+    Ready {
+        format: PixelFormat,
+        shm: PathBuf,
+        slots: usize,
+        slot_size: usize,
+        frame_size: usize,
+    },
+    Frame(FrameHeader),
+    Error {
+        message: String,
+    },
+}
+
+/// Kopf eines Frames; der Slot ist gültig, solange seine Sequenz gleich `sequence` ist
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FrameHeader {
+    /// Beginnt bei 1, Lücken sind vom Server verworfene Frames
+    pub sequence: u64,
+    pub slot: usize,
+    /// PTS der Quelle
+    pub pts_ns: u64,
+    /// Wanduhrzeit der Veröffentlichung, ns seit Unix-Epoche
+    pub timestamp_ns: u64,
+    pub size: usize,
+}
+
+pub fn write_message(stream: &mut impl Write, message: &Message) -> io::Result<()> {
+    let mut line = serde_json::to_vec(message).map_err(io::Error::other)?;
+    line.push(b'\n');
+    stream.write_all(&line)
+}
+
+/// None, wenn die Gegenseite die Verbindung geschlossen hat
+pub fn read_message(reader: &mut impl BufRead) -> io::Result<Option<Message>> {
+    let mut line = String::new();
+    if reader.read_line(&mut line)? == 0 {
+        return Ok(None);
+    }
+    serde_json::from_str(&line)
+        .map(Some)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+/// Sequenznummer am Anfang eines Slots
+///
+/// # Safety
+/// `slot` muss auf den Anfang eines Slots einer gültigen Abbildung zeigen.
+pub unsafe fn slot_sequence<'a>(slot: *const u8) -> &'a AtomicU64 {
+    unsafe { &*(slot as *const AtomicU64) }
+}
+
+/// Länge der Pixeldaten eines Slots
+///
+/// # Safety
+/// Wie bei `slot_sequence`.
+pub unsafe fn slot_size_field<'a>(slot: *const u8) -> &'a AtomicU64 {
+    unsafe { &*(slot.add(8) as *const AtomicU64) }
+}
+
+/// Ringpuffer eines Formats in einer Datei unter /dev/shm
+struct Ring {
+    path: PathBuf,
+    map: MmapMut,
+    slot_size: usize,
+    frame_size: usize,
+    next_slot: usize,
+}
+
+impl Ring {
+    fn create(path: PathBuf, frame_size: usize) -> io::Result<Self> {
+        // Auf 8 Byte aufrunden, damit die Slot-Köpfe ausgerichtet sind
+        let slot_size = (SLOT_HEADER_SIZE + frame_size).div_ceil(8) * 8;
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(&path)?;
+        file.set_len((slot_size * RING_SLOTS) as u64)?;
+        let map = unsafe { MmapMut::map_mut(&file)? };
+
+        Ok(Self {
+            path,
+            map,
+            slot_size,
+            frame_size,
+            next_slot: 0,
+        })
+    }
+
+    /// Schreibt den Frame in den nächsten Slot (Seqlock: erst Sequenz 0, zuletzt die neue)
+    fn write(&mut self, sequence: u64, data: &[u8]) -> usize {
+        let slot = self.next_slot;
+        self.next_slot = (slot + 1) % RING_SLOTS;
+
+        unsafe {
+            let base = self.map.as_mut_ptr().add(slot * self.slot_size);
+            slot_sequence(base).store(0, Ordering::Release);
+            fence(Ordering::Release);
+            std::ptr::copy_nonoverlapping(data.as_ptr(), base.add(SLOT_HEADER_SIZE), data.len());
+            slot_size_field(base).store(data.len() as u64, Ordering::Relaxed);
+            slot_sequence(base).store(sequence, Ordering::Release);
+        }
+        slot
+    }
+
+    fn ready_message(&self, format: PixelFormat) -> Message {
+        Message::Ready {
+            format,
+            shm: self.path.clone(),
+            slots: RING_SLOTS,
+            slot_size: self.slot_size,
+            frame_size: self.frame_size,
+        }
+    }
+}
+
+impl Drop for Ring {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+struct Subscriber {
+    stream: UnixStream,
+    format: PixelFormat,
+}
+
+struct Shared {
+    camera: String,
+    width: u32,
+    height: u32,
+    fps: f64,
+    /// Präfix der Shared-Memory-Dateien, je Format ein Ring
+    shm_prefix: PathBuf,
+    rings: Mutex<HashMap<PixelFormat, Ring>>,
+    subscribers: Mutex<Vec<Subscriber>>,
+}
+
+/// Veröffentlicht die Frames einer Kamera über einen Unix-Socket mit Shared-Memory-Puffern
+pub struct FrameServer {
+    socket_path: PathBuf,
+    shared: Arc<Shared>,
+    sequence: AtomicU64,
+    running: Arc<AtomicBool>,
+    listener: Option<JoinHandle<()>>,
+}
+
+/// `$XDG_RUNTIME_DIR/cam_record_sim`, sonst im temporären Verzeichnis
+pub fn default_socket_dir() -> PathBuf {
+    std::env::var_os("XDG_RUNTIME_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(std::env::temp_dir)
+        .join("cam_record_sim")
+}
+
+/// Socket einer Kamera im Socket-Verzeichnis, z.B. `left.sock`
+pub fn socket_path(dir: &Path, camera: &str) -> PathBuf {
+    dir.join(format!("{}.sock", sanitize(camera)))
+}
+
+/// Kameraname als Datei- bzw. URL-Bestandteil
+pub fn sanitize(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' { c } else { '_' })
+        .collect()
+}
+
+impl FrameServer {
+    /// Öffnet den Socket; ein verwaister Socket eines beendeten Servers wird ersetzt
+    pub fn start(socket_path: &Path, camera: &str, width: u32, height: u32, fps: f64) -> Result<Self> {
+        if socket_path.exists() {
+            if UnixStream::connect(socket_path).is_ok() {
+                return Err(FrameServerError::AddressInUse(socket_path.display().to_string()));
+            }
+            fs::remove_file(socket_path)?;
+        }
+        if let Some(dir) = socket_path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        let listener = UnixListener::bind(socket_path)?;
+        listener.set_nonblocking(true)?;
+
+        let shm_dir = Path::new("/dev/shm");
+        let shm_dir = if shm_dir.is_dir() {
+            shm_dir.to_path_buf()
+        } else {
+            std::env::temp_dir()
+        };
+        let shared = Arc::new(Shared {
+            camera: camera.to_string(),
+            width,
+            height,
+            fps,
+            shm_prefix: shm_dir.join(format!(
+                "cam_record_sim-{}-{}",
+                std::process::id(),
+                sanitize(camera)
+            )),
+            rings: Mutex::new(HashMap::new()),
+            subscribers: Mutex::new(Vec::new()),
+        });
+
+        let running = Arc::new(AtomicBool::new(true));
+        let listener = {
+            let shared = shared.clone();
+            let running = running.clone();
+            thread::spawn(move || accept_loop(listener, shared, running))
+        };
+
+        Ok(Self {
+            socket_path: socket_path.to_path_buf(),
+            shared,
+            sequence: AtomicU64::new(0),
+            running,
+            listener: Some(listener),
+        })
+    }
+
+    pub fn socket_path(&self) -> &Path {
+        &self.socket_path
+    }
+
+    pub fn client_count(&self) -> usize {
+        self.shared.subscribers.lock().unwrap().len()
+    }
+
+    /// Verteilt einen RGB-Frame an alle Clients; ohne Clients wird nichts kopiert
+    pub fn publish(&self, rgb: &[u8], pts: Duration) -> Result<()> {
+        let expected = (self.shared.width * self.shared.height * 3) as usize;
+        if rgb.len() != expected {
+            return Err(FrameServerError::FrameSize(rgb.len(), expected));
+        }
+
+        let sequence = self.sequence.fetch_add(1, Ordering::SeqCst) + 1;
+        let timestamp_ns = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos() as u64;
+
+        let mut subscribers = self.shared.subscribers.lock().unwrap();
+        if subscribers.is_empty() {
+            return Ok(());
+        }
+
+        let mut headers: HashMap<PixelFormat, FrameHeader> = HashMap::new();
+        {
+            let mut rings = self.shared.rings.lock().unwrap();
+            for subscriber in subscribers.iter() {
+                if headers.contains_key(&subscriber.format) {
+                    continue;
+                }
+                let Some(ring) = rings.get_mut(&subscriber.format) else {
+                    continue;
+                };
+                let data = subscriber.format.convert(rgb);
+                let slot = ring.write(sequence, &data);
+                headers.insert(
+                    subscriber.format,
+                    FrameHeader {
+                        sequence,
+                        slot,
+                        pts_ns: pts.as_nanos() as u64,
+                        timestamp_ns,
+                        size: data.len(),
+                    },
+                );
+            }
+        }
+
+        // Getrennte oder zu langsame Clients fallen heraus
+        subscribers.retain_mut(|subscriber| match headers.get(&subscriber.format) {
+            Some(header) => write_message(&mut subscriber.stream, &Message::Frame(*header)).is_ok(),
+            None => false,
+        });
+        Ok(())
+    }
+}
+
+impl Drop for FrameServer {
+    fn drop(&mut self) {
+        self.running.store(false, Ordering::SeqCst);
+        if let Some(listener) = self.listener.take() {
+            let _ = listener.join();
+        }
+        // Schließt die Verbindungen (Clients sehen EOF) und entfernt die Shared-Memory-Dateien
+        self.shared.subscribers.lock().unwrap().clear();
+        self.shared.rings.lock().unwrap().clear();
+        let _ = fs::remove_file(&self.socket_path);
+    }
+}
+
+fn accept_loop(listener: UnixListener, shared: Arc<Shared>, running: Arc<AtomicBool>) {
+    while running.load(Ordering::SeqCst) {
+        match listener.accept() {
+            Ok((stream, _)) => {
+                let shared = shared.clone();
+                thread::spawn(move || {
+                    if let Err(e) = handshake(stream, &shared) {
+                        eprintln!("Frame-Server {}: Client abgewiesen: {}", shared.camera, e);
+                    }
+                });
+            }
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
+                thread::sleep(Duration::from_millis(50));
+            }
+            Err(e) => {
+                eprintln!("Frame-Server {}: {}", shared.camera, e);
+                thread::sleep(Duration::from_millis(50));
+            }
+        }
+    }
+}
+
+/// `hello` senden, `subscribe` lesen, Ring des Formats anlegen, `ready` senden
+fn handshake(mut stream: UnixStream, shared: &Shared) -> io::Result<()> {
+    stream.set_nonblocking(false)?;
+    stream.set_read_timeout(Some(HANDSHAKE_TIMEOUT))?;
+    stream.set_write_timeout(Some(SEND_TIMEOUT))?;
+
+    write_message(
+        &mut stream,
+        &Message::Hello {
+            version: PROTOCOL_VERSION,
+            camera: shared.camera.clone(),
+            width: shared.width,
+            height: shared.height,
+            fps: shared.fps,
+            formats: PixelFormat::ALL.to_vec(),
+        },
+    )?;
+
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let format = match read_message(&mut reader)? {
+        Some(Message::Subscribe { format }) => format,
+        other => {
+            let message = format!("subscribe erwartet, erhalten: {:?}", other);
+            let _ = write_message(&mut stream, &Message::Error { message: message.clone() });
+            return Err(io::Error::new(io::ErrorKind::InvalidData, message));
+        }
+    };
+
+    let ready = {
+        let mut rings = shared.rings.lock().unwrap();
+        let ring = match rings.entry(format) {
+            std::collections::hash_map::Entry::Occupied(entry) => entry.into_mut(),
+            std::collections::hash_map::Entry::Vacant(entry) => {
+                let frame_size =
+                    (shared.width * shared.height) as usize * format.bytes_per_pixel();
+                let path = shared.shm_prefix.with_extension(format.to_string());
+                entry.insert(Ring::create(path, frame_size)?)
+            }
+        };
+        ring.ready_message(format)
+    };
+    write_message(&mut stream, &ready)?;
+
+    stream.set_read_timeout(None)?;
+    shared
+        .subscribers
+        .lock()
+        .unwrap()
+        .push(Subscriber { stream, format });
+    Ok(())
+}
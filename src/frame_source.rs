@@ -0,0 +1,230 @@
+use std::fmt;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+use thiserror::Error;
+
+use crate::camera::CameraDevice;
+use crate::playback_camera::{Pacing, PlaybackCamera};
+use crate::test_patterns::PatternConfig;
+use crate::virtual_camera::VirtualCamera;
+
+#[derive(Error, Debug)]
+pub enum FrameSourceError {
+    #[error("Quelle konnte nicht geöffnet werden: {0}")]
+    OpenError(String),
+    #[error("Frame konnte nicht gelesen werden: {0}")]
+    ReadError(String),
+}
+
+pub type Result<T> = std::result::Result<T, FrameSourceError>;
+
+/// RGB-Frame (3 Byte pro Pixel) mit seinem Zeitstempel
+pub struct SourceFrame {
+    pub data: Vec<u8>,
+    /// PTS der Aufnahme bzw. Zeit seit Öffnen der Quelle
+    pub pts: Duration,
+}
+
+/// Einheitlicher Zugriff auf echte, virtuelle und abgespielte Kameras für Ausgaben
+/// wie den Frame-Server
+pub trait FrameSource {
+    fn name(&self) -> String;
+    fn resolution(&self) -> (u32, u32);
+    fn fps(&self) -> f64;
+    /// Blockiert, bis der nächste Frame fällig ist
+    fn next_frame(&mut self) -> Result<SourceFrame>;
+    /// Takt von `next_frame`; echte Kameras liefern immer in ihrem eigenen Takt
+    fn set_pacing(&mut self, _pacing: Pacing) {}
+}
+
+/// Quelle auf der Kommandozeile: `camera:N`, `virtual:N[:TESTBILD]` oder Pfad zu
+/// Video/Bildfolge
+#[derive(Debug, Clone)]
+pub enum SourceSpec {
+    Camera(u32),
+    Virtual(u32, PatternConfig),
+    Playback(PathBuf),
+}
+
+impl fmt::Display for SourceSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SourceSpec::Camera(index) => write!(f, "camera:{}", index),
+            SourceSpec::Virtual(id, pattern) if *pattern == PatternConfig::default() => {
+                write!(f, "virtual:{}", id)
+            }
+            SourceSpec::Virtual(id, pattern) => write!(f, "virtual:{}:{}", id, pattern),
+            SourceSpec::Playback(path) => write!(f, "{}", path.display()),
+        }
+    }
+}
+
+impl std::str::FromStr for SourceSpec {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let parse_index = |value: &str| {
+            value
+                .parse::<u32>()
+                .map_err(|_| format!("Ungültiger Index in Quelle: {}", s))
+        };
+        if let Some(index) = s.strip_prefix("camera:") {
+            Ok(SourceSpec::Camera(parse_index(index)?))
+        } else if let Some(rest) = s.strip_prefix("virtual:") {
+            let (id, pattern) = match rest.split_once(':') {
+                Some((id, pattern)) => (id, pattern.parse()?),
+                None => (rest, PatternConfig::default()),
+            };
+            Ok(SourceSpec::Virtual(parse_index(id)?, pattern))
+        } else if s.is_empty() {
+            Err("Leere Quelle (camera:N, virtual:N oder Pfad)".to_string())
+        } else {
+            Ok(SourceSpec::Playback(PathBuf::from(s)))
+        }
+    }
+}
+
+impl SourceSpec {
+    /// Auflösung und Framerate gelten für echte und virtuelle Kameras; Aufnahmen laufen
+    /// in Echtzeit nach PTS und in Schleife
+    pub fn open(&self, width: u32, height: u32, fps: f64) -> Result<Box<dyn FrameSource>> {
+        match self {
+            SourceSpec::Camera(index) => {
+                let mut cam = CameraDevice::new_with_resolution(*index, width, height)
+                    .map_err(|e| FrameSourceError::OpenError(e.to_string()))?;
+                cam.start()
+                    .map_err(|e| FrameSourceError::OpenError(e.to_string()))?;
+                Ok(Box::new(LiveCamera {
+                    cam,
+                    fps,
+                    started: Instant::now(),
+                }))
+            }
+            SourceSpec::Virtual(id, pattern) => Ok(Box::new(SyntheticCamera {
+                cam: VirtualCamera::new(*id, width, height, fps).with_pattern(*pattern),
+                started: Instant::now(),
+                pacing: Pacing::RealTime,
+                frames: 0,
+            })),
+            SourceSpec::Playback(path) => {
+                let id = path
+                    .file_stem()
+                    .and_then(|stem| stem.to_str())
+                    .and_then(|stem| stem.strip_prefix("camera_")?.split("__").next()?.parse().ok())
+                    .unwrap_or(0);
+                let cam = PlaybackCamera::new(id, path, true)
+                    .map_err(|e| FrameSourceError::OpenError(e.to_string()))?;
+                Ok(Box::new(cam))
+            }
+        }
+    }
+}
+
+struct LiveCamera {
+    cam: CameraDevice,
+    fps: f64,
+    started: Instant,
+}
+
+impl FrameSource for LiveCamera {
+    fn name(&self) -> String {
+        format!("camera{}", self.cam.get_index())
+    }
+
+    fn resolution(&self) -> (u32, u32) {
+        self.cam.get_resolution()
+    }
+
+    fn fps(&self) -> f64 {
+        self.fps
+    }
+
+    fn next_frame(&mut self) -> Result<SourceFrame> {
+        // get_frame blockiert bis zum nächsten Kamera-Frame
+        let data = self
+            .cam
+            .get_frame()
+            .map_err(|e| FrameSourceError::ReadError(e.to_string()))?;
+        Ok(SourceFrame {
+            data,
+            pts: self.started.elapsed(),
+        })
+    }
+}
+
+impl Drop for LiveCamera {
+    fn drop(&mut self) {
+        let _ = self.cam.stop();
+    }
+}
+
+struct SyntheticCamera {
+    cam: VirtualCamera,
+    started: Instant,
+    pacing: Pacing,
+    frames: u64,
+}
+
+impl FrameSource for SyntheticCamera {
+    fn name(&self) -> String {
+        format!("virtual{}", self.cam.get_id())
+    }
+
+    fn resolution(&self) -> (u32, u32) {
+        self.cam.get_resolution()
+    }
+
+    fn fps(&self) -> f64 {
+        self.cam.get_fps()
+    }
+
+    fn next_frame(&mut self) -> Result<SourceFrame> {
+        // Ohne Takt zählt der PTS in Frame-Intervallen statt nach der Uhr
+        let pts = if self.pacing == Pacing::AsFastAsPossible {
+            Duration::from_secs_f64(self.frames as f64 / self.fps())
+        } else {
+            self.cam.wait_for_next_frame();
+            self.started.elapsed()
+        };
+        self.frames += 1;
+        let data = self
+            .cam
+            .get_frame()
+            .map_err(|e| FrameSourceError::ReadError(e.to_string()))?;
+        Ok(SourceFrame { data, pts })
+    }
+
+    fn set_pacing(&mut self, pacing: Pacing) {
+        self.pacing = pacing;
+    }
+}
+
+impl FrameSource for PlaybackCamera {
+    fn name(&self) -> String {
+        self.get_video_path()
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_else(|| format!("playback{}", self.get_camera_id()))
+    }
+
+    fn resolution(&self) -> (u32, u32) {
+        let info = self.get_media_info();
+        (info.width, info.height)
+    }
+
+    fn fps(&self) -> f64 {
+        self.get_fps()
+    }
+
+    fn next_frame(&mut self) -> Result<SourceFrame> {
+        let data = self
+            .get_frame()
+            .map_err(|e| FrameSourceError::ReadError(e.to_string()))?;
+        let pts = self.get_position().map(|p| p.pts).unwrap_or_default();
+        Ok(SourceFrame { data, pts })
+    }
+
+    fn set_pacing(&mut self, pacing: Pacing) {
+        PlaybackCamera::set_pacing(self, pacing);
+    }
+}
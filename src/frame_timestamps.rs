@@ -0,0 +1,66 @@
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// Zeitstempel-Datei neben dem Video: `camera_0__X.mp4` -> `camera_0__X.timestamps.csv`
+pub fn sidecar_path(video_path: &Path) -> PathBuf {
+    video_path.with_extension("timestamps.csv")
+}
+
+/// Schreibt pro Frame den PTS im Video und den tatsächlichen Capture-Zeitpunkt.
+///
+/// `VideoRecorder` stempelt mit festem Frame-Abstand, der echte Jitter der Kamera
+/// steht nur in dieser Datei.
+pub struct TimestampWriter {
+    file: BufWriter<File>,
+    first_capture: Option<Instant>,
+}
+
+impl TimestampWriter {
+    pub fn create(video_path: &Path) -> io::Result<Self> {
+        let mut file = BufWriter::new(File::create(sidecar_path(video_path))?);
+        writeln!(file, "frame,pts_ns,capture_ns")?;
+
+        Ok(Self {
+            file,
+            first_capture: None,
+        })
+    }
+
+    /// `captured_at` relativ zum ersten Frame
+    pub fn write(&mut self, frame: u64, pts: Duration, captured_at: Instant) -> io::Result<()> {
+        let first = *self.first_capture.get_or_insert(captured_at);
+        let capture = captured_at.saturating_duration_since(first);
+
+        writeln!(
+            self.file,
+            "{},{},{}",
+            frame,
+            pts.as_nanos(),
+            capture.as_nanos()
+        )
+    }
+
+    pub fn finish(mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+/// Liest die Capture-Zeitpunkte (relativ zum ersten Frame), falls eine Zeitstempel-Datei
+/// zum Video existiert
+pub fn read_capture_times(video_path: &Path) -> Option<Vec<Duration>> {
+    let file = File::open(sidecar_path(video_path)).ok()?;
+
+    let times: Vec<Duration> = BufReader::new(file)
+        .lines()
+        .skip(1)
+        .map_while(|line| line.ok())
+        .filter_map(|line| {
+            let capture_ns = line.split(',').nth(2)?.trim().parse::<u64>().ok()?;
+            Some(Duration::from_nanos(capture_ns))
+        })
+        .collect();
+
+    (!times.is_empty()).then_some(times)
+}
@@ -3,6 +3,8 @@ use gstreamer::prelude::*;
 use gstreamer_app as gst_app;
 use thiserror::Error;
 
+use crate::pipeline_health::BusWatcher;
+
 #[derive(Error, Debug)]
 pub enum GstCameraError {
     #[error("GStreamer initialization failed: {0}")]
@@ -11,6 +13,10 @@ pub enum GstCameraError {
     PipelineError(String),
     #[error("Frame capture failed: {0}")]
     FrameError(String),
+    #[error("Pipeline failed: {0}")]
+    PipelineFailed(String),
+    #[error("No frame within {0:?}")]
+    Timeout(std::time::Duration),
 }
 
 pub type Result<T> = std::result::Result<T, GstCameraError>;
@@ -18,10 +24,23 @@ pub type Result<T> = std::result::Result<T, GstCameraError>;
 pub struct GstCamera {
     pipeline: gst::Pipeline,
     appsink: gst_app::AppSink,
+    /// Only set for `new_simulated`
+    appsrc: Option<gst_app::AppSrc>,
     width: u32,
     height: u32,
+    health: BusWatcher,
+    frame_timeout: std::time::Duration,
 }
 
+/// Demosaic stage shared by real and simulated Bayer sources
+const DEMOSAIC_TO_APPSINK: &str = "bayer2rgb ! \
+     videoconvert ! \
+     video/x-raw,format=RGB ! \
+     appsink name=sink emit-signals=true sync=false max-buffers=1 drop=true";
+
+/// How long `get_frame` waits before giving up (a removed USB camera never delivers again)
+pub const DEFAULT_FRAME_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(2);
+
 impl GstCamera {
     pub fn new(index: u32, width: u32, height: u32, fps: u32) -> Result<Self> {
         // Initialize GStreamer
@@ -37,33 +56,13 @@ impl GstCamera {
         // Create pipeline for Bayer camera with conversion to RGB
         let pipeline_str = format!(
             "v4l2src device=/dev/video{} ! \
-             video/x-bayer,width={},height={},framerate={}/1 ! \
-             bayer2rgb ! \
-             videoconvert ! \
-             video/x-raw,format=RGB ! \
-             appsink name=sink emit-signals=true sync=false max-buffers=1 drop=true",
-            index, width, height, fps
+             video/x-bayer,width={},height={},framerate={}/1 ! {}",
+            index, width, height, fps, DEMOSAIC_TO_APPSINK
         );
 
         eprintln!("Creating GStreamer pipeline: {}", pipeline_str);
 
-        let pipeline = gst::parse::launch(&pipeline_str)
-            .map_err(|e| GstCameraError::PipelineError(e.to_string()))?
-            .dynamic_cast::<gst::Pipeline>()
-            .map_err(|_| GstCameraError::PipelineError("Not a pipeline".to_string()))?;
-
-        let appsink = pipeline
-            .by_name("sink")
-            .ok_or_else(|| GstCameraError::PipelineError("No appsink found".to_string()))?
-            .dynamic_cast::<gst_app::AppSink>()
-            .map_err(|_| GstCameraError::PipelineError("Not an appsink".to_string()))?;
-
-        Ok(Self {
-            pipeline,
-            appsink,
-            width,
-            height,
-        })
+        Self::launch(&pipeline_str, &format!("camera {}", index), width, height)
     }
 
     /// Create a GstCamera with explicit Bayer format (for advanced usage)
@@ -74,17 +73,42 @@ impl GstCamera {
         // Create pipeline for Bayer camera with conversion to RGB
         let pipeline_str = format!(
             "v4l2src device=/dev/video{} ! \
-             video/x-bayer,format={},width={},height={},framerate={}/1 ! \
-             bayer2rgb ! \
-             videoconvert ! \
-             video/x-raw,format=RGB ! \
-             appsink name=sink emit-signals=true sync=false max-buffers=1 drop=true",
-            index, bayer_format, width, height, fps
+             video/x-bayer,format={},width={},height={},framerate={}/1 ! {}",
+            index, bayer_format, width, height, fps, DEMOSAIC_TO_APPSINK
         );
 
         eprintln!("Creating GStreamer pipeline with format {}: {}", bayer_format, pipeline_str);
 
-        let pipeline = gst::parse::launch(&pipeline_str)
+        Self::launch(&pipeline_str, &format!("camera {}", index), width, height)
+    }
+
+    /// Create a GstCamera fed from an appsrc instead of v4l2src (simulated Bayer sensor).
+    /// Raw Bayer buffers go in through `push_raw`, RGB comes out of `get_frame` as usual.
+    /// `bayer_format` is a caps format such as "rggb" or "rggb16le".
+    pub fn new_simulated(name: &str, width: u32, height: u32, fps: u32, bayer_format: &str) -> Result<Self> {
+        gst::init().map_err(|e| GstCameraError::InitError(e.to_string()))?;
+
+        let pipeline_str = format!(
+            "appsrc name=src is-live=true format=time do-timestamp=true \
+             caps=video/x-bayer,format={},width={},height={},framerate={}/1 ! {}",
+            bayer_format, width, height, fps, DEMOSAIC_TO_APPSINK
+        );
+
+        eprintln!("Creating simulated Bayer pipeline: {}", pipeline_str);
+
+        let mut camera = Self::launch(&pipeline_str, name, width, height)?;
+        let appsrc = camera
+            .pipeline
+            .by_name("src")
+            .ok_or_else(|| GstCameraError::PipelineError("No appsrc found".to_string()))?
+            .dynamic_cast::<gst_app::AppSrc>()
+            .map_err(|_| GstCameraError::PipelineError("Not an appsrc".to_string()))?;
+        camera.appsrc = Some(appsrc);
+        Ok(camera)
+    }
+
+    fn launch(pipeline_str: &str, label: &str, width: u32, height: u32) -> Result<Self> {
+        let pipeline = gst::parse::launch(pipeline_str)
             .map_err(|e| GstCameraError::PipelineError(e.to_string()))?
             .dynamic_cast::<gst::Pipeline>()
             .map_err(|_| GstCameraError::PipelineError("Not a pipeline".to_string()))?;
@@ -95,11 +119,16 @@ impl GstCamera {
             .dynamic_cast::<gst_app::AppSink>()
             .map_err(|_| GstCameraError::PipelineError("Not an appsink".to_string()))?;
 
+        let health = BusWatcher::watch(label, &pipeline);
+
         Ok(Self {
             pipeline,
             appsink,
+            appsrc: None,
             width,
             height,
+            health,
+            frame_timeout: DEFAULT_FRAME_TIMEOUT,
         })
     }
 
@@ -116,10 +145,24 @@ impl GstCamera {
     }
 
     pub fn get_frame(&self) -> Result<Vec<u8>> {
-        let sample = self
-            .appsink
-            .pull_sample()
-            .map_err(|e| GstCameraError::FrameError(format!("Failed to pull sample: {}", e)))?;
+        // A failed pipeline never delivers another sample; report the bus error instead
+        if let Some(issue) = self.health.error() {
+            return Err(GstCameraError::PipelineFailed(issue.to_string()));
+        }
+
+        let timeout = gst::ClockTime::from_nseconds(self.frame_timeout.as_nanos() as u64);
+        let sample = match self.appsink.try_pull_sample(timeout) {
+            Some(sample) => sample,
+            None => {
+                return Err(match self.health.error() {
+                    Some(issue) => GstCameraError::PipelineFailed(issue.to_string()),
+                    None if self.appsink.is_eos() => {
+                        GstCameraError::PipelineFailed("End of stream (device removed?)".to_string())
+                    }
+                    None => GstCameraError::Timeout(self.frame_timeout),
+                });
+            }
+        };
 
         let buffer = sample
             .buffer()
@@ -143,6 +186,23 @@ impl GstCamera {
         Ok(data.to_vec())
     }
 
+    /// Feed one raw Bayer frame into a pipeline created with `new_simulated`
+    pub fn push_raw(&self, data: Vec<u8>) -> Result<()> {
+        let appsrc = self
+            .appsrc
+            .as_ref()
+            .ok_or_else(|| GstCameraError::FrameError("Not a simulated camera".to_string()))?;
+        appsrc
+            .push_buffer(gst::Buffer::from_mut_slice(data))
+            .map_err(|e| GstCameraError::FrameError(format!("Failed to push buffer: {:?}", e)))?;
+        Ok(())
+    }
+
+    /// Maximum wait in `get_frame` before returning `GstCameraError::Timeout`
+    pub fn set_frame_timeout(&mut self, timeout: std::time::Duration) {
+        self.frame_timeout = timeout;
+    }
+
     pub fn stop(&self) -> Result<()> {
         self.pipeline
             .set_state(gst::State::Null)
@@ -216,3 +276,27 @@ pub fn detect_bayer_format(index: u32) -> Option<String> {
 pub fn is_bayer_camera(index: u32) -> bool {
     detect_bayer_format(index).is_some()
 }
+
+/// Check if a camera can deliver a compressed MJPEG stream
+/// Returns true if v4l2 lists 'MJPG' among the supported formats (most USB webcams)
+pub fn is_mjpeg_camera(index: u32) -> bool {
+    use std::process::Command;
+
+    let device_path = format!("/dev/video{}", index);
+    match Command::new("v4l2-ctl")
+        .arg("--device")
+        .arg(&device_path)
+        .arg("--list-formats")
+        .output()
+    {
+        Ok(output) if output.status.success() => {
+            let info = String::from_utf8_lossy(&output.stdout);
+            info.contains("'MJPG'") || info.contains("Motion-JPEG")
+        }
+        Ok(_) => false,
+        Err(e) => {
+            eprintln!("Failed to check MJPEG support for {}: {}", device_path, e);
+            false
+        }
+    }
+}
@@ -1,18 +1,31 @@
 use gtk4::prelude::*;
 use gtk4::{
-    gdk_pixbuf::{Colorspace, Pixbuf},
-    glib, Application, ApplicationWindow, Box, Button, ComboBoxText, Entry, Image, Label,
-    Notebook, Orientation, ScrolledWindow, Separator, SpinButton, TextView,
+    gdk, gdk_pixbuf::{Colorspace, Pixbuf},
+    glib, Application, ApplicationWindow, Box, Button, CheckButton, ComboBoxText, DropTarget, Entry,
+    FileChooserAction, FileChooserDialog, FileFilter, Image, Label, Notebook, Orientation,
+    ResponseType, ScrolledWindow, Separator, SpinButton, TextView,
 };
 use glib::Bytes;
 use std::cell::RefCell;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::rc::Rc;
 
 use crate::camera::list_cameras;
-use crate::dual_recorder::{CameraSource, DualCameraRecorder};
+use crate::dual_recorder::{CameraSource, CaptureMode, DualCameraRecorder, QueueConfig};
+use crate::frame_queue::DropPolicy;
+use crate::fault_injection::FaultKind;
+use crate::frame_server::{default_socket_dir, socket_path, FrameServer};
+use crate::rtsp_server::{RtspConfig, RtspServer, RtspStream};
+use crate::recording_events::RecordingEvent;
+use crate::shutdown;
+use crate::passthrough_recorder::PassthroughContainer;
+use crate::pipeline_health::ErrorPolicy;
 use crate::player::list_recordings;
-use crate::playback_camera::StereoPlaybackSystem;
+use crate::playback_camera::{FramePosition, Pacing, StereoPlaybackSystem};
+use crate::image_sequence::IMAGE_EXTENSIONS;
+use crate::recording_sessions::{find_sessions, RecordingSession, VIDEO_EXTENSIONS};
+use crate::simulation_config::{LoopMode, PlaybackSettings, SimulationConfig, SyncBy};
+use crate::test_patterns::{PatternConfig, TestPattern, DEFAULT_SPEED};
 
 const APP_ID: &str = "com.github.fasttube.CamRecordSim";
 
@@ -66,6 +79,31 @@ fn build_ui(app: &Application) {
     main_box.append(&notebook);
 
     window.set_child(Some(&main_box));
+
+    // Ctrl-C/SIGTERM schließen das Fenster; abgeschlossen wird im close-request
+    let shutdown = shutdown::install();
+    let window_weak = window.downgrade();
+    glib::timeout_add_local(std::time::Duration::from_millis(200), move || {
+        if !shutdown.is_requested() {
+            return glib::ControlFlow::Continue;
+        }
+        if let Some(window) = window_weak.upgrade() {
+            window.close();
+        }
+        glib::ControlFlow::Break
+    });
+
+    window.connect_close_request(move |_| {
+        if recorder.borrow().is_busy() {
+            eprintln!("Fenster geschlossen, schließe laufende Aufnahme ab...");
+            let report = recorder.borrow_mut().shutdown(shutdown::SHUTDOWN_TIMEOUT);
+            for line in report.lines() {
+                eprintln!("{}", line);
+            }
+        }
+        glib::Propagation::Proceed
+    });
+
     window.present();
 }
 
@@ -102,6 +140,7 @@ fn create_dual_record_tab(recorder: Rc<RefCell<DualCameraRecorder>>, log_buffer:
     let camera_count = ComboBoxText::new();
     camera_count.append(Some("1"), "1 Camera");
     camera_count.append(Some("2"), "2 Cameras");
+    camera_count.append(Some("virtual"), "2 Virtual Cameras (test patterns)");
     camera_count.set_active(Some(0));
     cam_count_box.append(&cam_count_label);
     cam_count_box.append(&camera_count);
@@ -159,17 +198,41 @@ fn create_dual_record_tab(recorder: Rc<RefCell<DualCameraRecorder>>, log_buffer:
     cam_ids_box.append(&cam1_label);
     cam_ids_box.append(&cam1_combo);
 
+    left_col.append(&cam_ids_box);
+
+    let pattern_box = Box::new(Orientation::Vertical, 5);
+    let pattern_controls = [
+        PatternControls::new("Left pattern:", &pattern_box),
+        PatternControls::new("Right pattern:", &pattern_box),
+    ];
+    pattern_box.set_visible(false);
+    left_col.append(&pattern_box);
+
     let cam1_label_clone = cam1_label.clone();
     let cam1_combo_clone = cam1_combo.clone();
+    let cam_ids_box_clone = cam_ids_box.clone();
+    let pattern_box_clone = pattern_box.clone();
     camera_count.connect_changed(move |combo| {
         if let Some(id) = combo.active_id() {
             let show_cam1 = id.as_str() == "2";
             cam1_label_clone.set_visible(show_cam1);
             cam1_combo_clone.set_visible(show_cam1);
+            let is_virtual = id.as_str() == "virtual";
+            cam_ids_box_clone.set_visible(!is_virtual);
+            pattern_box_clone.set_visible(is_virtual);
         }
     });
 
-    left_col.append(&cam_ids_box);
+    let resolution_box = Box::new(Orientation::Horizontal, 5);
+    let width_spin = SpinButton::with_range(64.0, 3840.0, 16.0);
+    width_spin.set_value(640.0);
+    let height_spin = SpinButton::with_range(64.0, 2160.0, 16.0);
+    height_spin.set_value(480.0);
+    resolution_box.append(&Label::new(Some("Resolution:")));
+    resolution_box.append(&width_spin);
+    resolution_box.append(&Label::new(Some("x")));
+    resolution_box.append(&height_spin);
+    left_col.append(&resolution_box);
 
     let fps_box = Box::new(Orientation::Horizontal, 5);
     let fps_label = Label::new(Some("FPS:"));
@@ -196,6 +259,41 @@ fn create_dual_record_tab(recorder: Rc<RefCell<DualCameraRecorder>>, log_buffer:
     output_box.append(&output_entry);
     left_col.append(&output_box);
 
+    let capture_box = Box::new(Orientation::Horizontal, 5);
+    let capture_label = Label::new(Some("Capture path:"));
+    let capture_combo = ComboBoxText::new();
+    capture_combo.append(Some("appsrc"), "Standard (frames through application)");
+    capture_combo.append(Some("direct"), "Direct GStreamer pipeline (lower CPU)");
+    capture_combo.append(Some("passthrough"), "MJPEG passthrough (MKV, no re-encoding)");
+    capture_combo.set_active(Some(0));
+    capture_box.append(&capture_label);
+    capture_box.append(&capture_combo);
+    left_col.append(&capture_box);
+
+    let queue_box = Box::new(Orientation::Horizontal, 5);
+    let queue_label = Label::new(Some("Encoder queue:"));
+    let queue_depth_spin = SpinButton::with_range(1.0, 120.0, 1.0);
+    queue_depth_spin.set_value(QueueConfig::default().capacity as f64);
+    let queue_policy_combo = ComboBoxText::new();
+    queue_policy_combo.append(Some("block"), "Block capture");
+    queue_policy_combo.append(Some("drop-oldest"), "Drop oldest frame");
+    queue_policy_combo.append(Some("drop-newest"), "Drop newest frame");
+    queue_policy_combo.set_active(Some(0));
+    queue_box.append(&queue_label);
+    queue_box.append(&queue_depth_spin);
+    queue_box.append(&queue_policy_combo);
+    left_col.append(&queue_box);
+
+    let error_box = Box::new(Orientation::Horizontal, 5);
+    let error_label = Label::new(Some("On pipeline error:"));
+    let error_policy_combo = ComboBoxText::new();
+    error_policy_combo.append(Some("abort"), "Abort stream");
+    error_policy_combo.append(Some("restart"), "Restart into new file");
+    error_policy_combo.set_active(Some(0));
+    error_box.append(&error_label);
+    error_box.append(&error_policy_combo);
+    left_col.append(&error_box);
+
     settings_box.append(&left_col);
 
     tab_box.append(&settings_box);
@@ -250,7 +348,7 @@ fn create_dual_record_tab(recorder: Rc<RefCell<DualCameraRecorder>>, log_buffer:
     let camera_count_clone = camera_count.clone();
     camera_count.connect_changed(move |combo| {
         if let Some(id) = combo.active_id() {
-            right_preview_box_clone.set_visible(id.as_str() == "2");
+            right_preview_box_clone.set_visible(id.as_str() != "1");
         }
     });
 
@@ -262,11 +360,18 @@ fn create_dual_record_tab(recorder: Rc<RefCell<DualCameraRecorder>>, log_buffer:
     let fps_spin_clone = fps_spin.clone();
     let duration_spin_clone = duration_spin.clone();
     let output_entry_clone = output_entry.clone();
+    let capture_combo_clone = capture_combo.clone();
+    let queue_depth_spin_clone = queue_depth_spin.clone();
+    let queue_policy_combo_clone = queue_policy_combo.clone();
+    let error_policy_combo_clone = error_policy_combo.clone();
     let stop_btn_clone = stop_btn.clone();
     let status_label_clone = status_label.clone();
     let left_image_clone = left_image.clone();
     let right_image_clone = right_image.clone();
     let log_buffer_clone = log_buffer.clone();
+    let pattern_controls_clone = pattern_controls.clone();
+    let width_spin_clone = width_spin.clone();
+    let height_spin_clone = height_spin.clone();
 
     start_btn.connect_clicked(move |btn| {
         let cam_count = camera_count_clone.active_id().unwrap();
@@ -279,7 +384,14 @@ fn create_dual_record_tab(recorder: Rc<RefCell<DualCameraRecorder>>, log_buffer:
             .active_id()
             .and_then(|id| id.as_str().parse::<u32>().ok());
 
-        let source = if cam_count.as_str() == "1" {
+        let source = if cam_count.as_str() == "virtual" {
+            let patterns = [pattern_controls_clone[0].get(), pattern_controls_clone[1].get()];
+            log_message(
+                &log_buffer_clone,
+                &format!("Starting recording from virtual cameras ({}, {})", patterns[0], patterns[1]),
+            );
+            CameraSource::Virtual(patterns)
+        } else if cam_count.as_str() == "1" {
             if let Some(id) = cam0_id {
                 log_message(&log_buffer_clone, &format!("Starting recording from camera {}", id));
                 CameraSource::Single(id)
@@ -306,6 +418,37 @@ fn create_dual_record_tab(recorder: Rc<RefCell<DualCameraRecorder>>, log_buffer:
         let fps = fps_spin_clone.value();
         let duration = duration_spin_clone.value() as u64;
 
+        let capture_mode = match capture_combo_clone.active_id().as_deref() {
+            Some("direct") => CaptureMode::Direct,
+            Some("passthrough") => CaptureMode::Passthrough(PassthroughContainer::Mkv),
+            _ => CaptureMode::AppSrc,
+        };
+        log_message(&log_buffer_clone, &format!("Capture path: {:?}", capture_mode));
+        recorder_clone.borrow_mut().set_capture_mode(capture_mode);
+
+        let queue_config = QueueConfig {
+            capacity: queue_depth_spin_clone.value() as usize,
+            policy: queue_policy_combo_clone
+                .active_id()
+                .and_then(|id| id.as_str().parse::<DropPolicy>().ok())
+                .unwrap_or(DropPolicy::Block),
+        };
+        log_message(
+            &log_buffer_clone,
+            &format!("Encoder queue: {} frames, policy {}", queue_config.capacity, queue_config.policy),
+        );
+        recorder_clone.borrow_mut().set_queue_config(queue_config);
+
+        let error_policy = error_policy_combo_clone
+            .active_id()
+            .and_then(|id| id.as_str().parse::<ErrorPolicy>().ok())
+            .unwrap_or(ErrorPolicy::Abort);
+        recorder_clone.borrow_mut().set_error_policy(error_policy);
+
+        let (width, height) = (width_spin_clone.value() as u32, height_spin_clone.value() as u32);
+        log_message(&log_buffer_clone, &format!("Resolution: {}x{}", width, height));
+        recorder_clone.borrow_mut().set_resolution(width, height);
+
         match recorder_clone
             .borrow_mut()
             .start_recording(source, &output_dir, fps, duration)
@@ -319,6 +462,7 @@ fn create_dual_record_tab(recorder: Rc<RefCell<DualCameraRecorder>>, log_buffer:
                 let recorder_preview = recorder_clone.clone();
                 let left_img = left_image_clone.clone();
                 let right_img = right_image_clone.clone();
+                let (preview_w, preview_h) = recorder_clone.borrow().preview_resolution();
 
                 glib::timeout_add_local(std::time::Duration::from_millis(33), move || {
                     let rec = recorder_preview.borrow();
@@ -328,13 +472,13 @@ fn create_dual_record_tab(recorder: Rc<RefCell<DualCameraRecorder>>, log_buffer:
                     }
 
                     if let Some(frame) = rec.get_left_frame() {
-                        if let Some(pixbuf) = frame_to_pixbuf(&frame, 640, 480) {
+                        if let Some(pixbuf) = frame_to_pixbuf(&frame, preview_w as i32, preview_h as i32) {
                             left_img.set_from_pixbuf(Some(&pixbuf));
                         }
                     }
 
                     if let Some(frame) = rec.get_right_frame() {
-                        if let Some(pixbuf) = frame_to_pixbuf(&frame, 640, 480) {
+                        if let Some(pixbuf) = frame_to_pixbuf(&frame, preview_w as i32, preview_h as i32) {
                             right_img.set_from_pixbuf(Some(&pixbuf));
                         }
                     }
@@ -361,6 +505,45 @@ fn create_dual_record_tab(recorder: Rc<RefCell<DualCameraRecorder>>, log_buffer:
         status_label_clone2.set_label("Recording stopped");
     });
 
+    // Statusmeldungen der Aufnahme-Threads (Start, Stats, Warnungen, Fehler, Ende)
+    let events = recorder.borrow().events();
+    let start_btn_events = start_btn.clone();
+    let stop_btn_events = stop_btn.clone();
+    let status_label_events = status_label.clone();
+    let log_buffer_events = log_buffer.clone();
+
+    glib::spawn_future_local(async move {
+        let mut had_error = false;
+
+        while let Ok(event) = events.recv().await {
+            log_message(&log_buffer_events, &event.describe());
+
+            match &event {
+                RecordingEvent::Started { .. } => {
+                    had_error = false;
+                    status_label_events.set_label("Recording...");
+                }
+                RecordingEvent::Stats { .. } => {
+                    if !had_error {
+                        status_label_events.set_label(&format!("Recording... {}", event.describe()));
+                    }
+                }
+                RecordingEvent::Error { .. } => {
+                    had_error = true;
+                    status_label_events.set_label(&event.describe());
+                }
+                RecordingEvent::Finished => {
+                    start_btn_events.set_sensitive(true);
+                    stop_btn_events.set_sensitive(false);
+                    if !had_error {
+                        status_label_events.set_label("Recording finished");
+                    }
+                }
+                _ => {}
+            }
+        }
+    });
+
     tab_box
 }
 
@@ -381,7 +564,7 @@ fn frame_to_pixbuf(frame: &[u8], width: i32, height: i32) -> Option<Pixbuf> {
     ))
 }
 
-fn create_simulation_tab(_log_buffer: LogBuffer) -> Box {
+fn create_simulation_tab(log_buffer: LogBuffer) -> Box {
     let tab_box = Box::new(Orientation::Vertical, 10);
     tab_box.set_margin_start(10);
     tab_box.set_margin_end(10);
@@ -390,10 +573,10 @@ fn create_simulation_tab(_log_buffer: LogBuffer) -> Box {
 
     let desc = Label::new(Some(
         "<b>Virtual Camera Simulation:</b>\n\
-        Select a folder containing recordings. Videos will be played as virtual cameras:\n\
-        • First file → Left virtual camera (loop)\n\
-        • Second file → Right virtual camera (loop)\n\n\
-        Other applications can use these virtual cameras like real cameras!",
+        Select a recording session or a KITTI / EuRoC / TUM dataset from a folder, or pick the\n\
+        left and right videos yourself \
+        (file buttons or drag and drop onto a preview). Videos will be played as virtual cameras.\n\n\
+        Other applications can use these virtual cameras like real cameras via the frame server!",
     ));
     desc.set_use_markup(true);
     desc.set_xalign(0.0);
@@ -418,6 +601,22 @@ fn create_simulation_tab(_log_buffer: LogBuffer) -> Box {
 
     tab_box.append(&folder_box);
 
+    let session_box = Box::new(Orientation::Horizontal, 5);
+    session_box.append(&Label::new(Some("Session:")));
+    let session_combo = ComboBoxText::new();
+    session_combo.set_hexpand(true);
+    session_box.append(&session_combo);
+
+    let left_file_btn = Button::with_label("Left file…");
+    let right_file_btn = Button::with_label("Right file…");
+    let swap_btn = Button::with_label("Swap L/R");
+    swap_btn.set_sensitive(false);
+    session_box.append(&left_file_btn);
+    session_box.append(&right_file_btn);
+    session_box.append(&swap_btn);
+
+    tab_box.append(&session_box);
+
     let status_box = Box::new(Orientation::Vertical, 5);
     let left_status = Label::new(Some("<i>Left camera: Not loaded</i>"));
     left_status.set_use_markup(true);
@@ -451,12 +650,88 @@ fn create_simulation_tab(_log_buffer: LogBuffer) -> Box {
     let sim_status = Label::new(Some("Stopped"));
     sim_status.set_margin_start(20);
 
+    let pacing_combo = ComboBoxText::new();
+    pacing_combo.append(Some("realtime"), "Real-time (PTS)");
+    pacing_combo.append(Some("recorded"), "Recorded timestamps (original jitter)");
+    pacing_combo.append(Some("fast"), "As fast as possible");
+    pacing_combo.set_active(Some(0));
+
+    let publish_check = CheckButton::with_label("Frame server");
+    publish_check.set_tooltip_text(Some(&format!(
+        "Publish the simulated cameras for other processes:\n{}",
+        socket_path(&default_socket_dir(), "left").display()
+    )));
+
     button_box.append(&start_sim_btn);
     button_box.append(&stop_sim_btn);
+    button_box.append(&pacing_combo);
+    let rtsp_check = CheckButton::with_label("RTSP");
+    rtsp_check.set_tooltip_text(Some(&format!(
+        "Serve the simulated cameras via RTSP on localhost ({}, latency {} ms):\nrtsp://127.0.0.1:PORT/left",
+        RtspConfig::default().codec,
+        RtspConfig::default().latency_ms,
+    )));
+    let rtsp_port_spin = SpinButton::with_range(1024.0, 65535.0, 1.0);
+    rtsp_port_spin.set_value(RtspConfig::default().port as f64);
+    rtsp_port_spin.set_tooltip_text(Some("RTSP port"));
+
+    button_box.append(&publish_check);
+    button_box.append(&rtsp_check);
+    button_box.append(&rtsp_port_spin);
     button_box.append(&sim_status);
 
     tab_box.append(&button_box);
 
+    let frame_box = Box::new(Orientation::Horizontal, 5);
+
+    let pause_btn = Button::with_label("Pause");
+    let step_back_btn = Button::with_label("◀ Frame");
+    let step_fwd_btn = Button::with_label("Frame ▶");
+    let seek_spin = SpinButton::with_range(0.0, 10_000_000.0, 1.0);
+    seek_spin.set_digits(0);
+    let seek_btn = Button::with_label("Go to frame");
+    let position_label = Label::new(Some("Frame: -"));
+    position_label.set_margin_start(20);
+
+    for widget in [&pause_btn, &step_back_btn, &step_fwd_btn, &seek_btn] {
+        widget.set_sensitive(false);
+    }
+
+    frame_box.append(&pause_btn);
+    frame_box.append(&step_back_btn);
+    frame_box.append(&step_fwd_btn);
+    frame_box.append(&seek_spin);
+    frame_box.append(&seek_btn);
+    frame_box.append(&position_label);
+
+    tab_box.append(&frame_box);
+
+    tab_box.append(&Separator::new(Orientation::Horizontal));
+
+    let settings_label = Label::new(Some(
+        "<b>Playback Settings</b> (saved as simulation.json in the recordings folder):",
+    ));
+    settings_label.set_use_markup(true);
+    settings_label.set_xalign(0.0);
+    tab_box.append(&settings_label);
+
+    let left_controls = PlaybackControls::new("Left:", &tab_box);
+    let right_controls = PlaybackControls::new("Right:", &tab_box);
+
+    let apply_box = Box::new(Orientation::Horizontal, 5);
+    apply_box.append(&Label::new(Some("Stereo sync:")));
+    let sync_combo = ComboBoxText::new();
+    sync_combo.append(Some("timestamp"), "Lock-step by timestamp");
+    sync_combo.append(Some("frame-index"), "Lock-step by frame index");
+    sync_combo.append(Some("independent"), "Independent");
+    sync_combo.set_active(Some(0));
+    apply_box.append(&sync_combo);
+
+    let apply_btn = Button::with_label("Apply & Save");
+    apply_btn.set_sensitive(false);
+    apply_box.append(&apply_btn);
+    tab_box.append(&apply_box);
+
     tab_box.append(&Separator::new(Orientation::Horizontal));
 
     let preview_label = Label::new(Some("<b>Virtual Camera Preview:</b>"));
@@ -489,36 +764,263 @@ fn create_simulation_tab(_log_buffer: LogBuffer) -> Box {
     let stereo_system: Rc<RefCell<Option<StereoPlaybackSystem>>> = Rc::new(RefCell::new(None));
     let is_running = Rc::new(RefCell::new(false));
 
+    let sessions: Rc<RefCell<Vec<RecordingSession>>> = Rc::new(RefCell::new(Vec::new()));
+    // Frame-Server und RTSP, solange die Häkchen gesetzt und Videos geladen sind
+    let outputs: Rc<RefCell<SimulationOutputs>> = Rc::new(RefCell::new(SimulationOutputs::default()));
+    let settings = SimulationControls {
+        pacing: pacing_combo.clone(),
+        left: left_controls,
+        right: right_controls,
+        sync: sync_combo,
+    };
+
+    // Nach jedem Wechsel der Videos: Einstellungen anwenden, Status und Buttons aktualisieren
+    let refresh: Rc<dyn Fn()> = {
+        let stereo_system = stereo_system.clone();
+        let settings = settings.clone();
+        let folder_entry = folder_entry.clone();
+        let left_status = left_status.clone();
+        let right_status = right_status.clone();
+        let log_buffer = log_buffer.clone();
+        let publish_check = publish_check.clone();
+        let rtsp_check = rtsp_check.clone();
+        let rtsp_port_spin = rtsp_port_spin.clone();
+        let outputs = outputs.clone();
+        let buttons = [
+            start_sim_btn.clone(),
+            swap_btn.clone(),
+            pause_btn.clone(),
+            step_back_btn.clone(),
+            step_fwd_btn.clone(),
+            seek_btn.clone(),
+            apply_btn.clone(),
+        ];
+
+        Rc::new(move || {
+            let mut guard = stereo_system.borrow_mut();
+            let Some(system) = guard.as_mut() else {
+                return;
+            };
+
+            let folder_path = PathBuf::from(folder_entry.text().as_str());
+            if let Err(e) = system.apply_config(&settings.config(&folder_path)) {
+                log_message(&log_buffer, &format!("Playback settings not applied: {}", e));
+            }
+
+            if publish_check.is_active() || rtsp_check.is_active() {
+                outputs.borrow_mut().restart(
+                    system,
+                    publish_check.is_active(),
+                    rtsp_config(&rtsp_check, &rtsp_port_spin),
+                    &log_buffer,
+                );
+            }
+
+            let status = system.get_status();
+            let lines: Vec<&str> = status.lines().collect();
+            if lines.len() >= 2 {
+                left_status.set_markup(&format!("<b>{}</b>", glib::markup_escape_text(lines[0])));
+                right_status.set_markup(&format!("<b>{}</b>", glib::markup_escape_text(lines[1])));
+            }
+
+            for btn in &buttons {
+                btn.set_sensitive(true);
+            }
+        })
+    };
+
+    let show_load_error = {
+        let left_status = left_status.clone();
+        let right_status = right_status.clone();
+        move |side: Option<Side>, error: String| {
+            let message = format!(
+                "<span foreground='red'>{}</span>",
+                glib::markup_escape_text(&error)
+            );
+            match side {
+                Some(Side::Left) => left_status.set_markup(&message),
+                Some(Side::Right) => right_status.set_markup(&message),
+                None => {
+                    left_status.set_markup("<span foreground='red'><i>Error loading</i></span>");
+                    right_status.set_markup(&message);
+                }
+            }
+        }
+    };
+
     let folder_entry_clone = folder_entry.clone();
-    let left_status_clone = left_status.clone();
-    let right_status_clone = right_status.clone();
-    let start_sim_btn_clone = start_sim_btn.clone();
-    let stereo_system_clone = stereo_system.clone();
+    let sessions_load = sessions.clone();
+    let session_combo_load = session_combo.clone();
+    let settings_load = settings.clone();
+    let log_buffer_load = log_buffer.clone();
+    let show_error = show_load_error.clone();
 
     load_btn.connect_clicked(move |_| {
         let folder_path = PathBuf::from(folder_entry_clone.text().as_str());
 
-        match StereoPlaybackSystem::load_from_directory(&folder_path) {
+        let found = match find_sessions(&folder_path) {
+            Ok(found) if !found.is_empty() => found,
+            Ok(_) => {
+                show_error(None, "No recordings found in this folder".to_string());
+                return;
+            }
+            Err(e) => {
+                show_error(None, e.to_string());
+                return;
+            }
+        };
+
+        let config = SimulationConfig::load(&folder_path).unwrap_or_else(|e| {
+            log_message(&log_buffer_load, &format!("Simulation config ignored: {}", e));
+            SimulationConfig::default()
+        });
+        settings_load.set(&config);
+
+        session_combo_load.remove_all();
+        for (i, session) in found.iter().enumerate() {
+            session_combo_load.append(Some(&i.to_string()), &session.label());
+        }
+        *sessions_load.borrow_mut() = found;
+        // Lädt die neueste Session über connect_changed
+        session_combo_load.set_active(Some(0));
+    });
+
+    let stereo_system_session = stereo_system.clone();
+    let refresh_session = refresh.clone();
+    let show_error = show_load_error.clone();
+    let log_buffer_session = log_buffer.clone();
+    session_combo.connect_changed(move |combo| {
+        let Some(index) = combo.active() else {
+            return;
+        };
+        let loaded = match sessions.borrow().get(index as usize) {
+            Some(session) => {
+                // Kalibrierung der Datensätze als Tooltip und im Log
+                let calibration: Vec<String> = session
+                    .calibration
+                    .iter()
+                    .map(|path| path.display().to_string())
+                    .collect();
+                if calibration.is_empty() {
+                    combo.set_tooltip_text(None);
+                } else {
+                    combo.set_tooltip_text(Some(&format!("Calibration:\n{}", calibration.join("\n"))));
+                    log_message(
+                        &log_buffer_session,
+                        &format!("{} calibration: {}", session.label(), calibration.join(", ")),
+                    );
+                }
+                StereoPlaybackSystem::load_session(session)
+            }
+            None => return,
+        };
+
+        match loaded {
             Ok(system) => {
-                let status = system.get_status();
-                let lines: Vec<&str> = status.lines().collect();
+                *stereo_system_session.borrow_mut() = Some(system);
+                refresh_session();
+                println!("Stereo system loaded successfully!");
+            }
+            Err(e) => show_error(None, e.to_string()),
+        }
+    });
 
-                if lines.len() >= 2 {
-                    left_status_clone.set_markup(&format!("<b>{}</b>", lines[0]));
-                    right_status_clone.set_markup(&format!("<b>{}</b>", lines[1]));
+    // Einzelne Seite aus Datei-Dialog oder Drag and Drop
+    let load_side: Rc<dyn Fn(Side, PathBuf)> = {
+        let stereo_system = stereo_system.clone();
+        let refresh = refresh.clone();
+        let show_error = show_load_error.clone();
+        Rc::new(move |side: Side, path: PathBuf| {
+            // Ein einzelnes Bild steht für die Bildfolge in seinem Verzeichnis
+            let path = match path.parent() {
+                Some(dir) if is_image_file(&path) => dir.to_path_buf(),
+                _ => path,
+            };
+            let result = {
+                let mut guard = stereo_system.borrow_mut();
+                let system = guard.get_or_insert_with(StereoPlaybackSystem::new);
+                match side {
+                    Side::Left => system.set_left_camera(&path),
+                    Side::Right => system.set_right_camera(&path),
                 }
+            };
 
-                *stereo_system_clone.borrow_mut() = Some(system);
-                start_sim_btn_clone.set_sensitive(true);
+            match result {
+                Ok(()) => refresh(),
+                Err(e) => show_error(Some(side), e.to_string()),
+            }
+        })
+    };
+
+    for (btn, side) in [(&left_file_btn, Side::Left), (&right_file_btn, Side::Right)] {
+        let load_side = load_side.clone();
+        btn.connect_clicked(move |btn| {
+            let load_side = load_side.clone();
+            pick_video_file(btn, side, move |path| load_side(side, path));
+        });
+    }
 
-                println!("Stereo system loaded successfully!");
+    for (widget, side) in [(&left_preview_box, Side::Left), (&right_preview_box, Side::Right)] {
+        let load_side = load_side.clone();
+        accept_video_drop(widget, move |path| load_side(side, path));
+    }
+
+    for check in [&publish_check, &rtsp_check] {
+        let stereo_system = stereo_system.clone();
+        let outputs = outputs.clone();
+        let log_buffer = log_buffer.clone();
+        let publish_check = publish_check.clone();
+        let rtsp_check = rtsp_check.clone();
+        let rtsp_port_spin = rtsp_port_spin.clone();
+        check.connect_toggled(move |check| {
+            if !check.is_active() {
+                log_message(&log_buffer, &format!("{} stopped", check.label().unwrap_or_default()));
             }
-            Err(e) => {
-                left_status_clone.set_markup("<span foreground='red'><i>Error loading</i></span>");
-                right_status_clone.set_markup(&format!("<span foreground='red'>{}</span>", e));
-                start_sim_btn_clone.set_sensitive(false);
+            let mut outputs = outputs.borrow_mut();
+            match stereo_system.borrow().as_ref() {
+                Some(system) => outputs.restart(
+                    system,
+                    publish_check.is_active(),
+                    rtsp_config(&rtsp_check, &rtsp_port_spin),
+                    &log_buffer,
+                ),
+                None => *outputs = SimulationOutputs::default(),
+            }
+        });
+    }
+
+    {
+        let stereo_system = stereo_system.clone();
+        let outputs = outputs.clone();
+        let log_buffer = log_buffer.clone();
+        let publish_check = publish_check.clone();
+        let rtsp_check = rtsp_check.clone();
+        rtsp_port_spin.connect_value_changed(move |spin| {
+            // Neuer Port erst wirksam, wenn der Server neu startet
+            if !rtsp_check.is_active() {
+                return;
+            }
+            if let Some(system) = stereo_system.borrow().as_ref() {
+                outputs.borrow_mut().restart(
+                    system,
+                    publish_check.is_active(),
+                    rtsp_config(&rtsp_check, spin),
+                    &log_buffer,
+                );
+            }
+        });
+    }
+
+    let stereo_system_swap = stereo_system.clone();
+    let refresh_swap = refresh.clone();
+    let log_buffer_swap = log_buffer.clone();
+    swap_btn.connect_clicked(move |_| {
+        if let Some(system) = stereo_system_swap.borrow_mut().as_mut() {
+            if let Err(e) = system.swap() {
+                log_message(&log_buffer_swap, &format!("Swap: playback settings not applied: {}", e));
             }
         }
+        refresh_swap();
     });
 
     let stereo_system_clone2 = stereo_system.clone();
@@ -527,6 +1029,10 @@ fn create_simulation_tab(_log_buffer: LogBuffer) -> Box {
     let sim_status_clone = sim_status.clone();
     let left_preview_image_clone = left_preview_image.clone();
     let right_preview_image_clone = right_preview_image.clone();
+    let pacing_combo_clone = pacing_combo.clone();
+    let position_label_clone = position_label.clone();
+    let outputs_clone = outputs.clone();
+    let log_buffer_faults = log_buffer.clone();
 
     start_sim_btn.connect_clicked(move |btn| {
         if stereo_system_clone2.borrow().is_none() {
@@ -534,6 +1040,14 @@ fn create_simulation_tab(_log_buffer: LogBuffer) -> Box {
             return;
         }
 
+        let pacing = pacing_combo_clone
+            .active_id()
+            .and_then(|id| id.as_str().parse::<Pacing>().ok())
+            .unwrap_or(Pacing::RealTime);
+        if let Some(system) = stereo_system_clone2.borrow_mut().as_mut() {
+            system.set_pacing(pacing);
+        }
+
         *is_running_clone.borrow_mut() = true;
         btn.set_sensitive(false);
         stop_sim_btn_clone.set_sensitive(true);
@@ -545,24 +1059,41 @@ fn create_simulation_tab(_log_buffer: LogBuffer) -> Box {
         let is_running_preview = is_running_clone.clone();
         let left_img = left_preview_image_clone.clone();
         let right_img = right_preview_image_clone.clone();
+        let position_label = position_label_clone.clone();
+        let outputs = outputs_clone.clone();
+        let log_buffer = log_buffer_faults.clone();
 
-        glib::timeout_add_local(std::time::Duration::from_millis(33), move || {
+        // Kurzer Timer, die Frames kommen nach ihrem Zeitstempel (poll blockiert nicht)
+        glib::timeout_add_local(std::time::Duration::from_millis(5), move || {
             if !*is_running_preview.borrow() {
                 return glib::ControlFlow::Break;
             }
 
             if let Some(system) = stereo_clone.borrow_mut().as_mut() {
-                if let Ok(frame) = system.get_left_frame() {
-                    if let Some(pixbuf) = frame_to_pixbuf(&frame, 640, 480) {
-                        left_img.set_from_pixbuf(Some(&pixbuf));
+                if let Ok((left, right)) = system.poll_frames() {
+                    let (left_pos, right_pos) = system.get_positions();
+                    let outputs = outputs.borrow();
+                    for (side, frame, position) in [(0, &left, left_pos), (1, &right, right_pos)] {
+                        if let Some(frame) = frame {
+                            outputs.publish(side, frame, position.map(|p| p.pts).unwrap_or_default());
+                        }
+                    }
+
+                    for (frame, img) in [(left, &left_img), (right, &right_img)] {
+                        if let Some(pixbuf) = frame.and_then(|f| frame_to_pixbuf(&f, 640, 480)) {
+                            img.set_from_pixbuf(Some(&pixbuf));
+                        }
                     }
                 }
 
-                if let Ok(frame) = system.get_right_frame() {
-                    if let Some(pixbuf) = frame_to_pixbuf(&frame, 640, 480) {
-                        right_img.set_from_pixbuf(Some(&pixbuf));
+                // Jitter trifft fast jeden Frame, im Log stehen nur die seltenen Fehler
+                for event in system.fault_log().take_unreported() {
+                    if !matches!(event.kind, FaultKind::Delayed { .. }) {
+                        log_message(&log_buffer, &format!("Fault: {}", event));
                     }
                 }
+
+                position_label.set_label(&format_positions(system));
             }
 
             glib::ControlFlow::Continue
@@ -582,9 +1113,444 @@ fn create_simulation_tab(_log_buffer: LogBuffer) -> Box {
         println!("Simulation stopped");
     });
 
+    let stereo_system_apply = stereo_system.clone();
+    let folder_entry_apply = folder_entry.clone();
+    let sim_status_apply = sim_status.clone();
+    apply_btn.connect_clicked(move |_| {
+        let folder_path = PathBuf::from(folder_entry_apply.text().as_str());
+        let config = settings.config(&folder_path);
+
+        if let Some(system) = stereo_system_apply.borrow_mut().as_mut() {
+            if let Err(e) = system.apply_config(&config) {
+                sim_status_apply.set_label(&format!("Error: {}", e));
+                return;
+            }
+        }
+
+        match config.save(&folder_path) {
+            Ok(()) => log_message(
+                &log_buffer,
+                &format!("Simulation config saved: {}", SimulationConfig::path(&folder_path).display()),
+            ),
+            Err(e) => sim_status_apply.set_label(&format!("Error: {}", e)),
+        }
+    });
+
+    let stereo_system_pause = stereo_system.clone();
+    let position_label_pause = position_label.clone();
+    pause_btn.connect_clicked(move |btn| {
+        if let Some(system) = stereo_system_pause.borrow_mut().as_mut() {
+            if system.is_paused() {
+                system.resume();
+                btn.set_label("Pause");
+            } else {
+                system.pause();
+                btn.set_label("Resume");
+            }
+            position_label_pause.set_label(&format_positions(system));
+        }
+    });
+
+    // Einzelschritte und Springen pausieren die Wiedergabe und zeigen den Frame sofort an
+    let frame_step = {
+        let stereo_system = stereo_system.clone();
+        let is_running = is_running.clone();
+        let pause_btn = pause_btn.clone();
+        let sim_status = sim_status.clone();
+        let position_label = position_label.clone();
+        let left_img = left_preview_image.clone();
+        let right_img = right_preview_image.clone();
+
+        Rc::new(move |action: FrameAction| {
+            let mut guard = stereo_system.borrow_mut();
+            let Some(system) = guard.as_mut() else {
+                return;
+            };
+
+            let result = match action {
+                FrameAction::Forward => system.step_forward(),
+                FrameAction::Backward => system.step_backward(),
+                FrameAction::Seek(index) => system.seek_to_frame(index).and_then(|_| {
+                    if *is_running.borrow() && !system.is_paused() {
+                        Ok((None, None))
+                    } else {
+                        system.step_forward()
+                    }
+                }),
+            };
+
+            match result {
+                Ok((left, right)) => {
+                    for (frame, img) in [(left, &left_img), (right, &right_img)] {
+                        if let Some(pixbuf) = frame.and_then(|f| frame_to_pixbuf(&f, 640, 480)) {
+                            img.set_from_pixbuf(Some(&pixbuf));
+                        }
+                    }
+                }
+                Err(e) => sim_status.set_label(&format!("Error: {}", e)),
+            }
+
+            if system.is_paused() {
+                pause_btn.set_label("Resume");
+            }
+            position_label.set_label(&format_positions(system));
+        })
+    };
+
+    let step = frame_step.clone();
+    step_fwd_btn.connect_clicked(move |_| step(FrameAction::Forward));
+    let step = frame_step.clone();
+    step_back_btn.connect_clicked(move |_| step(FrameAction::Backward));
+    let seek_spin_clone = seek_spin.clone();
+    seek_btn.connect_clicked(move |_| {
+        frame_step(FrameAction::Seek(seek_spin_clone.value() as u64));
+    });
+
     tab_box
 }
 
+#[derive(Clone, Copy)]
+enum Side {
+    Left,
+    Right,
+}
+
+/// Datei-Dialog für ein Video einer Seite
+fn pick_video_file(anchor: &Button, side: Side, on_pick: impl Fn(PathBuf) + 'static) {
+    let title = match side {
+        Side::Left => "Select left video",
+        Side::Right => "Select right video",
+    };
+    let parent = anchor.root().and_downcast::<gtk4::Window>();
+    let dialog = FileChooserDialog::new(
+        Some(title),
+        parent.as_ref(),
+        FileChooserAction::Open,
+        &[("Cancel", ResponseType::Cancel), ("Open", ResponseType::Accept)],
+    );
+    dialog.set_modal(true);
+
+    let filter = FileFilter::new();
+    filter.set_name(Some("Videos"));
+    for extension in VIDEO_EXTENSIONS {
+        filter.add_suffix(extension);
+    }
+    dialog.add_filter(&filter);
+
+    let images = FileFilter::new();
+    images.set_name(Some("Image sequence (any frame)"));
+    for extension in IMAGE_EXTENSIONS {
+        images.add_suffix(extension);
+    }
+    dialog.add_filter(&images);
+
+    dialog.connect_response(move |dialog, response| {
+        if response == ResponseType::Accept {
+            if let Some(path) = dialog.file().and_then(|file| file.path()) {
+                on_pick(path);
+            }
+        }
+        dialog.close();
+    });
+    dialog.present();
+}
+
+/// Ausgaben der Simulation für andere Prozesse, je links und rechts
+#[derive(Default)]
+struct SimulationOutputs {
+    frame_servers: [Option<FrameServer>; 2],
+    rtsp: Option<RtspServer>,
+    rtsp_streams: [Option<RtspStream>; 2],
+}
+
+impl SimulationOutputs {
+    /// Startet die gewählten Ausgaben mit der Auflösung der geladenen Aufnahmen neu
+    fn restart(
+        &mut self,
+        system: &StereoPlaybackSystem,
+        frame_server: bool,
+        rtsp: Option<RtspConfig>,
+        log_buffer: &LogBuffer,
+    ) {
+        // Alte Sockets und Port erst freigeben, dann neu binden
+        *self = Self::default();
+
+        if let Some(config) = rtsp {
+            match RtspServer::start(config) {
+                Ok(server) => self.rtsp = Some(server),
+                Err(e) => log_message(log_buffer, &format!("RTSP server failed: {}", e)),
+            }
+        }
+
+        let (left, right) = system.get_media_infos();
+        for (side, (info, name)) in [left, right].into_iter().zip(["left", "right"]).enumerate() {
+            let Some(info) = info else {
+                continue;
+            };
+
+            if frame_server {
+                let path = socket_path(&default_socket_dir(), name);
+                match FrameServer::start(&path, name, info.width, info.height, info.fps) {
+                    Ok(server) => {
+                        log_message(
+                            log_buffer,
+                            &format!("Frame server {}: {}", name, server.socket_path().display()),
+                        );
+                        self.frame_servers[side] = Some(server);
+                    }
+                    Err(e) => log_message(log_buffer, &format!("Frame server {} failed: {}", name, e)),
+                }
+            }
+
+            if let Some(rtsp) = &self.rtsp {
+                match rtsp.add_stream(name, info.width, info.height, info.fps) {
+                    Ok(stream) => {
+                        log_message(log_buffer, &format!("RTSP {}: {}", name, stream.url()));
+                        self.rtsp_streams[side] = Some(stream);
+                    }
+                    Err(e) => log_message(log_buffer, &format!("RTSP {} failed: {}", name, e)),
+                }
+            }
+        }
+    }
+
+    fn publish(&self, side: usize, frame: &[u8], pts: std::time::Duration) {
+        if let Some(server) = &self.frame_servers[side] {
+            if let Err(e) = server.publish(frame, pts) {
+                eprintln!("Frame server: {}", e);
+            }
+        }
+        if let Some(stream) = &self.rtsp_streams[side] {
+            if let Err(e) = stream.publish(frame) {
+                eprintln!("RTSP: {}", e);
+            }
+        }
+    }
+}
+
+/// RTSP-Einstellungen aus dem Simulations-Tab, None = aus
+fn rtsp_config(check: &CheckButton, port: &SpinButton) -> Option<RtspConfig> {
+    check.is_active().then(|| RtspConfig {
+        port: port.value() as u16,
+        ..RtspConfig::default()
+    })
+}
+
+fn is_image_file(path: &Path) -> bool {
+    path.is_file()
+        && path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| IMAGE_EXTENSIONS.contains(&ext.to_lowercase().as_str()))
+}
+
+/// Nimmt per Drag and Drop abgelegte Dateien oder Bild-Verzeichnisse an (die erste zählt)
+fn accept_video_drop(widget: &Box, on_drop: impl Fn(PathBuf) + 'static) {
+    let target = DropTarget::new(gdk::FileList::static_type(), gdk::DragAction::COPY);
+    target.connect_drop(move |_, value, _, _| {
+        let path = value
+            .get::<gdk::FileList>()
+            .ok()
+            .and_then(|list| list.files().first().and_then(|file| file.path()));
+        match path {
+            Some(path) => {
+                on_drop(path);
+                true
+            }
+            None => false,
+        }
+    });
+    widget.add_controller(target);
+}
+
+/// Alle Einstellungs-Widgets des Simulations-Tabs, die in `simulation.json` landen
+#[derive(Clone)]
+struct SimulationControls {
+    pacing: ComboBoxText,
+    left: PlaybackControls,
+    right: PlaybackControls,
+    sync: ComboBoxText,
+}
+
+impl SimulationControls {
+    fn set(&self, config: &SimulationConfig) {
+        self.left.set(&config.left);
+        self.right.set(&config.right);
+        self.pacing.set_active_id(Some(&config.pacing.to_string()));
+        if config.lock_step {
+            self.sync.set_active_id(Some(&config.sync_by.to_string()));
+        } else {
+            self.sync.set_active_id(Some("independent"));
+        }
+    }
+
+    /// Konfiguration aus den Widgets; Felder ohne Eingabe im GUI (z.B. sync_offset_ms)
+    /// kommen aus der gespeicherten Datei
+    fn config(&self, folder_path: &Path) -> SimulationConfig {
+        let mut config = SimulationConfig::load(folder_path).unwrap_or_default();
+        config.pacing = self
+            .pacing
+            .active_id()
+            .and_then(|id| id.as_str().parse::<Pacing>().ok())
+            .unwrap_or(Pacing::RealTime);
+        config.left = self.left.get();
+        config.right = self.right.get();
+
+        let sync_by = self
+            .sync
+            .active_id()
+            .and_then(|id| id.as_str().parse::<SyncBy>().ok());
+        config.lock_step = sync_by.is_some();
+        if let Some(sync_by) = sync_by {
+            config.sync_by = sync_by;
+        }
+        config
+    }
+}
+
+/// Eingabefelder für die Abspiel-Einstellungen einer simulierten Kamera
+#[derive(Clone)]
+struct PlaybackControls {
+    rate: SpinButton,
+    in_frame: SpinButton,
+    out_frame: SpinButton,
+    loop_mode: ComboBoxText,
+}
+
+impl PlaybackControls {
+    fn new(title: &str, container: &Box) -> Self {
+        let row = Box::new(Orientation::Horizontal, 5);
+        row.append(&Label::new(Some(title)));
+
+        row.append(&Label::new(Some("Speed:")));
+        let rate = SpinButton::with_range(PlaybackSettings::MIN_RATE, PlaybackSettings::MAX_RATE, 0.05);
+        rate.set_digits(2);
+        row.append(&rate);
+
+        row.append(&Label::new(Some("In:")));
+        let in_frame = SpinButton::with_range(0.0, 10_000_000.0, 1.0);
+        row.append(&in_frame);
+
+        // -1 = bis zum Ende des Videos
+        row.append(&Label::new(Some("Out (-1 = end):")));
+        let out_frame = SpinButton::with_range(-1.0, 10_000_000.0, 1.0);
+        row.append(&out_frame);
+
+        let loop_mode = ComboBoxText::new();
+        loop_mode.append(Some("loop"), "Loop");
+        loop_mode.append(Some("ping-pong"), "Ping-pong");
+        loop_mode.append(Some("hold-last"), "Play once, hold last frame");
+        loop_mode.append(Some("once"), "Play once");
+        row.append(&loop_mode);
+
+        container.append(&row);
+
+        let controls = Self {
+            rate,
+            in_frame,
+            out_frame,
+            loop_mode,
+        };
+        controls.set(&PlaybackSettings::default());
+        controls
+    }
+
+    fn set(&self, settings: &PlaybackSettings) {
+        self.rate.set_value(settings.rate);
+        self.in_frame.set_value(settings.in_frame as f64);
+        self.out_frame
+            .set_value(settings.out_frame.map(|f| f as f64).unwrap_or(-1.0));
+        self.loop_mode
+            .set_active_id(Some(&settings.loop_mode.to_string()));
+    }
+
+    fn get(&self) -> PlaybackSettings {
+        let out_frame = self.out_frame.value();
+        PlaybackSettings {
+            rate: self.rate.value(),
+            in_frame: self.in_frame.value() as u64,
+            out_frame: (out_frame >= 0.0).then_some(out_frame as u64),
+            loop_mode: self
+                .loop_mode
+                .active_id()
+                .and_then(|id| id.as_str().parse::<LoopMode>().ok())
+                .unwrap_or(LoopMode::Loop),
+        }
+    }
+}
+
+/// Testbild-Einstellungen einer virtuellen Kamera im Aufnahme-Tab
+#[derive(Clone)]
+struct PatternControls {
+    pattern: ComboBoxText,
+    speed: SpinButton,
+    overlay: CheckButton,
+    markers: CheckButton,
+}
+
+impl PatternControls {
+    fn new(title: &str, container: &Box) -> Self {
+        let row = Box::new(Orientation::Horizontal, 5);
+        row.append(&Label::new(Some(title)));
+
+        let pattern = ComboBoxText::new();
+        for name in TestPattern::NAMES {
+            pattern.append(Some(name), name);
+        }
+        pattern.set_active(Some(0));
+        row.append(&pattern);
+
+        row.append(&Label::new(Some("Speed (px/s):")));
+        let speed = SpinButton::with_range(0.0, 1000.0, 10.0);
+        speed.set_value(DEFAULT_SPEED);
+        row.append(&speed);
+
+        let overlay = CheckButton::with_label("Frame counter + timestamp");
+        row.append(&overlay);
+        let markers = CheckButton::with_label("Frame ID markers");
+        row.append(&markers);
+
+        container.append(&row);
+
+        Self {
+            pattern,
+            speed,
+            overlay,
+            markers,
+        }
+    }
+
+    fn get(&self) -> PatternConfig {
+        PatternConfig {
+            pattern: self
+                .pattern
+                .active_id()
+                .and_then(|id| id.as_str().parse().ok())
+                .unwrap_or(TestPattern::Stripes),
+            speed: self.speed.value(),
+            overlay: self.overlay.is_active(),
+            markers: self.markers.is_active(),
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum FrameAction {
+    Forward,
+    Backward,
+    Seek(u64),
+}
+
+fn format_positions(system: &StereoPlaybackSystem) -> String {
+    let describe = |position: Option<FramePosition>| {
+        position
+            .map(|p| p.to_string())
+            .unwrap_or_else(|| "-".to_string())
+    };
+    let (left, right) = system.get_positions();
+    format!("Frame L: {}  R: {}", describe(left), describe(right))
+}
+
 fn create_playback_tab(_log_buffer: LogBuffer) -> Box {
     let tab_box = Box::new(Orientation::Vertical, 10);
     tab_box.set_margin_start(10);
@@ -0,0 +1,443 @@
+use image::codecs::jpeg::JpegEncoder;
+use image::imageops::{self, FilterType};
+use image::{ExtendedColorType, RgbImage};
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+use thiserror::Error;
+
+use crate::frame_server::sanitize;
+
+#[derive(Error, Debug)]
+pub enum HttpPreviewError {
+    #[error("IO Fehler: {0}")]
+    IoError(#[from] io::Error),
+    #[error("JPEG-Kodierung fehlgeschlagen: {0}")]
+    EncodeError(String),
+    #[error("Frame hat {0} Bytes, erwartet {1}")]
+    FrameSize(usize, usize),
+}
+
+pub type Result<T> = std::result::Result<T, HttpPreviewError>;
+
+/// Grenze des MJPEG-Streams zwischen zwei Bildern
+const BOUNDARY: &str = "frame";
+
+/// Zeit für einen Client, seine Anfrage zu schicken
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Langsamere Betrachter werden getrennt, statt Bilder zu stauen
+const SEND_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Einstellungen der Vorschau; Breite, Qualität und Rate begrenzen die Last neben der Aufnahme
+#[derive(Debug, Clone, Copy)]
+pub struct PreviewConfig {
+    pub bind: IpAddr,
+    pub port: u16,
+    /// Breite der JPEGs (Seitenverhältnis bleibt), None = Auflösung der Quelle
+    pub width: Option<u32>,
+    /// JPEG-Qualität 1..=100
+    pub quality: u8,
+    /// Höchstens so viele Bilder pro Sekunde werden übernommen und kodiert
+    pub max_fps: f64,
+}
+
+impl Default for PreviewConfig {
+    fn default() -> Self {
+        Self {
+            bind: IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+            port: 8080,
+            width: None,
+            quality: 70,
+            max_fps: 5.0,
+        }
+    }
+}
+
+/// Eingebetteter HTTP-Server: `/` listet die Streams, `/NAME.mjpg` liefert
+/// `multipart/x-mixed-replace`, `/NAME.jpg` ein Einzelbild
+pub struct HttpPreviewServer {
+    config: PreviewConfig,
+    shared: Arc<Shared>,
+    running: Arc<AtomicBool>,
+    listener: Option<JoinHandle<()>>,
+}
+
+struct Shared {
+    config: PreviewConfig,
+    streams: Mutex<Vec<Arc<StreamState>>>,
+}
+
+/// Letztes übernommenes Bild einer Kamera und sein JPEG (kodiert beim ersten Abruf)
+struct StreamState {
+    name: String,
+    width: u32,
+    height: u32,
+    latest: Mutex<Latest>,
+    updated: Condvar,
+    viewers: AtomicUsize,
+    /// `PreviewStream` gefallen: offene Streams enden
+    closed: AtomicBool,
+}
+
+#[derive(Default)]
+struct Latest {
+    sequence: u64,
+    stored_at: Option<Instant>,
+    rgb: Option<Arc<Vec<u8>>>,
+    jpeg: Option<(u64, Arc<Vec<u8>>)>,
+}
+
+impl HttpPreviewServer {
+    pub fn start(config: PreviewConfig) -> Result<Self> {
+        let listener = TcpListener::bind(SocketAddr::new(config.bind, config.port))?;
+        listener.set_nonblocking(true)?;
+
+        let shared = Arc::new(Shared {
+            config,
+            streams: Mutex::new(Vec::new()),
+        });
+        let running = Arc::new(AtomicBool::new(true));
+        let listener = {
+            let shared = shared.clone();
+            let running = running.clone();
+            thread::spawn(move || accept_loop(listener, shared, running))
+        };
+
+        Ok(Self {
+            config,
+            shared,
+            running,
+            listener: Some(listener),
+        })
+    }
+
+    /// Adresse der Übersichtsseite; bei 0.0.0.0 im Netz über den Rechnernamen erreichbar
+    pub fn url(&self) -> String {
+        let host = if self.config.bind.is_unspecified() {
+            "localhost".to_string()
+        } else {
+            self.config.bind.to_string()
+        };
+        format!("http://{}:{}/", host, self.config.port)
+    }
+
+    /// Meldet eine Kamera an; sie verschwindet von der Seite, wenn `PreviewStream` fällt
+    pub fn add_stream(&self, name: &str, width: u32, height: u32) -> PreviewStream {
+        let state = Arc::new(StreamState {
+            name: sanitize(name),
+            width,
+            height,
+            latest: Mutex::new(Latest::default()),
+            updated: Condvar::new(),
+            viewers: AtomicUsize::new(0),
+            closed: AtomicBool::new(false),
+        });
+        self.shared.streams.lock().unwrap().push(state.clone());
+
+        PreviewStream {
+            state,
+            shared: self.shared.clone(),
+            interval: Duration::from_secs_f64(1.0 / self.config.max_fps.max(0.1)),
+            url: format!("{}{}.mjpg", self.url(), sanitize(name)),
+        }
+    }
+}
+
+impl Drop for HttpPreviewServer {
+    fn drop(&mut self) {
+        self.running.store(false, Ordering::SeqCst);
+        if let Some(listener) = self.listener.take() {
+            let _ = listener.join();
+        }
+        // Wartende Betrachter aufwecken, damit sie `running` sehen
+        for stream in self.shared.streams.lock().unwrap().iter() {
+            stream.updated.notify_all();
+        }
+    }
+}
+
+/// Eingang einer Kamera der Vorschau
+pub struct PreviewStream {
+    state: Arc<StreamState>,
+    shared: Arc<Shared>,
+    interval: Duration,
+    url: String,
+}
+
+impl PreviewStream {
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Übernimmt einen RGB-Frame, wenn seit dem letzten `1 / max_fps` vergangen ist;
+    /// kodiert wird erst im Thread eines Betrachters
+    pub fn publish(&self, rgb: &[u8]) -> Result<()> {
+        let expected = (self.state.width * self.state.height * 3) as usize;
+        if rgb.len() != expected {
+            return Err(HttpPreviewError::FrameSize(rgb.len(), expected));
+        }
+
+        let mut latest = self.state.latest.lock().unwrap();
+        if latest.stored_at.is_some_and(|t| t.elapsed() < self.interval) {
+            return Ok(());
+        }
+        latest.sequence += 1;
+        latest.stored_at = Some(Instant::now());
+        latest.rgb = Some(Arc::new(rgb.to_vec()));
+        drop(latest);
+
+        self.state.updated.notify_all();
+        Ok(())
+    }
+}
+
+impl Drop for PreviewStream {
+    fn drop(&mut self) {
+        self.shared
+            .streams
+            .lock()
+            .unwrap()
+            .retain(|state| !Arc::ptr_eq(state, &self.state));
+        self.state.closed.store(true, Ordering::SeqCst);
+        self.state.updated.notify_all();
+    }
+}
+
+impl StreamState {
+    /// JPEG-Größe nach `PreviewConfig::width`
+    fn output_size(&self, config: &PreviewConfig) -> (u32, u32) {
+        match config.width {
+            Some(width) if width > 0 && width < self.width => {
+                let height = (self.height as u64 * width as u64 / self.width as u64).max(1) as u32;
+                (width, height)
+            }
+            _ => (self.width, self.height),
+        }
+    }
+
+    /// Wartet auf ein Bild neuer als `after`; None bei Timeout
+    fn wait_for(&self, after: u64, timeout: Duration) -> Option<(u64, Arc<Vec<u8>>)> {
+        let latest = self.latest.lock().unwrap();
+        let (latest, _) = self
+            .updated
+            .wait_timeout_while(latest, timeout, |latest| {
+                (latest.sequence <= after || latest.rgb.is_none())
+                    && !self.closed.load(Ordering::SeqCst)
+            })
+            .unwrap();
+        if latest.sequence <= after {
+            return None;
+        }
+        Some((latest.sequence, latest.rgb.clone()?))
+    }
+
+    /// JPEG des neuesten Bilds; mehrere Betrachter teilen sich die Kodierung
+    fn jpeg(&self, config: &PreviewConfig, sequence: u64, rgb: &[u8]) -> Result<Arc<Vec<u8>>> {
+        if let Some((cached, jpeg)) = &self.latest.lock().unwrap().jpeg {
+            if *cached == sequence {
+                return Ok(jpeg.clone());
+            }
+        }
+
+        let image = RgbImage::from_raw(self.width, self.height, rgb.to_vec())
+            .ok_or_else(|| HttpPreviewError::EncodeError("Puffer zu klein".to_string()))?;
+        let (width, height) = self.output_size(config);
+        let image = if (width, height) == (self.width, self.height) {
+            image
+        } else {
+            imageops::resize(&image, width, height, FilterType::Triangle)
+        };
+
+        let mut jpeg = Vec::new();
+        JpegEncoder::new_with_quality(&mut jpeg, config.quality.clamp(1, 100))
+            .encode(image.as_raw(), width, height, ExtendedColorType::Rgb8)
+            .map_err(|e| HttpPreviewError::EncodeError(e.to_string()))?;
+        let jpeg = Arc::new(jpeg);
+
+        let mut latest = self.latest.lock().unwrap();
+        if latest.jpeg.as_ref().is_none_or(|(cached, _)| *cached < sequence) {
+            latest.jpeg = Some((sequence, jpeg.clone()));
+        }
+        Ok(jpeg)
+    }
+}
+
+fn accept_loop(listener: TcpListener, shared: Arc<Shared>, running: Arc<AtomicBool>) {
+    while running.load(Ordering::SeqCst) {
+        match listener.accept() {
+            Ok((stream, _)) => {
+                let shared = shared.clone();
+                let running = running.clone();
+                thread::spawn(move || {
+                    if let Err(e) = handle_client(stream, &shared, &running) {
+                        // Geschlossene Browser-Tabs sind der Normalfall
+                        if !matches!(
+                            e.kind(),
+                            io::ErrorKind::BrokenPipe | io::ErrorKind::ConnectionReset
+                        ) {
+                            eprintln!("HTTP-Vorschau: {}", e);
+                        }
+                    }
+                });
+            }
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
+                thread::sleep(Duration::from_millis(50));
+            }
+            Err(e) => {
+                eprintln!("HTTP-Vorschau: {}", e);
+                thread::sleep(Duration::from_millis(50));
+            }
+        }
+    }
+}
+
+fn handle_client(mut stream: TcpStream, shared: &Shared, running: &AtomicBool) -> io::Result<()> {
+    stream.set_nonblocking(false)?;
+    stream.set_read_timeout(Some(REQUEST_TIMEOUT))?;
+    stream.set_write_timeout(Some(SEND_TIMEOUT))?;
+
+    // Nur die Anfragezeile zählt, die Header werden überlesen
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    let mut header = String::new();
+    while reader.read_line(&mut header)? > 0 && !header.trim().is_empty() {
+        header.clear();
+    }
+
+    let mut parts = request_line.split_whitespace();
+    let (method, target) = (parts.next().unwrap_or(""), parts.next().unwrap_or("/"));
+    if method != "GET" && method != "HEAD" {
+        return respond(&mut stream, "405 Method Not Allowed", "text/plain", b"GET only\n");
+    }
+    let path = target.split('?').next().unwrap_or("/");
+
+    if path == "/" || path == "/index.html" {
+        let page = index_page(shared);
+        return respond(&mut stream, "200 OK", "text/html; charset=utf-8", page.as_bytes());
+    }
+
+    let name = path.trim_start_matches('/');
+    let (name, mjpeg) = if let Some(name) = name.strip_suffix(".mjpg") {
+        (name, true)
+    } else if let Some(name) = name.strip_suffix(".jpg") {
+        (name, false)
+    } else {
+        return respond(&mut stream, "404 Not Found", "text/plain", b"Not found\n");
+    };
+    let state = shared
+        .streams
+        .lock()
+        .unwrap()
+        .iter()
+        .find(|state| state.name == name)
+        .cloned();
+    let Some(state) = state else {
+        return respond(&mut stream, "404 Not Found", "text/plain", b"Unknown camera\n");
+    };
+
+    if mjpeg {
+        state.viewers.fetch_add(1, Ordering::SeqCst);
+        let result = stream_mjpeg(&mut stream, shared, &state, running);
+        state.viewers.fetch_sub(1, Ordering::SeqCst);
+        result
+    } else {
+        // Einzelbild: das neueste vorhandene, sonst kurz auf das erste warten
+        match state.wait_for(0, REQUEST_TIMEOUT) {
+            Some((sequence, rgb)) => match state.jpeg(&shared.config, sequence, &rgb) {
+                Ok(jpeg) => respond(&mut stream, "200 OK", "image/jpeg", &jpeg),
+                Err(e) => respond(
+                    &mut stream,
+                    "500 Internal Server Error",
+                    "text/plain",
+                    format!("{}\n", e).as_bytes(),
+                ),
+            },
+            None => respond(&mut stream, "503 Service Unavailable", "text/plain", b"No frame yet\n"),
+        }
+    }
+}
+
+fn stream_mjpeg(
+    stream: &mut TcpStream,
+    shared: &Shared,
+    state: &StreamState,
+    running: &AtomicBool,
+) -> io::Result<()> {
+    write!(
+        stream,
+        "HTTP/1.1 200 OK\r\n\
+         Content-Type: multipart/x-mixed-replace; boundary={}\r\n\
+         Cache-Control: no-cache\r\n\
+         Connection: close\r\n\r\n",
+        BOUNDARY
+    )?;
+
+    let mut last = 0;
+    while running.load(Ordering::SeqCst) && !state.closed.load(Ordering::SeqCst) {
+        let Some((sequence, rgb)) = state.wait_for(last, Duration::from_millis(500)) else {
+            continue;
+        };
+        last = sequence;
+        let jpeg = state
+            .jpeg(&shared.config, sequence, &rgb)
+            .map_err(|e| io::Error::other(e.to_string()))?;
+
+        write!(
+            stream,
+            "--{}\r\nContent-Type: image/jpeg\r\nContent-Length: {}\r\n\r\n",
+            BOUNDARY,
+            jpeg.len()
+        )?;
+        stream.write_all(&jpeg)?;
+        stream.write_all(b"\r\n")?;
+    }
+    Ok(())
+}
+
+fn respond(stream: &mut TcpStream, status: &str, content_type: &str, body: &[u8]) -> io::Result<()> {
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nCache-Control: no-cache\r\nConnection: close\r\n\r\n",
+        status,
+        content_type,
+        body.len()
+    )?;
+    stream.write_all(body)?;
+    stream.flush()
+}
+
+/// Übersichtsseite mit Live-Bild, Stream- und Snapshot-Link je Kamera
+fn index_page(shared: &Shared) -> String {
+    let config = &shared.config;
+    let mut rows = String::new();
+    for state in shared.streams.lock().unwrap().iter() {
+        let (width, height) = state.output_size(config);
+        rows.push_str(&format!(
+            "<figure><img src=\"/{name}.mjpg\" width=\"{width}\" height=\"{height}\" alt=\"{name}\">\
+             <figcaption>{name} ({sw}x{sh}, preview {width}x{height}) \
+             <a href=\"/{name}.mjpg\">stream</a> <a href=\"/{name}.jpg\">snapshot</a> \
+             {viewers} viewer(s)</figcaption></figure>\n",
+            name = state.name,
+            sw = state.width,
+            sh = state.height,
+            width = width,
+            height = height,
+            viewers = state.viewers.load(Ordering::SeqCst),
+        ));
+    }
+    if rows.is_empty() {
+        rows.push_str("<p>No active cameras.</p>\n");
+    }
+
+    format!(
+        "<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\"><title>cam_record_sim preview</title>\
+         <style>body{{font-family:sans-serif}} figure{{display:inline-block;margin:8px}}</style></head>\n\
+         <body><h1>cam_record_sim preview</h1>\n<p>JPEG quality {}, up to {} FPS</p>\n{}</body></html>\n",
+        config.quality, config.max_fps, rows
+    )
+}
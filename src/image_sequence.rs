@@ -0,0 +1,331 @@
+use chrono::{DateTime, FixedOffset, NaiveDateTime};
+use gstreamer as gst;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ImageSequenceError {
+    #[error("IO Fehler: {0}")]
+    IoError(#[from] std::io::Error),
+    #[error("Keine Bilder gefunden: {0}")]
+    Empty(String),
+    #[error("Bild konnte nicht gelesen werden: {0}")]
+    DecodeError(String),
+    #[error("Ungültige Zeitstempel-Datei: {0}")]
+    TimestampError(String),
+    #[error("Ungültige Framerate: {0} (muss endlich und größer 0 sein)")]
+    InvalidFps(f64),
+}
+
+pub type Result<T> = std::result::Result<T, ImageSequenceError>;
+
+pub const IMAGE_EXTENSIONS: [&str; 3] = ["png", "jpg", "jpeg"];
+
+/// Optionale Beschreibung im Bild-Verzeichnis
+pub const DESCRIPTOR_FILE: &str = "sequence.json";
+
+/// Ohne Angabe und ohne Zeitstempel-Datei
+const DEFAULT_FPS: f64 = 30.0;
+
+/// Werden ohne `timestamps_file` automatisch verwendet, falls vorhanden
+const TIMESTAMP_FILES: [&str; 2] = ["timestamps.txt", "times.txt"];
+
+/// Zeitstempel ab hier (Sekunden, 1973) gelten als Unix-Zeit statt relativ zum Start
+const MIN_EPOCH_SECS: f64 = 1e8;
+
+/// Welche Bilder eines Verzeichnisses in welchem Takt abgespielt werden
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ImageSequenceSpec {
+    /// Dateinamen-Muster mit `*` und `?`, z.B. `left_*.png`; None = alle PNG/JPEG
+    pub pattern: Option<String>,
+    /// Bilder pro Sekunde; mit Zeitstempel-Datei nur für die Frame-Umrechnung beim Springen
+    pub fps: Option<f64>,
+    /// Ein Zeitstempel pro Zeile in der ersten Spalte (Sekunden, Nanosekunden oder
+    /// `YYYY-MM-DD HH:MM:SS.f`), relativ zum Bild-Verzeichnis
+    pub timestamps_file: Option<PathBuf>,
+}
+
+impl ImageSequenceSpec {
+    /// `sequence.json` im Verzeichnis, sonst Standardwerte
+    pub fn load(dir: &Path) -> Result<Self> {
+        let path = dir.join(DESCRIPTOR_FILE);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let json = fs::read_to_string(&path)?;
+        serde_json::from_str(&json)
+            .map_err(|e| ImageSequenceError::DecodeError(format!("{}: {}", path.display(), e)))
+    }
+}
+
+/// Verzeichnis nummerierter Bilder als Frame-Quelle
+pub struct ImageSequence {
+    frames: Vec<PathBuf>,
+    /// PTS je Bild, relativ zum ersten
+    timestamps: Vec<Duration>,
+    fps: f64,
+    width: u32,
+    height: u32,
+    /// Absolute Zeit des ersten Bildes, falls die Zeitstempel keine relativen sind
+    started_at: Option<DateTime<FixedOffset>>,
+}
+
+impl ImageSequence {
+    pub fn open(dir: &Path, spec: &ImageSequenceSpec) -> Result<Self> {
+        let frames = list_images(dir, spec.pattern.as_deref())?;
+        if frames.is_empty() {
+            return Err(ImageSequenceError::Empty(dir.display().to_string()));
+        }
+
+        let timestamps_file = spec.timestamps_file.clone().or_else(|| {
+            TIMESTAMP_FILES
+                .iter()
+                .map(PathBuf::from)
+                .find(|name| dir.join(name).exists())
+        });
+
+        if let Some(fps) = spec.fps
+            && (!fps.is_finite() || fps <= 0.0)
+        {
+            return Err(ImageSequenceError::InvalidFps(fps));
+        }
+
+        let mut started_at = None;
+        let (timestamps, fps) = match timestamps_file {
+            Some(file) => {
+                let (timestamps, start) = read_timestamps(&dir.join(file), frames.len())?;
+                started_at = start;
+                let span = timestamps.last().copied().unwrap_or_default().as_secs_f64();
+                let measured = if span > 0.0 {
+                    (timestamps.len() - 1) as f64 / span
+                } else {
+                    DEFAULT_FPS
+                };
+                (timestamps, spec.fps.unwrap_or(measured))
+            }
+            None => {
+                let fps = spec.fps.unwrap_or(DEFAULT_FPS);
+                let timestamps = (0..frames.len())
+                    .map(|i| Duration::from_secs_f64(i as f64 / fps))
+                    .collect();
+                (timestamps, fps)
+            }
+        };
+
+        let (width, height) = image::image_dimensions(&frames[0])
+            .map_err(|e| ImageSequenceError::DecodeError(format!("{}: {}", frames[0].display(), e)))?;
+
+        Ok(Self {
+            frames,
+            timestamps,
+            fps,
+            width,
+            height,
+            started_at,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn fps(&self) -> f64 {
+        self.fps
+    }
+
+    pub fn resolution(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    pub fn started_at(&self) -> Option<DateTime<FixedOffset>> {
+        self.started_at
+    }
+
+    /// Bis zum Ende des letzten Bildes
+    pub fn duration(&self) -> Duration {
+        self.timestamps.last().copied().unwrap_or_default() + self.frame_duration()
+    }
+
+    fn frame_duration(&self) -> Duration {
+        Duration::from_secs_f64(1.0 / self.fps)
+    }
+
+    /// Dekodiert Bild `index` als RGB-Sample mit PTS, wie es die Video-Pipeline liefert
+    pub fn read(&self, index: usize) -> Result<gst::Sample> {
+        let path = &self.frames[index];
+        let image = image::open(path)
+            .map_err(|e| ImageSequenceError::DecodeError(format!("{}: {}", path.display(), e)))?
+            .to_rgb8();
+        if image.dimensions() != (self.width, self.height) {
+            return Err(ImageSequenceError::DecodeError(format!(
+                "{}: {}x{} statt {}x{}",
+                path.display(),
+                image.width(),
+                image.height(),
+                self.width,
+                self.height
+            )));
+        }
+
+        let mut buffer = gst::Buffer::from_mut_slice(image.into_raw());
+        {
+            let buffer = buffer.get_mut().unwrap();
+            buffer.set_pts(gst::ClockTime::from_nseconds(
+                self.timestamps[index].as_nanos() as u64,
+            ));
+            buffer.set_duration(gst::ClockTime::from_nseconds(
+                self.frame_duration().as_nanos() as u64,
+            ));
+        }
+
+        let caps = gst::Caps::builder("video/x-raw")
+            .field("format", "RGB")
+            .field("width", self.width as i32)
+            .field("height", self.height as i32)
+            .build();
+
+        Ok(gst::Sample::builder().buffer(&buffer).caps(&caps).build())
+    }
+}
+
+/// Enthält das Verzeichnis Bilder, die als Sequenz abgespielt werden können?
+pub fn is_image_sequence(dir: &Path) -> bool {
+    dir.is_dir()
+        && ImageSequenceSpec::load(dir)
+            .ok()
+            .and_then(|spec| list_images(dir, spec.pattern.as_deref()).ok())
+            .is_some_and(|frames| !frames.is_empty())
+}
+
+/// Bilder im Verzeichnis, natürlich sortiert (`img_2` vor `img_10`)
+fn list_images(dir: &Path, pattern: Option<&str>) -> Result<Vec<PathBuf>> {
+    let mut frames = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+            continue;
+        };
+        let matches = match pattern {
+            Some(pattern) => wildcard_match(pattern, name),
+            None => path
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .is_some_and(|ext| IMAGE_EXTENSIONS.contains(&ext.to_lowercase().as_str())),
+        };
+        if matches && path.is_file() {
+            frames.push(path);
+        }
+    }
+
+    frames.sort_by_cached_key(|path| natural_key(&path.file_name().unwrap().to_string_lossy()));
+    Ok(frames)
+}
+
+/// `*` passt auf beliebig viele Zeichen, `?` auf genau eines
+fn wildcard_match(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    let (mut p, mut n) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while n < name.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == name[n]) {
+            p += 1;
+            n += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, n));
+            p += 1;
+        } else if let Some((star, matched)) = backtrack {
+            p = star + 1;
+            n = matched + 1;
+            backtrack = Some((star, matched + 1));
+        } else {
+            return false;
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// Zahlenblöcke numerisch vergleichen, den Rest als Text
+fn natural_key(name: &str) -> Vec<(u64, String)> {
+    let mut key = Vec::new();
+    let mut chars = name.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        let digits = c.is_ascii_digit();
+        let mut part = String::new();
+        while let Some(&c) = chars.peek() {
+            if c.is_ascii_digit() != digits {
+                break;
+            }
+            part.push(c);
+            chars.next();
+        }
+        if digits {
+            key.push((part.parse().unwrap_or(u64::MAX), String::new()));
+        } else {
+            key.push((0, part));
+        }
+    }
+    key
+}
+
+/// Erste Spalte jeder Zeile (Trennzeichen Komma oder Leerzeichen, `#` = Kommentar) bzw.
+/// Datum und Uhrzeit wie bei KITTI raw. Werte über 1e11 gelten als Nanosekunden (z.B.
+/// EuRoC), sonst als Sekunden. Liefert die Zeiten relativ zum ersten Bild und dessen
+/// absolute Zeit, falls die Werte Unix-Zeiten sind.
+fn read_timestamps(
+    path: &Path,
+    frame_count: usize,
+) -> Result<(Vec<Duration>, Option<DateTime<FixedOffset>>)> {
+    let content = fs::read_to_string(path)?;
+    let mut datetimes = false;
+    let values: Vec<f64> = content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            let first = line.split([',', ' ', '\t']).next().unwrap_or_default();
+            if let Ok(value) = first.parse::<f64>() {
+                return Ok(value);
+            }
+            let time = NaiveDateTime::parse_from_str(line, "%Y-%m-%d %H:%M:%S%.f")
+                .map_err(|_| {
+                    ImageSequenceError::TimestampError(format!("{}: '{}'", path.display(), line))
+                })?
+                .and_utc();
+            datetimes = true;
+            Ok(time.timestamp() as f64 + time.timestamp_subsec_nanos() as f64 * 1e-9)
+        })
+        .collect::<Result<_>>()?;
+
+    if values.len() < frame_count {
+        return Err(ImageSequenceError::TimestampError(format!(
+            "{}: {} Zeitstempel für {} Bilder",
+            path.display(),
+            values.len(),
+            frame_count
+        )));
+    }
+
+    let scale = if !datetimes && values.iter().any(|&v| v > 1e11) {
+        1e-9
+    } else {
+        1.0
+    };
+    let first = values[0] * scale;
+    let started_at = (first >= MIN_EPOCH_SECS)
+        .then(|| DateTime::from_timestamp(first.floor() as i64, (first.fract() * 1e9) as u32))
+        .flatten()
+        .map(|t| t.fixed_offset());
+
+    let timestamps = values[..frame_count]
+        .iter()
+        .map(|&v| Duration::from_secs_f64((v * scale - first).max(0.0)))
+        .collect();
+    Ok((timestamps, started_at))
+}
@@ -6,16 +6,58 @@ mod virtual_camera;
 mod playback_camera;
 mod dual_recorder;
 mod gui;
+mod passthrough_recorder;
+mod direct_recorder;
+mod benchmark;
+mod frame_queue;
+mod frame_clock;
+mod recording_events;
+mod pipeline_health;
+mod camera_watchdog;
+mod shutdown;
+mod frame_timestamps;
+mod media_info;
+mod simulation_config;
+mod recording_sessions;
+mod image_sequence;
+mod datasets;
+mod frame_source;
+mod frame_server;
+mod frame_client;
+mod rtsp_server;
+mod http_preview;
+mod video_pipe;
+mod fault_injection;
+mod seeded_rng;
+mod sensor_model;
+mod bayer_sim;
+mod test_patterns;
+mod frame_markers;
 
-use camera::{CameraDevice, list_cameras};
+use camera::list_cameras;
+use camera_watchdog::{CaptureEvent, ReconnectConfig, ReconnectMode, ReconnectingCamera};
 use clap::{Parser, Subcommand};
 use player::{VideoPlayer, list_recordings};
+use direct_recorder::DirectRecorder;
+use dual_recorder::{CameraSource, DualCameraRecorder};
+use fault_injection::{FaultConfig, FaultLog, FaultySource};
+use sensor_model::{SensorConfig, SensorSource};
+use bayer_sim::{BayerFormat, BayerSimSource};
+use frame_clock::{FrameClock, TimingSource};
+use frame_client::FrameClient;
+use frame_markers::MarkerAnalyzer;
+use frame_server::{FrameServer, PixelFormat, default_socket_dir, socket_path};
+use frame_source::{FrameSource, SourceSpec};
+use http_preview::{HttpPreviewServer, PreviewConfig};
+use passthrough_recorder::{PassthroughContainer, PassthroughRecorder};
+use pipeline_health::ErrorPolicy;
+use playback_camera::{Pacing, PlaybackCamera};
 use recorder::VideoRecorder;
+use recording_events::{ProgressFormat, RecordingEvent};
+use rtsp_server::{RtspCodec, RtspConfig, RtspServer};
 use std::path::PathBuf;
-use std::sync::{
-    atomic::{AtomicBool, Ordering},
-    Arc,
-};
+use video_pipe::{PipeFormat, VideoPipeWriter, is_closed_pipe, open_output};
+use test_patterns::PatternConfig;
 use virtual_camera::{VirtualCamera, create_virtual_cameras};
 
 #[derive(Parser)]
@@ -44,6 +86,48 @@ enum Commands {
 
         #[arg(short, long, default_value = "60", help = "Aufnahmedauer in Sekunden")]
         duration: u64,
+
+        #[arg(long, default_value = "640", help = "Breite in Pixeln")]
+        width: u32,
+
+        #[arg(long, default_value = "480", help = "Höhe in Pixeln")]
+        height: u32,
+
+        #[arg(long, conflicts_with = "direct", help = "MJPEG-Stream der Kamera ohne Neu-Kodierung speichern")]
+        passthrough: bool,
+
+        #[arg(long, default_value = "mkv", help = "Container für Passthrough (mkv oder avi)")]
+        container: PassthroughContainer,
+
+        #[arg(long, help = "Direkte GStreamer-Pipeline bis zur Datei (ohne appsrc)")]
+        direct: bool,
+
+        #[arg(long, default_value = "2.0", help = "Sekunden ohne Frame, bis die Kamera als getrennt gilt")]
+        frame_timeout: f64,
+
+        #[arg(long, default_value = "new-segment", help = "Nach Wiederverbinden: new-segment oder same-file")]
+        reconnect: ReconnectMode,
+    },
+
+    #[command(about = "Vergleicht CPU-Last und Latenz von appsrc- und direkter Aufnahme")]
+    BenchCapture {
+        #[arg(short, long, help = "Kamera-Index (0, 1, ...)")]
+        camera: u32,
+
+        #[arg(short, long, default_value = "/tmp/cam_record_sim_bench", help = "Ausgabe-Verzeichnis")]
+        output: PathBuf,
+
+        #[arg(short, long, default_value = "30.0", help = "Frames pro Sekunde")]
+        fps: f64,
+
+        #[arg(short, long, default_value = "10", help = "Dauer pro Durchlauf in Sekunden")]
+        duration: u64,
+
+        #[arg(long, default_value = "640", help = "Breite in Pixeln")]
+        width: u32,
+
+        #[arg(long, default_value = "480", help = "Höhe in Pixeln")]
+        height: u32,
     },
 
     #[command(about = "Startet virtuelle Kamera-Simulation und nimmt auf")]
@@ -59,6 +143,75 @@ enum Commands {
 
         #[arg(short, long, default_value = "10", help = "Aufnahmedauer in Sekunden")]
         duration: u64,
+
+        #[arg(long, default_value = "stripes", help = "Testbild mit Optionen, z.B. smpte oder calibration,speed=120,overlay")]
+        pattern: PatternConfig,
+
+        #[arg(long, default_value = "640", help = "Breite in Pixeln")]
+        width: u32,
+
+        #[arg(long, default_value = "480", help = "Höhe in Pixeln")]
+        height: u32,
+    },
+
+    #[command(about = "Nimmt synchron von einer oder zwei Kameras auf (wie der Recording-Tab)")]
+    RecordStereo {
+        #[arg(long, help = "Linke Kamera (Index)")]
+        left: Option<u32>,
+
+        #[arg(long, help = "Rechte Kamera (Index)")]
+        right: Option<u32>,
+
+        #[arg(long = "virtual", help = "Zwei virtuelle Testkameras statt echter Kameras")]
+        use_virtual: bool,
+
+        #[arg(long, default_value = "stripes", help = "Testbild der linken virtuellen Kamera")]
+        left_pattern: PatternConfig,
+
+        #[arg(long, default_value = "stripes", help = "Testbild der rechten virtuellen Kamera")]
+        right_pattern: PatternConfig,
+
+        #[arg(long, default_value = "640", help = "Breite in Pixeln (echte und virtuelle Kameras)")]
+        width: u32,
+
+        #[arg(long, default_value = "480", help = "Höhe in Pixeln (echte und virtuelle Kameras)")]
+        height: u32,
+
+        #[arg(short, long, default_value = "recordings", help = "Ausgabe-Verzeichnis")]
+        output: PathBuf,
+
+        #[arg(short, long, default_value = "30.0", help = "Frames pro Sekunde")]
+        fps: f64,
+
+        #[arg(short, long, default_value = "10", help = "Aufnahmedauer in Sekunden")]
+        duration: u64,
+
+        #[arg(long, default_value = "text", help = "Fortschrittsausgabe (text oder json)")]
+        progress: ProgressFormat,
+
+        #[arg(long, default_value = "abort", help = "Bei Pipeline-Fehler: abort oder restart")]
+        on_error: ErrorPolicy,
+
+        #[arg(long, default_value = "2.0", help = "Sekunden ohne Frame, bis die Kamera als getrennt gilt")]
+        frame_timeout: f64,
+
+        #[arg(long, default_value = "new-segment", help = "Nach Wiederverbinden: new-segment oder same-file")]
+        reconnect: ReconnectMode,
+
+        #[arg(long, help = "MJPEG-Vorschau über HTTP im Netzwerk (http://RECHNER:PORT/)")]
+        http: bool,
+
+        #[arg(long, default_value = "8080", help = "HTTP-Port der Vorschau")]
+        http_port: u16,
+
+        #[arg(long, help = "Breite der Vorschau-JPEGs (Standard: Auflösung der Kamera)")]
+        preview_width: Option<u32>,
+
+        #[arg(long, default_value = "70", help = "JPEG-Qualität der Vorschau (1-100)")]
+        jpeg_quality: u8,
+
+        #[arg(long, default_value = "5.0", help = "Höchste Bildrate der Vorschau")]
+        preview_fps: f64,
     },
 
     #[command(about = "Listet alle Aufnahmen auf")]
@@ -76,6 +229,195 @@ enum Commands {
         dir: PathBuf,
     },
 
+    #[command(about = "Veröffentlicht Kameras über den Frame-Server (Unix-Socket + Shared Memory), optional RTSP und HTTP-Vorschau")]
+    Serve {
+        #[arg(long = "source", required = true, help = "Quelle: camera:N, virtual:N[:TESTBILD] oder Pfad zu Video/Bildfolge (mehrfach möglich)")]
+        sources: Vec<SourceSpec>,
+
+        #[arg(long, help = "Verzeichnis der Sockets (Standard: $XDG_RUNTIME_DIR/cam_record_sim)")]
+        socket_dir: Option<PathBuf>,
+
+        #[arg(short, long, default_value = "30.0", help = "Frames pro Sekunde (echte und virtuelle Kameras)")]
+        fps: f64,
+
+        #[arg(long, default_value = "640", help = "Breite in Pixeln")]
+        width: u32,
+
+        #[arg(long, default_value = "480", help = "Höhe in Pixeln")]
+        height: u32,
+
+        #[arg(long, help = "Zusätzlich als RTSP-Stream auf localhost (rtsp://127.0.0.1:PORT/NAME)")]
+        rtsp: bool,
+
+        #[arg(long, default_value = "8554", help = "RTSP-Port")]
+        rtsp_port: u16,
+
+        #[arg(long, default_value = "h264", help = "RTSP-Codec (h264, h265, mjpeg)")]
+        codec: RtspCodec,
+
+        #[arg(long, default_value = "200", help = "RTSP-Latenz in Millisekunden")]
+        latency_ms: u32,
+
+        #[arg(long, default_value = "4000", help = "RTSP-Bitrate in kbit/s (h264, h265)")]
+        bitrate: u32,
+
+        #[arg(long, help = "MJPEG-Vorschau über HTTP im Netzwerk (http://RECHNER:PORT/)")]
+        http: bool,
+
+        #[arg(long, default_value = "8080", help = "HTTP-Port der Vorschau")]
+        http_port: u16,
+
+        #[arg(long, help = "Breite der Vorschau-JPEGs (Standard: Auflösung der Kamera)")]
+        preview_width: Option<u32>,
+
+        #[arg(long, default_value = "70", help = "JPEG-Qualität der Vorschau (1-100)")]
+        jpeg_quality: u8,
+
+        #[arg(long, default_value = "5.0", help = "Höchste Bildrate der Vorschau")]
+        preview_fps: f64,
+
+        #[arg(long, help = "Sensor- und Objektiv-Effekte aus JSON-Datei (SensorConfig)")]
+        sensor: Option<PathBuf>,
+
+        #[arg(long, help = "Simulierter Bayer-Sensor: rggb, bggr, grbg, gbrg, optional mit 16 (z.B. rggb16)")]
+        bayer: Option<BayerFormat>,
+
+        #[arg(long, help = "Fehler-Injektion aus JSON-Datei (FaultConfig)")]
+        faults: Option<PathBuf>,
+
+        #[arg(long, default_value = "0", help = "Seed der Fehler-Injektion (gleicher Seed = gleiche Fehler)")]
+        fault_seed: u64,
+
+        #[arg(long, help = "Protokoll der injizierten Fehler als JSON Lines")]
+        fault_log: Option<PathBuf>,
+    },
+
+    #[command(about = "Prüft, ob ein lokaler RTSP-Client (rtspsrc) Frames empfängt; ohne URL gegen einen eigenen Server")]
+    CheckRtsp {
+        #[arg(help = "URL eines laufenden Servers, z.B. rtsp://127.0.0.1:8554/left (ohne: eigener Server mit --source)")]
+        url: Option<String>,
+
+        #[arg(long, default_value = "virtual:0", help = "Quelle des eigenen Servers: camera:N, virtual:N[:TESTBILD] oder Pfad")]
+        source: SourceSpec,
+
+        #[arg(long, default_value = "8554", help = "RTSP-Port des eigenen Servers")]
+        rtsp_port: u16,
+
+        #[arg(long, default_value = "h264", help = "RTSP-Codec des eigenen Servers (h264, h265, mjpeg)")]
+        codec: RtspCodec,
+
+        #[arg(long, default_value = "200", help = "RTSP-Latenz in Millisekunden")]
+        latency_ms: u32,
+
+        #[arg(short = 'n', long, default_value = "30", help = "So viele Frames müssen ankommen")]
+        count: u32,
+
+        #[arg(long, default_value = "10.0", help = "Sekunden ohne Frame, bis die Prüfung fehlschlägt")]
+        timeout: f64,
+
+        #[arg(long, default_value = "640", help = "Breite in Pixeln (eigener Server)")]
+        width: u32,
+
+        #[arg(long, default_value = "480", help = "Höhe in Pixeln (eigener Server)")]
+        height: u32,
+    },
+
+    #[command(about = "Empfängt Frames von einem Frame-Server und zeigt Rate, Verluste und Latenz")]
+    Subscribe {
+        #[arg(help = "Socket der Kamera, z.B. $XDG_RUNTIME_DIR/cam_record_sim/left.sock")]
+        socket: PathBuf,
+
+        #[arg(long, default_value = "rgb", help = "Pixelformat (rgb, bgr, gray8)")]
+        format: PixelFormat,
+
+        #[arg(short = 'n', long, default_value = "0", help = "Nach so vielen Frames beenden (0 = endlos)")]
+        count: u64,
+    },
+
+    #[command(about = "Schreibt Frames als YUV4MPEG2 oder RGB nach stdout oder in eine FIFO")]
+    Stream {
+        #[arg(long, help = "Quelle: camera:N, virtual:N[:TESTBILD] oder Pfad zu Video/Bildfolge")]
+        source: SourceSpec,
+
+        #[arg(long, default_value = "y4m", help = "Ausgabeformat: y4m, rgb (mit JSON-Kopfzeile) oder raw")]
+        format: PipeFormat,
+
+        #[arg(short, long, help = "FIFO als Ziel (wird angelegt, falls nicht vorhanden); Standard: stdout")]
+        output: Option<PathBuf>,
+
+        #[arg(short, long, default_value = "30.0", help = "Frames pro Sekunde (echte und virtuelle Kameras)")]
+        fps: f64,
+
+        #[arg(long, default_value = "640", help = "Breite in Pixeln")]
+        width: u32,
+
+        #[arg(long, default_value = "480", help = "Höhe in Pixeln")]
+        height: u32,
+
+        #[arg(long, default_value = "realtime", help = "Takt: realtime, recorded oder fast (so schnell der Leser liest)")]
+        pace: Pacing,
+
+        #[arg(short = 'n', long, default_value = "0", help = "Nach so vielen Frames beenden (0 = endlos)")]
+        count: u64,
+
+        #[arg(short, long, help = "Nach so vielen Sekunden beenden")]
+        duration: Option<f64>,
+
+        #[arg(long, help = "Sensor- und Objektiv-Effekte aus JSON-Datei (SensorConfig)")]
+        sensor: Option<PathBuf>,
+
+        #[arg(long, help = "Simulierter Bayer-Sensor: rggb, bggr, grbg, gbrg, optional mit 16 (z.B. rggb16)")]
+        bayer: Option<BayerFormat>,
+
+        #[arg(long, help = "Fehler-Injektion aus JSON-Datei (FaultConfig)")]
+        faults: Option<PathBuf>,
+
+        #[arg(long, default_value = "0", help = "Seed der Fehler-Injektion (gleicher Seed = gleiche Fehler)")]
+        fault_seed: u64,
+
+        #[arg(long, help = "Protokoll der injizierten Fehler als JSON Lines")]
+        fault_log: Option<PathBuf>,
+    },
+
+    #[command(about = "Prüft den Bayer-Pfad ohne Hardware: Mosaik, bayer2rgb und Vergleich mit dem Original")]
+    TestBayer {
+        #[arg(long, default_value = "virtual:0", help = "Quelle: virtual:N[:TESTBILD] oder Pfad zu Video/Bildfolge")]
+        source: SourceSpec,
+
+        #[arg(long = "format", help = "Bayer-Format, mehrfach möglich (Standard: alle Muster mit 8 und 16 Bit)")]
+        formats: Vec<BayerFormat>,
+
+        #[arg(short = 'n', long, default_value = "10", help = "Frames pro Format")]
+        count: u32,
+
+        #[arg(long, default_value = "20.0", help = "Mindest-PSNR in dB, sonst schlägt der Test fehl")]
+        min_psnr: f64,
+
+        #[arg(long, default_value = "640", help = "Breite in Pixeln")]
+        width: u32,
+
+        #[arg(long, default_value = "480", help = "Höhe in Pixeln")]
+        height: u32,
+    },
+
+    #[command(about = "Liest Frame-ID-Marker aus einer Aufnahme oder von einem Frame-Server und meldet Verluste, Duplikate, Umsortierung und Latenz")]
+    AnalyzeMarkers {
+        #[arg(help = "Aufnahme (Video/Bildfolge) oder Socket eines Frame-Servers")]
+        input: PathBuf,
+
+        #[arg(short = 'n', long, default_value = "0", help = "Nach so vielen Frames beenden (0 = bis Ende bzw. Strg+C)")]
+        count: u64,
+
+        #[arg(short, long, help = "Nach so vielen Sekunden beenden")]
+        duration: Option<f64>,
+
+        #[arg(long, help = "Ergebnis als JSON ausgeben")]
+        json: bool,
+
+        #[arg(long, help = "Mit Fehler beenden, wenn Frames fehlen, doppelt, umsortiert oder unlesbar sind")]
+        strict: bool,
+    },
+
     #[command(about = "Testet zwei virtuelle Kameras")]
     TestVirtual {
         #[arg(short, long, default_value = "5", help = "Testdauer in Sekunden")]
@@ -111,52 +453,171 @@ fn main() -> anyhow::Result<()> {
             output,
             fps,
             duration,
+            width,
+            height,
+            passthrough,
+            container,
+            direct,
+            frame_timeout,
+            reconnect,
         } => {
-            println!("Öffne Kamera {}...", camera);
-            let mut cam = CameraDevice::new(camera)?;
-            cam.start()?;
+            let shutdown = shutdown::install();
 
-            println!("Starte Aufnahme für {} Sekunden...", duration);
-            let mut recorder = VideoRecorder::new(camera, 640, 480, fps, &output)?;
+            if passthrough {
+                println!("Starte MJPEG-Passthrough von Kamera {} für {} Sekunden...", camera, duration);
+                let recorder = PassthroughRecorder::new(
+                    camera,
+                    width as i32,
+                    height as i32,
+                    fps,
+                    container,
+                    &output,
+                )?;
 
-            let running = Arc::new(AtomicBool::new(true));
-            let r = running.clone();
+                let start = std::time::Instant::now();
+                let mut preview_count = 0;
+
+                while !shutdown.is_requested() && start.elapsed().as_secs() < duration {
+                    for warning in recorder.take_warnings() {
+                        eprintln!("Warnung: {}", warning);
+                    }
+                    if let Err(e) = recorder.check_health() {
+                        let _ = recorder.finalize();
+                        return Err(e.into());
+                    }
 
-            ctrlc::set_handler(move || {
-                println!("\nStoppe Aufnahme...");
-                r.store(false, Ordering::SeqCst);
-            })
-            .expect("Fehler beim Setzen des Ctrl-C Handlers");
+                    if recorder.get_preview_frame().is_some() {
+                        preview_count += 1;
+
+                        if preview_count % 30 == 0 {
+                            println!("Vorschau: {} frames", preview_count);
+                        }
+                    }
+                }
+
+                let metadata = recorder.finalize()?;
+                println!("Aufnahme abgeschlossen!");
+                println!("  Datei: {}", metadata.filename);
+                println!("  Dauer: {:.2}s", metadata.duration_secs);
+                return Ok(());
+            }
+
+            if direct {
+                println!("Starte direkte Aufnahme von Kamera {} für {} Sekunden...", camera, duration);
+                let recorder = DirectRecorder::new(camera, width as i32, height as i32, fps, &output)?;
+
+                let start = std::time::Instant::now();
+                while !shutdown.is_requested() && start.elapsed().as_secs() < duration {
+                    for warning in recorder.take_warnings() {
+                        eprintln!("Warnung: {}", warning);
+                    }
+                    if let Err(e) = recorder.check_health() {
+                        let _ = recorder.finalize();
+                        return Err(e.into());
+                    }
+
+                    let _ = recorder.get_preview_frame();
+                }
+
+                let metadata = recorder.finalize()?;
+                println!("Aufnahme abgeschlossen!");
+                println!("  Datei: {}", metadata.filename);
+                println!("  Dauer: {:.2}s", metadata.duration_secs);
+                return Ok(());
+            }
+
+            println!("Öffne Kamera {}...", camera);
+            let mut cam = ReconnectingCamera::open(
+                camera,
+                width,
+                height,
+                std::time::Duration::from_secs_f64(frame_timeout),
+            )?;
+
+            println!("Starte Aufnahme für {} Sekunden...", duration);
+            let mut recorder = VideoRecorder::new(camera, width as i32, height as i32, fps, &output)?;
 
             let start = std::time::Instant::now();
             let mut frame_count = 0;
+            // get_frame blockiert bis die Kamera liefert, sie gibt den Takt vor
+            let mut clock = FrameClock::new(fps, TimingSource::Camera);
 
-            while running.load(Ordering::SeqCst) && start.elapsed().as_secs() < duration {
-                match cam.get_frame() {
-                    Ok(frame) => {
+            while !shutdown.is_requested() && start.elapsed().as_secs() < duration {
+                match cam.next() {
+                    CaptureEvent::Frame(frame) => {
                         recorder.write_frame(&frame)?;
                         frame_count += 1;
 
+                        for warning in recorder.take_warnings() {
+                            eprintln!("Warnung: {}", warning);
+                        }
+
                         if frame_count % 30 == 0 {
                             println!("Aufgenommen: {} frames", frame_count);
                         }
                     }
-                    Err(e) => {
+                    CaptureEvent::Error(e) => {
                         eprintln!("Fehler beim Lesen des Frames: {}", e);
+                        std::thread::sleep(clock.interval());
+                        continue;
                     }
+                    CaptureEvent::Lost(reason) => {
+                        eprintln!("Kamera getrennt: {} - warte auf Wiederverbinden...", reason);
+                        continue;
+                    }
+                    CaptureEvent::Reconnected(outage) => {
+                        println!("Kamera wieder verbunden nach {:.1}s", outage.duration_secs);
+                        recorder.add_outage(outage);
+
+                        if reconnect == ReconnectMode::NewSegment {
+                            let next = VideoRecorder::new(camera, width as i32, height as i32, fps, &output)?;
+                            let previous = std::mem::replace(&mut recorder, next);
+                            let metadata = previous.finalize()?;
+                            println!("Segment abgeschlossen: {}", metadata.filename);
+                        }
+                        continue;
+                    }
+                    CaptureEvent::Waiting => continue,
                 }
 
-                std::thread::sleep(std::time::Duration::from_millis(
-                    (1000.0 / fps) as u64,
-                ));
+                clock.wait();
             }
 
-            cam.stop()?;
+            if let Some(outage) = cam.end_outage() {
+                recorder.add_outage(outage);
+            }
+            cam.stop();
+            let timing = clock.stats();
+            recorder.set_timing_stats(timing);
             let metadata = recorder.finalize()?;
             println!("Aufnahme abgeschlossen!");
             println!("  Datei: {}", metadata.filename);
             println!("  Dauer: {:.2}s", metadata.duration_secs);
             println!("  Frames: {}", frame_count);
+            println!("  Timing: {}", timing.summary());
+        }
+
+        Commands::BenchCapture {
+            camera,
+            output,
+            fps,
+            duration,
+            width,
+            height,
+        } => {
+            let duration = std::time::Duration::from_secs(duration);
+
+            println!("Benchmark appsrc-Pfad ({}s)...", duration.as_secs());
+            let appsrc = benchmark::bench_appsrc(camera, width, height, fps, duration, &output)?;
+
+            // Kamera kurz freigeben, bevor v4l2src sie öffnet
+            std::thread::sleep(std::time::Duration::from_millis(500));
+
+            println!("Benchmark direkter Pfad ({}s)...", duration.as_secs());
+            let direct = benchmark::bench_direct(camera, width, height, fps, duration, &output)?;
+
+            println!();
+            benchmark::print_results(&[appsrc, direct]);
         }
 
         Commands::SimRecord {
@@ -164,31 +625,27 @@ fn main() -> anyhow::Result<()> {
             output,
             fps,
             duration,
+            pattern,
+            width,
+            height,
         } => {
             if camera > 1 {
                 println!("Virtuelle Kamera ID muss 0 oder 1 sein!");
                 return Ok(());
             }
 
-            println!("Starte virtuelle Kamera {}...", camera);
-            let vcam = VirtualCamera::new(camera, 640, 480, fps as u32);
+            println!("Starte virtuelle Kamera {} ({})...", camera, pattern);
+            let vcam = VirtualCamera::new(camera, width, height, fps).with_pattern(pattern);
 
             println!("Starte Aufnahme für {} Sekunden...", duration);
-            let mut recorder = VideoRecorder::new(camera, 640, 480, fps, &output)?;
-
-            let running = Arc::new(AtomicBool::new(true));
-            let r = running.clone();
+            let mut recorder = VideoRecorder::new(camera, width as i32, height as i32, fps, &output)?;
 
-            ctrlc::set_handler(move || {
-                println!("\nStoppe Aufnahme...");
-                r.store(false, Ordering::SeqCst);
-            })
-            .expect("Fehler beim Setzen des Ctrl-C Handlers");
+            let shutdown = shutdown::install();
 
             let start = std::time::Instant::now();
             let mut frame_count = 0;
 
-            while running.load(Ordering::SeqCst) && start.elapsed().as_secs() < duration {
+            while !shutdown.is_requested() && start.elapsed().as_secs() < duration {
                 match vcam.get_frame() {
                     Ok(frame) => {
                         recorder.write_frame(&frame)?;
@@ -206,11 +663,110 @@ fn main() -> anyhow::Result<()> {
                 vcam.wait_for_next_frame();
             }
 
+            let timing = vcam.get_timing_stats();
+            recorder.set_timing_stats(timing);
             let metadata = recorder.finalize()?;
             println!("Aufnahme abgeschlossen!");
             println!("  Datei: {}", metadata.filename);
             println!("  Dauer: {:.2}s", metadata.duration_secs);
             println!("  Frames: {}", frame_count);
+            println!("  Timing: {}", timing.summary());
+        }
+
+        Commands::RecordStereo {
+            left,
+            right,
+            use_virtual,
+            left_pattern,
+            right_pattern,
+            width,
+            height,
+            output,
+            fps,
+            duration,
+            progress,
+            on_error,
+            frame_timeout,
+            reconnect,
+            http,
+            http_port,
+            preview_width,
+            jpeg_quality,
+            preview_fps,
+        } => {
+            let source = match (use_virtual, left, right) {
+                (true, _, _) => CameraSource::Virtual([left_pattern, right_pattern]),
+                (false, Some(l), Some(r)) => CameraSource::Dual(l, r),
+                (false, Some(id), None) | (false, None, Some(id)) => CameraSource::Single(id),
+                (false, None, None) => {
+                    eprintln!("Bitte --left/--right oder --virtual angeben");
+                    return Ok(());
+                }
+            };
+
+            let shutdown = shutdown::install();
+
+            let mut recorder = DualCameraRecorder::new();
+            recorder.set_resolution(width, height);
+            recorder.set_error_policy(on_error);
+            recorder.set_reconnect_config(ReconnectConfig {
+                frame_timeout: std::time::Duration::from_secs_f64(frame_timeout),
+                mode: reconnect,
+            });
+            let events = recorder.events();
+            recorder.start_recording(source, &output, fps, duration)?;
+
+            // Vorschau der laufenden Aufnahme (Vorschau-Frames des Recorders)
+            let (preview_w, preview_h) = recorder.preview_resolution();
+            let preview = if http {
+                let server = HttpPreviewServer::start(PreviewConfig {
+                    port: http_port,
+                    width: preview_width,
+                    quality: jpeg_quality,
+                    max_fps: preview_fps,
+                    ..PreviewConfig::default()
+                })?;
+                println!("HTTP-Vorschau: {}", server.url());
+                let streams = [
+                    server.add_stream("left", preview_w, preview_h),
+                    server.add_stream("right", preview_w, preview_h),
+                ];
+                Some((server, streams))
+            } else {
+                None
+            };
+
+            while !shutdown.is_requested() {
+                if let Some((_, [left, right])) = &preview {
+                    for (stream, frame) in [(left, recorder.get_left_frame()), (right, recorder.get_right_frame())] {
+                        // Die Vorschau darf die Aufnahme nicht abbrechen
+                        if let Some(Err(e)) = frame.map(|frame| stream.publish(&frame)) {
+                            eprintln!("HTTP-Vorschau: {}", e);
+                        }
+                    }
+                }
+                match events.try_recv() {
+                    Ok(event) => {
+                        println!("{}", progress.format(&event));
+                        if matches!(event, RecordingEvent::Finished) {
+                            break;
+                        }
+                    }
+                    Err(_) => std::thread::sleep(std::time::Duration::from_millis(50)),
+                }
+            }
+
+            let report = recorder.shutdown(shutdown::SHUTDOWN_TIMEOUT);
+            // Events aus dem Abschließen (finalized, finished) noch ausgeben
+            while let Ok(event) = events.try_recv() {
+                println!("{}", progress.format(&event));
+            }
+            for line in report.lines() {
+                eprintln!("{}", line);
+            }
+            if !report.is_clean() {
+                anyhow::bail!("Nicht alle Aufnahmen wurden sauber abgeschlossen");
+            }
         }
 
         Commands::ListRecordings { dir } => {
@@ -231,9 +787,462 @@ fn main() -> anyhow::Result<()> {
             println!("Lade Video: {:?}", video_path);
 
             let mut player = VideoPlayer::new(&video_path)?;
+            println!("Video: {}", player.get_media_info().summary());
             player.play()?;
         }
 
+        Commands::Serve {
+            sources,
+            socket_dir,
+            fps,
+            width,
+            height,
+            rtsp,
+            rtsp_port,
+            codec,
+            latency_ms,
+            bitrate,
+            http,
+            http_port,
+            preview_width,
+            jpeg_quality,
+            preview_fps,
+            sensor,
+            bayer,
+            faults,
+            fault_seed,
+            fault_log,
+        } => {
+            let shutdown = shutdown::install();
+            let sensor_config = sensor.as_deref().map(SensorConfig::load).transpose()?;
+            let fault_config = faults.as_deref().map(FaultConfig::load).transpose()?;
+            let fault_events = FaultLog::new();
+            let socket_dir = socket_dir.unwrap_or_else(default_socket_dir);
+            let rtsp = if rtsp {
+                let config = RtspConfig {
+                    port: rtsp_port,
+                    codec,
+                    latency_ms,
+                    bitrate_kbps: bitrate,
+                };
+                Some(std::sync::Arc::new(RtspServer::start(config)?))
+            } else {
+                None
+            };
+            let preview = if http {
+                let server = HttpPreviewServer::start(PreviewConfig {
+                    port: http_port,
+                    width: preview_width,
+                    quality: jpeg_quality,
+                    max_fps: preview_fps,
+                    ..PreviewConfig::default()
+                })?;
+                println!("HTTP-Vorschau: {}", server.url());
+                Some(std::sync::Arc::new(server))
+            } else {
+                None
+            };
+
+            // Eine Quelle pro Thread; Kameras werden im Thread geöffnet, der sie liest
+            let handles: Vec<_> = sources
+                .into_iter()
+                .enumerate()
+                .map(|(i, spec)| {
+                    let socket_dir = socket_dir.clone();
+                    let fault_events = fault_events.clone();
+                    let rtsp = rtsp.clone();
+                    let preview = preview.clone();
+                    std::thread::spawn(move || -> anyhow::Result<()> {
+                        let mut source = spec.open(width, height, fps)?;
+                        if let Some(mut config) = sensor_config {
+                            // Eigener Seed je Quelle wie bei den Fehlern, sonst rauschen alle gleich
+                            config.seed = config.seed.wrapping_add(i as u64);
+                            source = Box::new(SensorSource::new(source, config));
+                        }
+                        if let Some(format) = bayer {
+                            source = Box::new(BayerSimSource::new(source, format)?);
+                        }
+                        if let Some(config) = fault_config {
+                            // Eigener Seed je Quelle, damit nicht alle gleichzeitig ausfallen
+                            let seed = fault_seed.wrapping_add(i as u64);
+                            source = Box::new(FaultySource::new(source, config, seed, fault_events));
+                        }
+                        let (w, h) = source.resolution();
+                        let name = source.name();
+                        let server = FrameServer::start(
+                            &socket_path(&socket_dir, &name),
+                            &name,
+                            w,
+                            h,
+                            source.fps(),
+                        )?;
+                        println!(
+                            "{} ({}x{} @ {:.2} FPS) -> {}",
+                            spec,
+                            w,
+                            h,
+                            source.fps(),
+                            server.socket_path().display()
+                        );
+                        let stream = match &rtsp {
+                            Some(rtsp) => {
+                                let stream = rtsp.add_stream(&name, w, h, source.fps())?;
+                                println!("{} -> {}", spec, stream.url());
+                                Some(stream)
+                            }
+                            None => None,
+                        };
+                        let preview = match &preview {
+                            Some(preview) => {
+                                let stream = preview.add_stream(&name, w, h);
+                                println!("{} -> {}", spec, stream.url());
+                                Some(stream)
+                            }
+                            None => None,
+                        };
+
+                        while !shutdown.is_requested() {
+                            let frame = source.next_frame()?;
+                            server.publish(&frame.data, frame.pts)?;
+                            if let Some(stream) = &stream {
+                                stream.publish(&frame.data)?;
+                            }
+                            if let Some(preview) = &preview {
+                                preview.publish(&frame.data)?;
+                            }
+                        }
+                        Ok(())
+                    })
+                })
+                .collect();
+
+            for handle in handles {
+                match handle.join() {
+                    Ok(Err(e)) => eprintln!("Quelle beendet: {}", e),
+                    Err(_) => eprintln!("Quelle abgestürzt"),
+                    Ok(Ok(())) => (),
+                }
+            }
+
+            if fault_config.is_some() {
+                println!("Injizierte Fehler: {}", fault_events.summary());
+            }
+            if let Some(path) = fault_log {
+                fault_events.write_jsonl(&path)?;
+            }
+        }
+
+        Commands::CheckRtsp {
+            url,
+            source,
+            rtsp_port,
+            codec,
+            latency_ms,
+            count,
+            timeout,
+            width,
+            height,
+        } => {
+            use std::sync::atomic::{AtomicBool, Ordering};
+            use std::sync::{Arc, mpsc};
+
+            // Ohne URL: eigener Server wie bei `serve`, die Quelle wird im Thread geöffnet
+            let running = Arc::new(AtomicBool::new(true));
+            let (url, publisher) = match url {
+                Some(url) => (url, None),
+                None => {
+                    let server = Arc::new(RtspServer::start(RtspConfig {
+                        port: rtsp_port,
+                        codec,
+                        latency_ms,
+                        ..RtspConfig::default()
+                    })?);
+                    let (ready_tx, ready_rx) = mpsc::channel();
+                    let label = source.to_string();
+                    let handle = {
+                        let server = server.clone();
+                        let running = running.clone();
+                        std::thread::spawn(move || -> anyhow::Result<()> {
+                            let opened = source
+                                .open(width, height, 30.0)
+                                .map_err(anyhow::Error::from)
+                                .and_then(|frames| {
+                                    let (w, h) = frames.resolution();
+                                    let stream = server.add_stream("check", w, h, frames.fps())?;
+                                    Ok((frames, stream))
+                                });
+                            let (mut frames, stream) = match opened {
+                                Ok(opened) => opened,
+                                Err(e) => {
+                                    let _ = ready_tx.send(Err(e.to_string()));
+                                    return Ok(());
+                                }
+                            };
+                            let _ = ready_tx.send(Ok(stream.url().to_string()));
+                            while running.load(Ordering::SeqCst) {
+                                let frame = frames.next_frame()?;
+                                stream.publish(&frame.data)?;
+                            }
+                            Ok(())
+                        })
+                    };
+                    let url = ready_rx
+                        .recv()?
+                        .map_err(|e| anyhow::anyhow!("Quelle {}: {}", label, e))?;
+                    println!("{} -> {}", label, url);
+                    (url, Some((server, handle)))
+                }
+            };
+
+            let result = rtsp_server::receive(
+                &url,
+                count,
+                latency_ms,
+                std::time::Duration::from_secs_f64(timeout),
+            );
+
+            running.store(false, Ordering::SeqCst);
+            if let Some((_server, handle)) = publisher {
+                match handle.join() {
+                    Ok(Err(e)) => eprintln!("Quelle beendet: {}", e),
+                    Err(_) => eprintln!("Quelle abgestürzt"),
+                    Ok(Ok(())) => (),
+                }
+            }
+
+            let report = result?;
+            println!(
+                "{}: {} Frames {}x{}, erster Frame nach {:.2} s, {:.1} FPS",
+                url,
+                report.frames,
+                report.width,
+                report.height,
+                report.first_frame.as_secs_f64(),
+                report.fps()
+            );
+            println!("RTSP OK");
+        }
+
+        Commands::Subscribe {
+            socket,
+            format,
+            count,
+        } => {
+            let shutdown = shutdown::install();
+            let mut client = FrameClient::connect(&socket, format)?;
+            let info = client.info().clone();
+            println!(
+                "Verbunden mit {}: {}x{} @ {:.2} FPS, Format {}",
+                info.camera, info.width, info.height, info.fps, info.format
+            );
+
+            let mut received = 0u64;
+            let mut window_start = std::time::Instant::now();
+            let mut window_frames = 0u32;
+            let mut max_latency = std::time::Duration::ZERO;
+
+            while !shutdown.is_requested() && (count == 0 || received < count) {
+                let frame = client.next_frame()?;
+                received += 1;
+                window_frames += 1;
+                let latency = std::time::SystemTime::now()
+                    .duration_since(frame.timestamp)
+                    .unwrap_or_default();
+                max_latency = max_latency.max(latency);
+
+                if window_start.elapsed() >= std::time::Duration::from_secs(1) {
+                    println!(
+                        "#{} pts {:.3}s, {} Bytes, {:.1} FPS, max. Latenz {:.2} ms, verloren {}",
+                        frame.sequence,
+                        frame.pts.as_secs_f64(),
+                        frame.data.len(),
+                        window_frames as f64 / window_start.elapsed().as_secs_f64(),
+                        max_latency.as_secs_f64() * 1000.0,
+                        client.dropped_frames()
+                    );
+                    window_start = std::time::Instant::now();
+                    window_frames = 0;
+                    max_latency = std::time::Duration::ZERO;
+                }
+            }
+
+            println!("{} Frames empfangen, {} verloren", received, client.dropped_frames());
+        }
+
+        Commands::Stream {
+            source,
+            format,
+            output,
+            fps,
+            width,
+            height,
+            pace,
+            count,
+            duration,
+            sensor,
+            bayer,
+            faults,
+            fault_seed,
+            fault_log,
+        } => {
+            // stdout gehört den Frames, Meldungen gehen nach stderr
+            let shutdown = shutdown::install();
+            let fault_events = FaultLog::new();
+            let mut frames = source.open(width, height, fps)?;
+            if let Some(path) = &sensor {
+                frames = Box::new(SensorSource::new(frames, SensorConfig::load(path)?));
+            }
+            if let Some(format) = bayer {
+                frames = Box::new(BayerSimSource::new(frames, format)?);
+            }
+            if let Some(path) = &faults {
+                let config = FaultConfig::load(path)?;
+                frames = Box::new(FaultySource::new(frames, config, fault_seed, fault_events.clone()));
+            }
+            frames.set_pacing(pace);
+            let (w, h) = frames.resolution();
+            eprintln!("{}: {}x{} @ {:.2} FPS als {}", source, w, h, frames.fps(), format);
+
+            if let Some(path) = &output {
+                eprintln!("Warte auf Leser an {}", path.display());
+            }
+            let mut writer = VideoPipeWriter::new(open_output(output.as_deref())?, format, w, h, frames.fps());
+
+            let start = std::time::Instant::now();
+            let mut written = 0u64;
+            while !shutdown.is_requested()
+                && (count == 0 || written < count)
+                && duration.is_none_or(|secs| start.elapsed().as_secs_f64() < secs)
+            {
+                let frame = frames.next_frame()?;
+                match writer.write_frame(&frame.data) {
+                    Ok(()) => written += 1,
+                    Err(e) if is_closed_pipe(&e) => {
+                        eprintln!("Leser hat die Pipe geschlossen");
+                        break;
+                    }
+                    Err(e) => return Err(e.into()),
+                }
+            }
+
+            eprintln!(
+                "{} Frames in {:.1} s geschrieben",
+                written,
+                start.elapsed().as_secs_f64()
+            );
+            if faults.is_some() {
+                eprintln!("Injizierte Fehler: {}", fault_events.summary());
+            }
+            if let Some(path) = fault_log {
+                fault_events.write_jsonl(&path)?;
+            }
+        }
+
+        Commands::TestBayer {
+            source,
+            formats,
+            count,
+            min_psnr,
+            width,
+            height,
+        } => {
+            let formats = if formats.is_empty() {
+                BayerFormat::ALL.to_vec()
+            } else {
+                formats
+            };
+
+            let mut failed = Vec::new();
+            for format in formats {
+                let mut frames = source.open(width, height, 30.0)?;
+                frames.set_pacing(Pacing::AsFastAsPossible);
+                let mut sim = BayerSimSource::new(frames, format)?;
+
+                let mut worst = f64::INFINITY;
+                for _ in 0..count {
+                    let frame = sim.next_frame()?;
+                    worst = worst.min(bayer_sim::psnr(sim.last_original(), &frame.data));
+                }
+
+                let ok = worst >= min_psnr;
+                println!(
+                    "{:8} {} Frames, min. PSNR {:.1} dB {}",
+                    format.to_string(),
+                    count,
+                    worst,
+                    if ok { "OK" } else { "FEHLER" }
+                );
+                if !ok {
+                    failed.push(format.to_string());
+                }
+            }
+
+            if !failed.is_empty() {
+                anyhow::bail!("Bayer-Test fehlgeschlagen für {}", failed.join(", "));
+            }
+            println!("Bayer-Pfad OK");
+        }
+
+        Commands::AnalyzeMarkers {
+            input,
+            count,
+            duration,
+            json,
+            strict,
+        } => {
+            use std::os::unix::fs::FileTypeExt;
+
+            let shutdown = shutdown::install();
+            let mut analyzer = MarkerAnalyzer::new();
+            let start = std::time::Instant::now();
+            let running = |frames: u64| {
+                !shutdown.is_requested()
+                    && (count == 0 || frames < count)
+                    && duration.is_none_or(|secs| start.elapsed().as_secs_f64() < secs)
+            };
+
+            let mut frames = 0u64;
+            if std::fs::metadata(&input)?.file_type().is_socket() {
+                // Live: Latenz vom Erzeugen in der virtuellen Kamera bis zum Empfang hier
+                let mut client = FrameClient::connect(&input, PixelFormat::Rgb)?;
+                let info = client.info().clone();
+                eprintln!("Verbunden mit {}: {}x{}", info.camera, info.width, info.height);
+                while running(frames) {
+                    let frame = client.next_frame()?;
+                    let received = std::time::SystemTime::now();
+                    analyzer.push(&frame.data, info.width, info.height, Some(received));
+                    frames += 1;
+                }
+            } else {
+                // Ohne Schleife, sonst zählt jeder Neustart als Umsortierung
+                let mut cam = PlaybackCamera::new(0, &input, false)?;
+                cam.set_pacing(Pacing::AsFastAsPossible);
+                let (w, h) = cam.resolution();
+                while running(frames) && !cam.is_finished() {
+                    match cam.get_frame() {
+                        Ok(data) => analyzer.push(&data, w, h, None),
+                        // Ende bei unbekannter Frame-Anzahl
+                        Err(e) if frames > 0 => {
+                            eprintln!("Ende der Aufnahme: {}", e);
+                            break;
+                        }
+                        Err(e) => return Err(e.into()),
+                    }
+                    frames += 1;
+                }
+            }
+
+            let report = analyzer.report();
+            if json {
+                println!("{}", serde_json::to_string_pretty(&report)?);
+            } else {
+                print!("{}", report);
+            }
+            if strict && !report.is_clean() {
+                anyhow::bail!("Frame-Marker zeigen Verluste oder Fehler");
+            }
+        }
+
         Commands::TestVirtual { duration } => {
             println!("Starte Test mit zwei virtuellen Kameras...");
             let vcams = create_virtual_cameras();
@@ -242,7 +1251,7 @@ fn main() -> anyhow::Result<()> {
             for vcam in &vcams {
                 let (w, h) = vcam.get_resolution();
                 println!(
-                    "  - Kamera {} ({}x{} @ {} FPS)",
+                    "  - Kamera {} ({}x{} @ {:.2} FPS)",
                     vcam.get_id(),
                     w,
                     h,
@@ -253,6 +1262,7 @@ fn main() -> anyhow::Result<()> {
             println!("\nGeneriere Frames für {} Sekunden...", duration);
             let start = std::time::Instant::now();
             let mut frame_counts = vec![0; vcams.len()];
+            let mut clock = FrameClock::new(30.0, TimingSource::Clock);
 
             while start.elapsed().as_secs() < duration {
                 for (i, vcam) in vcams.iter().enumerate() {
@@ -266,10 +1276,11 @@ fn main() -> anyhow::Result<()> {
                     }
                 }
 
-                std::thread::sleep(std::time::Duration::from_millis(33)); // ~30 FPS
+                clock.wait();
             }
 
             println!("\nTest abgeschlossen!");
+            println!("  Timing: {}", clock.stats().summary());
             for (i, count) in frame_counts.iter().enumerate() {
                 println!("  Kamera {}: {} Frames generiert", i, count);
             }
@@ -0,0 +1,107 @@
+use chrono::{DateTime, FixedOffset};
+use gstreamer as gst;
+use gstreamer::prelude::*;
+use std::path::Path;
+use std::time::Duration;
+
+use crate::frame_timestamps::read_capture_times;
+use crate::image_sequence::ImageSequence;
+use crate::recorder::read_metadata;
+
+/// Eckdaten eines Videos für Wiedergabe und Fortschrittsanzeige
+#[derive(Debug, Clone, Default)]
+pub struct MediaInfo {
+    pub width: u32,
+    pub height: u32,
+    /// 0.0, wenn weder Caps noch Metadaten eine Framerate liefern
+    pub fps: f64,
+    pub duration: Option<Duration>,
+    pub frame_count: Option<u64>,
+    /// Wanduhrzeit des ersten Frames laut Metadaten (Versatz zwischen Stereo-Aufnahmen)
+    pub started_at: Option<DateTime<FixedOffset>>,
+}
+
+impl MediaInfo {
+    /// Ermittelt die Eckdaten einer vorgerollten Pipeline (PAUSED oder PLAYING).
+    ///
+    /// `pad` ist ein Pad hinter dem Decoder, dessen ausgehandelte Caps Auflösung und
+    /// Framerate enthalten. Fehlende Angaben kommen aus der Metadaten-JSON neben dem
+    /// Video, die genaue Frame-Anzahl aus der Zeitstempel-Datei.
+    pub fn discover(pipeline: &gst::Pipeline, pad: Option<gst::Pad>, video_path: &Path) -> Self {
+        let mut info = MediaInfo::default();
+
+        if let Some(structure) = pad
+            .and_then(|pad| pad.current_caps())
+            .and_then(|caps| caps.structure(0).map(|s| s.to_owned()))
+        {
+            info.width = structure.get::<i32>("width").unwrap_or(0).max(0) as u32;
+            info.height = structure.get::<i32>("height").unwrap_or(0).max(0) as u32;
+            // 0/1 heißt variable Framerate, dann zählen die Metadaten
+            if let Ok(rate) = structure.get::<gst::Fraction>("framerate") {
+                if rate.numer() > 0 && rate.denom() > 0 {
+                    info.fps = rate.numer() as f64 / rate.denom() as f64;
+                }
+            }
+        }
+
+        info.duration = pipeline
+            .query_duration::<gst::ClockTime>()
+            .map(|d| Duration::from_nanos(d.nseconds()));
+
+        if let Some(metadata) = read_metadata(video_path) {
+            if info.width == 0 || info.height == 0 {
+                info.width = metadata.width.max(0) as u32;
+                info.height = metadata.height.max(0) as u32;
+            }
+            if info.fps <= 0.0 {
+                info.fps = metadata.fps;
+            }
+            if info.duration.is_none() && metadata.duration_secs > 0.0 {
+                info.duration = Some(Duration::from_secs_f64(metadata.duration_secs));
+            }
+            info.started_at = metadata
+                .first_frame_at
+                .and_then(|t| DateTime::parse_from_rfc3339(&t).ok());
+        }
+
+        info.frame_count = match read_capture_times(video_path) {
+            Some(times) => Some(times.len() as u64),
+            None => info
+                .duration
+                .filter(|_| info.fps > 0.0)
+                .map(|d| (d.as_secs_f64() * info.fps).round() as u64),
+        };
+
+        info
+    }
+
+    /// Bild-Verzeichnis: Anzahl und Auflösung sind exakt bekannt, die Startzeit nur bei
+    /// absoluten Zeitstempeln
+    pub fn from_image_sequence(sequence: &ImageSequence) -> Self {
+        let (width, height) = sequence.resolution();
+        MediaInfo {
+            width,
+            height,
+            fps: sequence.fps(),
+            duration: Some(sequence.duration()),
+            frame_count: Some(sequence.len() as u64),
+            started_at: sequence.started_at(),
+        }
+    }
+
+    pub fn summary(&self) -> String {
+        let frames = self
+            .frame_count
+            .map(|n| n.to_string())
+            .unwrap_or_else(|| "?".to_string());
+        let duration = self
+            .duration
+            .map(|d| format!("{:.2}s", d.as_secs_f64()))
+            .unwrap_or_else(|| "?".to_string());
+
+        format!(
+            "{}x{} @ {:.2} FPS, {} Frames, {}",
+            self.width, self.height, self.fps, frames, duration
+        )
+    }
+}
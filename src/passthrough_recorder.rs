@@ -0,0 +1,234 @@
+use chrono::Local;
+use gstreamer as gst;
+use gstreamer::prelude::*;
+use gstreamer_app as gst_app;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::gst_camera::is_mjpeg_camera;
+use crate::pipeline_health::{BusWatcher, PipelineIssue};
+use crate::recorder::{
+    RecorderError, RecordingMetadata, Result, unique_output_path, wait_for_eos, write_metadata,
+};
+
+/// Auflösung der dekodierten Vorschau (entspricht der GUI-Vorschau)
+pub const PREVIEW_WIDTH: u32 = 640;
+pub const PREVIEW_HEIGHT: u32 = 480;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PassthroughContainer {
+    Mkv,
+    Avi,
+}
+
+impl PassthroughContainer {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            PassthroughContainer::Mkv => "mkv",
+            PassthroughContainer::Avi => "avi",
+        }
+    }
+
+    fn muxer(&self) -> &'static str {
+        match self {
+            PassthroughContainer::Mkv => "matroskamux",
+            PassthroughContainer::Avi => "avimux",
+        }
+    }
+}
+
+impl std::str::FromStr for PassthroughContainer {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "mkv" => Ok(PassthroughContainer::Mkv),
+            "avi" => Ok(PassthroughContainer::Avi),
+            other => Err(format!("Unbekannter Container: {} (mkv oder avi)", other)),
+        }
+    }
+}
+
+/// Speichert den MJPEG-Stream der Kamera ohne Dekodieren und Neu-Kodieren.
+///
+/// Ein `tee` teilt den komprimierten Stream: ein Zweig geht unverändert in den
+/// Muxer, der andere wird dekodiert und auf Vorschau-Größe skaliert.
+pub struct PassthroughRecorder {
+    pipeline: gst::Pipeline,
+    preview_sink: gst_app::AppSink,
+    camera_id: u32,
+    start_time: std::time::Instant,
+    fps: f64,
+    width: i32,
+    height: i32,
+    output_path: PathBuf,
+    health: BusWatcher,
+    finalized: bool,
+}
+
+impl PassthroughRecorder {
+    pub fn new(
+        camera_id: u32,
+        width: i32,
+        height: i32,
+        fps: f64,
+        container: PassthroughContainer,
+        output_dir: &Path,
+    ) -> Result<Self> {
+        gst::init().map_err(|e| RecorderError::GStreamerError(e.to_string()))?;
+
+        // Ohne MJPEG scheitert die Pipeline erst später mit einem unklaren Caps-Fehler
+        if !is_mjpeg_camera(camera_id) {
+            return Err(RecorderError::FormatError(format!(
+                "/dev/video{} liefert kein MJPEG, Passthrough nicht möglich",
+                camera_id
+            )));
+        }
+        let framerate = gst::Fraction::approximate_f64(fps)
+            .filter(|rate| rate.numer() > 0)
+            .ok_or_else(|| RecorderError::PipelineError(format!("Ungültige Framerate: {}", fps)))?;
+
+        fs::create_dir_all(output_dir)?;
+
+        let timestamp = Local::now().format("%Y%m%d_%H%M%S");
+        let output_path = unique_output_path(
+            output_dir,
+            &format!("camera_{}__{}", camera_id, timestamp),
+            container.extension(),
+        );
+
+        let pipeline_str = format!(
+            "v4l2src device=/dev/video{} ! \
+             image/jpeg,width={},height={},framerate={}/{} ! \
+             tee name=t \
+             t. ! queue ! jpegparse ! {} ! filesink location={} \
+             t. ! queue leaky=downstream max-size-buffers=2 ! jpegdec ! videoscale ! videoconvert ! \
+             video/x-raw,format=RGB,width={},height={} ! \
+             appsink name=preview sync=false max-buffers=1 drop=true",
+            camera_id,
+            width,
+            height,
+            framerate.numer(),
+            framerate.denom(),
+            container.muxer(),
+            output_path.to_str().unwrap(),
+            PREVIEW_WIDTH,
+            PREVIEW_HEIGHT
+        );
+
+        eprintln!("Creating passthrough pipeline: {}", pipeline_str);
+
+        let pipeline = gst::parse::launch(&pipeline_str)
+            .map_err(|e| RecorderError::PipelineError(e.to_string()))?
+            .downcast::<gst::Pipeline>()
+            .map_err(|_| RecorderError::PipelineError("Kein Pipeline Element".to_string()))?;
+
+        let preview_sink = pipeline
+            .by_name("preview")
+            .ok_or_else(|| RecorderError::PipelineError("appsink nicht gefunden".to_string()))?
+            .downcast::<gst_app::AppSink>()
+            .map_err(|_| RecorderError::PipelineError("Kein AppSink Element".to_string()))?;
+
+        pipeline
+            .set_state(gst::State::Playing)
+            .map_err(|e| RecorderError::PipelineError(e.to_string()))?;
+
+        let health = BusWatcher::watch(&format!("passthrough camera {}", camera_id), &pipeline);
+
+        Ok(Self {
+            pipeline,
+            preview_sink,
+            camera_id,
+            start_time: std::time::Instant::now(),
+            fps,
+            width,
+            height,
+            output_path,
+            health,
+            finalized: false,
+        })
+    }
+
+    /// Holt den neuesten dekodierten Vorschau-Frame (RGB, PREVIEW_WIDTH x PREVIEW_HEIGHT)
+    pub fn get_preview_frame(&self) -> Option<Vec<u8>> {
+        let sample = self
+            .preview_sink
+            .try_pull_sample(gst::ClockTime::from_mseconds(100))?;
+        let buffer = sample.buffer()?;
+        let map = buffer.map_readable().ok()?;
+        Some(map.as_slice().to_vec())
+    }
+
+    /// Fehler vom Pipeline-Bus (z.B. Kamera getrennt) als `PipelineFailed`
+    pub fn check_health(&self) -> Result<()> {
+        match self.health.error() {
+            Some(issue) => Err(RecorderError::PipelineFailed(issue.to_string())),
+            None => Ok(()),
+        }
+    }
+
+    /// Warnungen vom Pipeline-Bus seit dem letzten Aufruf
+    pub fn take_warnings(&self) -> Vec<PipelineIssue> {
+        self.health.take_warnings()
+    }
+
+    pub fn finalize(mut self) -> Result<RecordingMetadata> {
+        self.finish()
+    }
+
+    fn finish(&mut self) -> Result<RecordingMetadata> {
+        self.finalized = true;
+        // Der Watcher darf die EOS-Nachricht nicht vom Bus nehmen
+        self.health.stop();
+
+        let duration = self.start_time.elapsed().as_secs_f64();
+
+        self.pipeline.send_event(gst::event::Eos::new());
+
+        wait_for_eos(&self.pipeline, &self.health)?;
+
+        let metadata = RecordingMetadata {
+            camera_id: self.camera_id,
+            timestamp: Local::now().to_rfc3339(),
+            duration_secs: duration,
+            fps: self.fps,
+            width: self.width,
+            height: self.height,
+            filename: self
+                .output_path
+                .file_name()
+                .unwrap()
+                .to_string_lossy()
+                .to_string(),
+            codec: Some("mjpeg".to_string()),
+            queue_policy: None,
+            dropped_frames: None,
+            timing: None,
+            outages: None,
+            timestamps_file: None,
+            first_frame_at: None,
+        };
+
+        write_metadata(&self.output_path, &metadata)?;
+
+        eprintln!(
+            "Passthrough-Aufnahme gespeichert: {} ({:.2}s)",
+            self.output_path.display(),
+            duration
+        );
+
+        Ok(metadata)
+    }
+}
+
+impl Drop for PassthroughRecorder {
+    /// Beim Aufräumen ohne `finalize` (z.B. Panic) trotzdem EOS senden
+    fn drop(&mut self) {
+        if !self.finalized {
+            eprintln!("Aufnahme von Kamera {} nicht abgeschlossen, schließe beim Aufräumen ab", self.camera_id);
+            if let Err(e) = self.finish() {
+                eprintln!("Abschließen fehlgeschlagen: {}", e);
+            }
+        }
+    }
+}
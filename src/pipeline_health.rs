@@ -0,0 +1,172 @@
+use gstreamer as gst;
+use gstreamer::prelude::*;
+use std::fmt;
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc, Mutex,
+};
+use std::thread;
+
+/// Reaktion auf einen Pipeline-Fehler während der Aufnahme
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorPolicy {
+    Abort,   // Stream beenden und Fehler melden
+    Restart, // Datei abschließen und mit neuer Pipeline weitermachen
+}
+
+impl std::str::FromStr for ErrorPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "abort" => Ok(ErrorPolicy::Abort),
+            "restart" => Ok(ErrorPolicy::Restart),
+            other => Err(format!("Unbekannte Fehler-Policy: {} (abort oder restart)", other)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IssueLevel {
+    Warning,
+    Error,
+}
+
+/// Fehler oder Warnung vom Bus einer Pipeline, inkl. GStreamer Debug-Info
+#[derive(Debug, Clone)]
+pub struct PipelineIssue {
+    pub level: IssueLevel,
+    pub pipeline: String,
+    pub element: String,
+    pub message: String,
+    pub debug: Option<String>,
+}
+
+impl fmt::Display for PipelineIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}] {}: {}", self.pipeline, self.element, self.message)?;
+        if let Some(debug) = &self.debug {
+            write!(f, " ({})", debug)?;
+        }
+        Ok(())
+    }
+}
+
+impl PipelineIssue {
+    /// Liest Fehler/Warnung aus einer Bus-Nachricht (andere Nachrichten: None)
+    pub fn from_message(pipeline: &str, msg: &gst::Message) -> Option<Self> {
+        use gst::MessageView;
+
+        let element = msg
+            .src()
+            .map(|s| s.path_string().to_string())
+            .unwrap_or_else(|| "?".to_string());
+
+        let (level, message, debug) = match msg.view() {
+            MessageView::Error(err) => (
+                IssueLevel::Error,
+                err.error().to_string(),
+                err.debug().map(|d| d.to_string()),
+            ),
+            MessageView::Warning(warn) => (
+                IssueLevel::Warning,
+                warn.error().to_string(),
+                warn.debug().map(|d| d.to_string()),
+            ),
+            _ => return None,
+        };
+
+        Some(Self {
+            level,
+            pipeline: pipeline.to_string(),
+            element,
+            message,
+            debug,
+        })
+    }
+}
+
+/// Liest den Bus einer Pipeline in einem eigenen Thread, solange die Pipeline läuft.
+///
+/// Fehler werden sofort sichtbar (`error()`), nicht erst beim Abschließen. Vor dem
+/// EOS in `finalize` muss der Watcher mit `stop()` beendet werden, damit er die
+/// EOS-Nachricht nicht selbst vom Bus nimmt.
+pub struct BusWatcher {
+    warnings: Arc<Mutex<Vec<PipelineIssue>>>,
+    error: Arc<Mutex<Option<PipelineIssue>>>,
+    stop: Arc<AtomicBool>,
+    handle: Option<thread::JoinHandle<()>>,
+}
+
+impl BusWatcher {
+    pub fn watch(name: &str, pipeline: &gst::Pipeline) -> Self {
+        let warnings = Arc::new(Mutex::new(Vec::new()));
+        let error = Arc::new(Mutex::new(None));
+        let stop = Arc::new(AtomicBool::new(false));
+
+        let handle = pipeline.bus().map(|bus| {
+            let name = name.to_string();
+            let warnings = warnings.clone();
+            let error = error.clone();
+            let stop = stop.clone();
+
+            thread::spawn(move || {
+                while !stop.load(Ordering::SeqCst) {
+                    let Some(msg) = bus.timed_pop_filtered(
+                        gst::ClockTime::from_mseconds(100),
+                        &[gst::MessageType::Error, gst::MessageType::Warning],
+                    ) else {
+                        continue;
+                    };
+
+                    let Some(issue) = PipelineIssue::from_message(&name, &msg) else {
+                        continue;
+                    };
+
+                    eprintln!("Pipeline {:?}: {}", issue.level, issue);
+
+                    match issue.level {
+                        IssueLevel::Warning => warnings.lock().unwrap().push(issue),
+                        IssueLevel::Error => {
+                            // Der erste Fehler ist die Ursache, Folgefehler nicht überschreiben
+                            let mut slot = error.lock().unwrap();
+                            if slot.is_none() {
+                                *slot = Some(issue);
+                            }
+                        }
+                    }
+                }
+            })
+        });
+
+        Self {
+            warnings,
+            error,
+            stop,
+            handle,
+        }
+    }
+
+    /// Erster Fehler seit dem Start, falls die Pipeline ausgefallen ist
+    pub fn error(&self) -> Option<PipelineIssue> {
+        self.error.lock().unwrap().clone()
+    }
+
+    /// Neue Warnungen seit dem letzten Aufruf
+    pub fn take_warnings(&self) -> Vec<PipelineIssue> {
+        std::mem::take(&mut *self.warnings.lock().unwrap())
+    }
+
+    pub fn stop(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+impl Drop for BusWatcher {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
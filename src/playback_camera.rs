@@ -1,10 +1,21 @@
 use gstreamer as gst;
 use gstreamer::prelude::*;
 use gstreamer_app as gst_app;
+use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use thiserror::Error;
 
+use crate::fault_injection::{FaultInjector, FaultLog};
+use crate::frame_source::SourceFrame;
+use crate::frame_timestamps::read_capture_times;
+use crate::image_sequence::{ImageSequence, ImageSequenceSpec};
+use crate::media_info::MediaInfo;
+use crate::recording_sessions::{Recording, RecordingSession};
+use crate::sensor_model::SensorModel;
+use crate::simulation_config::{LoopMode, PlaybackSettings, SimulationConfig, SyncBy};
+
 #[derive(Error, Debug)]
 pub enum PlaybackCameraError {
     #[error("Video konnte nicht geöffnet werden: {0}")]
@@ -13,19 +24,114 @@ pub enum PlaybackCameraError {
     ReadError(String),
     #[error("GStreamer Fehler: {0}")]
     GStreamerError(String),
+    #[error("Springen fehlgeschlagen: {0}")]
+    SeekError(String),
+    #[error("Ungültige Abspiel-Einstellungen: {0}")]
+    InvalidSettings(String),
 }
 
 pub type Result<T> = std::result::Result<T, PlaybackCameraError>;
 
-/// Virtuelle Kamera die eine Video-Datei als Input verwendet
+/// Nach welchem Takt PlaybackCamera Frames ausliefert
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Pacing {
+    #[serde(rename = "realtime")]
+    RealTime, // Nach PTS gegen die Uhr, wie die aufgenommene Kamera
+    #[serde(rename = "recorded")]
+    RecordedTimestamps, // Nach der Zeitstempel-Datei (Original-Jitter), sonst wie RealTime
+    #[serde(rename = "fast")]
+    AsFastAsPossible, // Für Offline-Verarbeitung
+}
+
+impl std::fmt::Display for Pacing {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Pacing::RealTime => "realtime",
+            Pacing::RecordedTimestamps => "recorded",
+            Pacing::AsFastAsPossible => "fast",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl std::str::FromStr for Pacing {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "realtime" | "real-time" => Ok(Pacing::RealTime),
+            "recorded" | "timestamps" => Ok(Pacing::RecordedTimestamps),
+            "fast" | "asap" => Ok(Pacing::AsFastAsPossible),
+            other => Err(format!("Unbekannter Takt: {} (realtime, recorded oder fast)", other)),
+        }
+    }
+}
+
+/// Ab dieser Verspätung wird die Uhr neu aufgesetzt statt Frames nachzuholen
+const MAX_LATENESS: Duration = Duration::from_secs(1);
+
+/// Index und PTS des zuletzt ausgelieferten Frames
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FramePosition {
+    pub index: u64,
+    pub pts: Duration,
+}
+
+impl std::fmt::Display for FramePosition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "#{} @ {:.3}s", self.index, self.pts.as_secs_f64())
+    }
+}
+
+/// Dekodierter Frame mit dem Zeitstempel, nach dem er getaktet wird
+struct DecodedFrame {
+    sample: gst::Sample,
+    timestamp: Duration,
+    position: FramePosition,
+}
+
+/// Woher PlaybackCamera ihre Frames dekodiert
+enum FrameInput {
+    Video {
+        pipeline: gst::Pipeline,
+        appsink: gst_app::AppSink,
+    },
+    /// Bild-Verzeichnis; `next` ist das nächste zu lesende Bild, `last` das letzte des
+    /// Abspielbereichs in Abspielrichtung (beim Rückwärtsspielen der In-Punkt)
+    Images {
+        sequence: ImageSequence,
+        next: i64,
+        last: i64,
+    },
+}
+
+/// Virtuelle Kamera die eine Video-Datei oder ein Bild-Verzeichnis als Input verwendet
 pub struct PlaybackCamera {
-    pipeline: gst::Pipeline,
-    appsink: gst_app::AppSink,
+    source: FrameInput,
     camera_id: u32,
     video_path: PathBuf,
-    loop_playback: bool,
-    frame_count: Arc<Mutex<i32>>,
+    settings: PlaybackSettings,
+    /// Rückwärts abspielen (Ping-Pong)
+    reverse: bool,
+    info: MediaInfo,
     current_frame: Arc<Mutex<i32>>,
+    pacing: Pacing,
+    capture_times: Option<Vec<Duration>>,
+    /// Wanduhr-Zeitpunkt und Zeitstempel des Frames, an dem die Uhr ausgerichtet ist
+    clock_origin: Option<(Instant, Duration)>,
+    /// Spielzeit: wächst auch rückwärts und über Loop-Grenzen hinweg monoton
+    timeline: Duration,
+    /// Zeitstempel des letzten dekodierten Frames, None nach einem Sprung
+    last_raw: Option<Duration>,
+    /// Dekodiert, aber noch nicht fällig (für `poll_frame`)
+    pending: Option<DecodedFrame>,
+    /// Nur vom Nutzer (Pause, Einzelschritt), nicht vom Ende des Abspielbereichs
+    paused: bool,
+    /// Am Ende mit `LoopMode::HoldLast` stehen geblieben, bis zum nächsten Sprung
+    holding_last: bool,
+    /// Zuletzt dekodierter Frame, wird im Pausenzustand erneut ausgeliefert
+    last_sample: Option<gst::Sample>,
+    position: Option<FramePosition>,
 }
 
 impl PlaybackCamera {
@@ -39,8 +145,69 @@ impl PlaybackCamera {
 
         gst::init().map_err(|e| PlaybackCameraError::GStreamerError(e.to_string()))?;
 
+        let (source, info) = if video_path.is_dir() {
+            let spec = ImageSequenceSpec::load(video_path)
+                .map_err(|e| PlaybackCameraError::OpenError(e.to_string()))?;
+            Self::open_images(video_path, &spec)?
+        } else {
+            Self::open_video(video_path)?
+        };
+
+        Ok(Self::with_source(camera_id, video_path, source, info, loop_playback))
+    }
+
+    /// Bild-Verzeichnis mit vorgegebener Beschreibung statt `sequence.json` (Datensätze)
+    pub fn from_image_sequence(
+        camera_id: u32,
+        dir: &Path,
+        spec: &ImageSequenceSpec,
+        loop_playback: bool,
+    ) -> Result<Self> {
+        gst::init().map_err(|e| PlaybackCameraError::GStreamerError(e.to_string()))?;
+        let (source, info) = Self::open_images(dir, spec)?;
+        Ok(Self::with_source(camera_id, dir, source, info, loop_playback))
+    }
+
+    fn with_source(
+        camera_id: u32,
+        video_path: &Path,
+        source: FrameInput,
+        info: MediaInfo,
+        loop_playback: bool,
+    ) -> Self {
+        Self {
+            source,
+            camera_id,
+            video_path: video_path.to_path_buf(),
+            settings: PlaybackSettings {
+                loop_mode: if loop_playback {
+                    LoopMode::Loop
+                } else {
+                    LoopMode::Once
+                },
+                ..PlaybackSettings::default()
+            },
+            reverse: false,
+            info,
+            current_frame: Arc::new(Mutex::new(0)),
+            pacing: Pacing::RealTime,
+            capture_times: None,
+            clock_origin: None,
+            timeline: Duration::ZERO,
+            last_raw: None,
+            pending: None,
+            paused: false,
+            holding_last: false,
+            last_sample: None,
+            position: None,
+        }
+    }
+
+    fn open_video(video_path: &Path) -> Result<(FrameInput, MediaInfo)> {
+        // sync=false: getaktet wird in get_frame nach PTS, nicht von der Pipeline-Uhr
         let pipeline_str = format!(
-            "filesrc location={} ! decodebin ! videoconvert ! video/x-raw,format=RGB ! appsink name=sink",
+            "filesrc location={} ! decodebin ! videoconvert ! video/x-raw,format=RGB ! \
+             appsink name=sink sync=false max-buffers=2",
             video_path.to_str().unwrap()
         );
 
@@ -58,57 +225,459 @@ impl PlaybackCamera {
         pipeline
             .set_state(gst::State::Playing)
             .map_err(|e| PlaybackCameraError::GStreamerError(e.to_string()))?;
+        // Auf Preroll warten, erst dann sind Caps und Dauer verfügbar
+        let _ = pipeline.state(gst::ClockTime::from_seconds(5));
 
-        Ok(Self {
-            pipeline,
-            appsink,
-            camera_id,
-            video_path: video_path.to_path_buf(),
-            loop_playback,
-            frame_count: Arc::new(Mutex::new(0)),
-            current_frame: Arc::new(Mutex::new(0)),
-        })
+        let info = MediaInfo::discover(&pipeline, appsink.static_pad("sink"), video_path);
+
+        Ok((FrameInput::Video { pipeline, appsink }, info))
+    }
+
+    fn open_images(dir: &Path, spec: &ImageSequenceSpec) -> Result<(FrameInput, MediaInfo)> {
+        let sequence = ImageSequence::open(dir, spec)
+            .map_err(|e| PlaybackCameraError::OpenError(e.to_string()))?;
+        let info = MediaInfo::from_image_sequence(&sequence);
+        let last = sequence.len() as i64 - 1;
+
+        Ok((
+            FrameInput::Images {
+                sequence,
+                next: 0,
+                last,
+            },
+            info,
+        ))
     }
 
-    /// Liest den nächsten Frame und gibt ihn als RGB-Buffer zurück
+    pub fn set_pacing(&mut self, pacing: Pacing) {
+        self.pacing = pacing;
+        self.capture_times = match pacing {
+            // Bei Bild-Verzeichnissen enthält der PTS bereits die Zeitstempel-Datei
+            Pacing::RecordedTimestamps if matches!(self.source, FrameInput::Images { .. }) => None,
+            Pacing::RecordedTimestamps => {
+                let times = read_capture_times(&self.video_path);
+                if times.is_none() {
+                    eprintln!(
+                        "Keine Zeitstempel-Datei für {}, verwende PTS",
+                        self.video_path.display()
+                    );
+                }
+                times
+            }
+            _ => None,
+        };
+        self.clock_origin = None;
+        self.last_raw = None;
+    }
+
+    /// Geschwindigkeit, Abspielbereich und Loop-Modus; springt an den In-Punkt
+    pub fn set_playback_settings(&mut self, settings: PlaybackSettings) -> Result<()> {
+        settings
+            .validate()
+            .map_err(PlaybackCameraError::InvalidSettings)?;
+        if let (Some(out_frame), Some(count)) = (settings.out_frame, self.info.frame_count) {
+            if out_frame >= count {
+                return Err(PlaybackCameraError::InvalidSettings(format!(
+                    "Out-Punkt {} hinter dem Ende des Videos ({} Frames)",
+                    out_frame, count
+                )));
+            }
+        }
+
+        self.settings = settings;
+        self.reset()
+    }
+
+    /// Liest den nächsten Frame und gibt ihn als RGB-Buffer zurück.
+    /// Wartet (außer bei `Pacing::AsFastAsPossible`), bis der Frame fällig ist.
+    /// Pausiert oder am Ende (`HoldLast`) wird der aktuelle Frame im Frame-Takt wiederholt,
+    /// wie eine stehende Kamera.
     pub fn get_frame(&mut self) -> Result<Vec<u8>> {
-        match self.appsink.try_pull_sample(gst::ClockTime::from_seconds(1)) {
+        if self.paused || self.holding_last {
+            if self.pacing != Pacing::AsFastAsPossible {
+                std::thread::sleep(self.frame_interval());
+            }
+            return self.current_frame_data();
+        }
+
+        let frame = self.next_decoded()?;
+
+        let wait = self.time_until_due(frame.timestamp);
+        if !wait.is_zero() {
+            std::thread::sleep(wait);
+        }
+
+        self.deliver(frame)
+    }
+
+    /// Nicht blockierend (für den GTK-Timer): den nächsten Frame, sobald er fällig ist
+    pub fn poll_frame(&mut self) -> Result<Option<Vec<u8>>> {
+        if self.paused || self.holding_last {
+            return Ok(None);
+        }
+
+        let frame = self.next_decoded()?;
+
+        if self.time_until_due(frame.timestamp).is_zero() {
+            self.deliver(frame).map(Some)
+        } else {
+            self.pending = Some(frame);
+            Ok(None)
+        }
+    }
+
+    fn next_decoded(&mut self) -> Result<DecodedFrame> {
+        match self.pending.take() {
+            Some(frame) => Ok(frame),
+            None => self.decode_next(),
+        }
+    }
+
+    /// Macht den Frame zum aktuellen (Position, Pausenbild) und gibt seine Daten zurück
+    fn deliver(&mut self, frame: DecodedFrame) -> Result<Vec<u8>> {
+        let data = sample_data(&frame.sample)?;
+        self.position = Some(frame.position);
+        self.last_sample = Some(frame.sample);
+        Ok(data)
+    }
+
+    /// Dekodiert den nächsten Frame mit seinem Zeitstempel (aus der Zeitstempel-Datei
+    /// oder dem PTS); am Ende des Abspielbereichs entscheidet der Loop-Modus
+    fn decode_next(&mut self) -> Result<DecodedFrame> {
+        match self.pull_sample()? {
             Some(sample) => {
-                let buffer = sample.buffer().ok_or_else(|| {
-                    PlaybackCameraError::ReadError("Kein Buffer im Sample".to_string())
-                })?;
+                let pts = sample
+                    .buffer()
+                    .and_then(|buffer| buffer.pts())
+                    .map(|pts| Duration::from_nanos(pts.nseconds()))
+                    .unwrap_or_default();
+
+                let index = *self.current_frame.lock().unwrap();
+                let raw = self
+                    .capture_times
+                    .as_ref()
+                    .and_then(|times| times.get(index.max(0) as usize).copied())
+                    .unwrap_or(pts);
+
+                // Nach einem Sprung (Loop, Umkehr, Seek) einen Frame-Abstand ansetzen
+                let step = match self.last_raw.replace(raw) {
+                    Some(previous) => raw.abs_diff(previous),
+                    None => self.frame_interval(),
+                };
+                self.timeline += step;
 
-                let map = buffer.map_readable().map_err(|e| {
-                    PlaybackCameraError::ReadError(format!("Buffer mapping fehlgeschlagen: {}", e))
-                })?;
+                *self.current_frame.lock().unwrap() += if self.reverse { -1 } else { 1 };
 
-                let data = map.as_slice().to_vec();
-                *self.current_frame.lock().unwrap() += 1;
+                Ok(DecodedFrame {
+                    sample,
+                    timestamp: self.timeline,
+                    position: FramePosition {
+                        index: index.max(0) as u64,
+                        pts,
+                    },
+                })
+            }
+            None => self.end_of_range(),
+        }
+    }
+
+    /// Nächstes Sample der Quelle, None am Ende des Abspielbereichs
+    fn pull_sample(&mut self) -> Result<Option<gst::Sample>> {
+        match &mut self.source {
+            FrameInput::Video { appsink, .. } => {
+                match appsink.try_pull_sample(gst::ClockTime::from_seconds(1)) {
+                    Some(sample) => Ok(Some(sample)),
+                    None if appsink.is_eos() => Ok(None),
+                    None => Err(PlaybackCameraError::ReadError(
+                        "Zeitüberschreitung beim Dekodieren".to_string(),
+                    )),
+                }
+            }
+            FrameInput::Images {
+                sequence,
+                next,
+                last,
+            } => {
+                let step = if self.reverse { -1 } else { 1 };
+                if (*next - *last) * step > 0 {
+                    return Ok(None);
+                }
+                let sample = sequence
+                    .read(*next as usize)
+                    .map_err(|e| PlaybackCameraError::ReadError(e.to_string()))?;
+                *next += step;
+                Ok(Some(sample))
+            }
+        }
+    }
 
-                Ok(data)
+    fn end_of_range(&mut self) -> Result<DecodedFrame> {
+        let ended = || PlaybackCameraError::ReadError("Ende des Videos erreicht".to_string());
+
+        match self.settings.loop_mode {
+            LoopMode::Once => Err(ended()),
+            LoopMode::HoldLast => {
+                let (Some(sample), Some(position)) = (self.last_sample.clone(), self.position)
+                else {
+                    return Err(ended());
+                };
+                self.holding_last = true;
+                Ok(DecodedFrame {
+                    sample,
+                    timestamp: self.timeline,
+                    position,
+                })
             }
-            None => {
-                if self.loop_playback {
-                    self.reset()?;
-                    self.get_frame()
+            LoopMode::Loop | LoopMode::PingPong => {
+                // Seit dem letzten Sprung kein Frame: leerer Bereich, nicht endlos neu starten
+                if self.last_raw.is_none() {
+                    return Err(PlaybackCameraError::ReadError(
+                        "Abspielbereich enthält keine Frames".to_string(),
+                    ));
+                }
+
+                let last = self
+                    .position
+                    .map(|p| p.index)
+                    .unwrap_or(self.settings.in_frame);
+                if self.settings.loop_mode == LoopMode::Loop {
+                    self.seek_range(self.settings.in_frame, false)?;
+                } else if self.reverse {
+                    self.seek_range(last + 1, false)?;
+                } else if last > self.settings.in_frame {
+                    self.seek_range(last - 1, true)?;
                 } else {
-                    Err(PlaybackCameraError::ReadError(
-                        "Ende des Videos erreicht".to_string(),
-                    ))
+                    // Bereich aus einem einzigen Frame
+                    self.seek_range(self.settings.in_frame, false)?;
                 }
+                self.decode_next()
             }
         }
     }
 
-    pub fn reset(&mut self) -> Result<()> {
-        self.pipeline
-            .seek_simple(
-                gst::SeekFlags::FLUSH | gst::SeekFlags::KEY_UNIT,
-                gst::ClockTime::ZERO,
+    /// Spielt ab Frame `first` bis zum Out-Punkt (bzw. rückwärts bis zum In-Punkt)
+    fn seek_range(&mut self, first: u64, reverse: bool) -> Result<()> {
+        match &mut self.source {
+            FrameInput::Video { pipeline, .. } => {
+                Self::seek_video(pipeline, &self.info, &self.settings, first, reverse)?
+            }
+            FrameInput::Images {
+                sequence,
+                next,
+                last,
+            } => {
+                let end = sequence.len() as u64 - 1;
+                if first > end {
+                    return Err(PlaybackCameraError::SeekError(format!(
+                        "Frame {} außerhalb der Bildfolge ({} Bilder)",
+                        first,
+                        sequence.len()
+                    )));
+                }
+                *next = first as i64;
+                *last = if reverse {
+                    self.settings.in_frame as i64
+                } else {
+                    self.settings.out_frame.unwrap_or(end).min(end) as i64
+                };
+            }
+        }
+
+        *self.current_frame.lock().unwrap() = first as i32;
+        self.reverse = reverse;
+        self.pending = None;
+        self.last_raw = None;
+        self.holding_last = false;
+        Ok(())
+    }
+
+    /// Alle Grenzen liegen in der Mitte eines Frames, damit Rundungen der PTS nicht den
+    /// Nachbar-Frame treffen; ACCURATE statt Keyframe-Raster.
+    fn seek_video(
+        pipeline: &gst::Pipeline,
+        info: &MediaInfo,
+        settings: &PlaybackSettings,
+        first: u64,
+        reverse: bool,
+    ) -> Result<()> {
+        let fps = info.fps;
+        let mid = |index: u64| {
+            gst::ClockTime::from_nseconds(((index as f64 + 0.5) / fps * 1e9).round() as u64)
+        };
+
+        let (rate, start, stop) = if fps <= 0.0 {
+            if reverse || first > 0 || settings.out_frame.is_some() {
+                return Err(PlaybackCameraError::SeekError(
+                    "Framerate unbekannt".to_string(),
+                ));
+            }
+            (1.0, Some(gst::ClockTime::ZERO), gst::ClockTime::NONE)
+        } else if reverse {
+            (-1.0, Some(mid(settings.in_frame)), Some(mid(first)))
+        } else {
+            (1.0, Some(mid(first)), settings.out_frame.map(mid))
+        };
+
+        pipeline
+            .seek(
+                rate,
+                gst::SeekFlags::FLUSH | gst::SeekFlags::ACCURATE,
+                gst::SeekType::Set,
+                start,
+                gst::SeekType::Set,
+                stop,
             )
-            .map_err(|e| PlaybackCameraError::GStreamerError(e.to_string()))?;
+            .map_err(|e| PlaybackCameraError::SeekError(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Wie lange bis ein Frame mit diesem Zeitstempel fällig ist (Null = sofort)
+    fn time_until_due(&mut self, timestamp: Duration) -> Duration {
+        if self.pacing == Pacing::AsFastAsPossible {
+            return Duration::ZERO;
+        }
 
-        *self.current_frame.lock().unwrap() = 0;
+        let now = Instant::now();
+        let (origin, origin_ts) = *self.clock_origin.get_or_insert((now, timestamp));
+        let due = origin + timestamp.saturating_sub(origin_ts).div_f64(self.settings.rate);
+
+        if now >= due {
+            if now - due > MAX_LATENESS {
+                // Aufrufer hat lange nicht abgeholt: nicht im Schnelldurchlauf aufholen
+                self.clock_origin = Some((now, timestamp));
+            }
+            Duration::ZERO
+        } else {
+            due - now
+        }
+    }
+
+    /// Frame des letzten Dekodierens (bzw. der nächste, falls noch keiner dekodiert wurde)
+    fn current_frame_data(&mut self) -> Result<Vec<u8>> {
+        match &self.last_sample {
+            Some(sample) => sample_data(sample),
+            None => self.step_forward(),
+        }
+    }
+
+    fn frame_interval(&self) -> Duration {
+        if self.info.fps > 0.0 {
+            Duration::from_secs_f64(1.0 / self.info.fps)
+        } else {
+            Duration::from_millis(33)
+        }
+    }
+
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    /// Setzt die Wiedergabe fort; die Uhr wird am nächsten Frame neu ausgerichtet
+    pub fn resume(&mut self) {
+        self.paused = false;
+        self.clock_origin = None;
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Steht am Ende des Abspielbereichs auf dem letzten Frame (`LoopMode::HoldLast`)
+    pub fn is_holding_last(&self) -> bool {
+        self.holding_last
+    }
+
+    /// Dekodiert sofort den nächsten Frame (ohne Takt) und pausiert
+    pub fn step_forward(&mut self) -> Result<Vec<u8>> {
+        self.paused = true;
+        self.advance()
+    }
+
+    /// Nächster Frame ohne Takt und ohne Pause (Folgekamera im Lock-Step)
+    fn advance(&mut self) -> Result<Vec<u8>> {
+        let frame = self.next_decoded()?;
+        self.deliver(frame)
+    }
+
+    /// Erster und letzter Frame des Abspielbereichs (letzter unbekannt ohne Frame-Anzahl)
+    fn frame_range(&self) -> (u64, Option<u64>) {
+        let last = self
+            .settings
+            .out_frame
+            .or(self.info.frame_count.map(|count| count.saturating_sub(1)));
+        (self.settings.in_frame, last)
+    }
+
+    /// Springt für den Lock-Step an `index` (in den Abspielbereich begrenzt); der nächste
+    /// `advance` liefert diesen Frame
+    fn align_to(&mut self, index: u64, reverse: bool) -> Result<()> {
+        let (first, last) = self.frame_range();
+        let index = index.max(first).min(last.unwrap_or(u64::MAX));
+        self.seek_range(index, reverse)?;
+        self.last_sample = None;
+        self.position = None;
+        Ok(())
+    }
+
+    /// Springt genau einen Frame zurück und pausiert; am In-Punkt bleibt es bei diesem
+    pub fn step_backward(&mut self) -> Result<Vec<u8>> {
+        let target = self
+            .position
+            .map(|position| position.index.saturating_sub(1))
+            .unwrap_or(0)
+            .max(self.settings.in_frame);
+        self.seek_to_frame(target)?;
+        self.step_forward()
+    }
+
+    /// Genaues (nicht auf Keyframes gerundetes) Springen: der nächste ausgelieferte
+    /// Frame hat den Index `index`
+    pub fn seek_to_frame(&mut self, index: u64) -> Result<()> {
+        let fps = self.info.fps;
+        if fps <= 0.0 {
+            return Err(PlaybackCameraError::SeekError(
+                "Framerate unbekannt".to_string(),
+            ));
+        }
+        if let Some(count) = self.info.frame_count {
+            if index >= count {
+                return Err(PlaybackCameraError::SeekError(format!(
+                    "Frame {} außerhalb des Videos ({} Frames)",
+                    index, count
+                )));
+            }
+        }
+        let out_frame = self.settings.out_frame.unwrap_or(u64::MAX);
+        if index < self.settings.in_frame || index > out_frame {
+            return Err(PlaybackCameraError::SeekError(format!(
+                "Frame {} außerhalb des Abspielbereichs",
+                index
+            )));
+        }
+
+        self.seek_range(index, false)?;
+        self.clock_origin = None;
+        self.last_sample = None;
+        Ok(())
+    }
+
+    /// Springt zu dem Frame, der zum Zeitpunkt `time` (PTS) angezeigt wird
+    pub fn seek_to_time(&mut self, time: Duration) -> Result<()> {
+        let index = (time.as_secs_f64() * self.info.fps).floor() as u64;
+        self.seek_to_frame(index)
+    }
+
+    /// Index und PTS des zuletzt ausgelieferten Frames
+    pub fn get_position(&self) -> Option<FramePosition> {
+        self.position
+    }
+
+    /// Zurück an den In-Punkt (vorwärts), die Uhr wird neu ausgerichtet
+    pub fn reset(&mut self) -> Result<()> {
+        self.seek_range(self.settings.in_frame, false)?;
+        self.clock_origin = None;
+        self.last_sample = None;
+        self.position = None;
         Ok(())
     }
 
@@ -116,12 +685,17 @@ impl PlaybackCamera {
         self.camera_id
     }
 
+    pub fn get_media_info(&self) -> &MediaInfo {
+        &self.info
+    }
+
     pub fn get_fps(&self) -> f64 {
-        30.0
+        self.info.fps
     }
 
+    /// 0, wenn die Frame-Anzahl nicht ermittelt werden konnte
     pub fn get_frame_count(&self) -> i32 {
-        *self.frame_count.lock().unwrap()
+        self.info.frame_count.unwrap_or(0) as i32
     }
 
     pub fn get_current_frame(&self) -> i32 {
@@ -137,8 +711,32 @@ impl PlaybackCamera {
         }
     }
 
+    /// Bei unbekannter Frame-Anzahl erst mit `HoldLast` am Ende fertig (sonst meldet
+    /// das Ende `get_frame`)
     pub fn is_finished(&self) -> bool {
-        self.get_current_frame() >= self.get_frame_count()
+        self.holding_last
+            || self
+                .info
+                .frame_count
+                .is_some_and(|count| self.get_current_frame() as u64 >= count)
+    }
+
+    fn frame_status(&self) -> String {
+        let total = self
+            .info
+            .frame_count
+            .map(|n| n.to_string())
+            .unwrap_or_else(|| "?".to_string());
+        let info = self.get_media_info();
+        format!(
+            "{} ({}/{}, {}x{} @ {:.2} FPS)",
+            self.get_video_path().file_name().unwrap().to_string_lossy(),
+            self.get_current_frame(),
+            total,
+            info.width,
+            info.height,
+            info.fps
+        )
     }
 
     pub fn get_video_path(&self) -> &Path {
@@ -146,43 +744,122 @@ impl PlaybackCamera {
     }
 }
 
+fn sample_data(sample: &gst::Sample) -> Result<Vec<u8>> {
+    let buffer = sample
+        .buffer()
+        .ok_or_else(|| PlaybackCameraError::ReadError("Kein Buffer im Sample".to_string()))?;
+
+    let map = buffer.map_readable().map_err(|e| {
+        PlaybackCameraError::ReadError(format!("Buffer mapping fehlgeschlagen: {}", e))
+    })?;
+
+    Ok(map.as_slice().to_vec())
+}
+
 /// Verwaltet zwei PlaybackCameras (links und rechts) für Stereo-Simulation
+///
+/// Im Lock-Step gibt die linke Kamera Takt, Geschwindigkeit und Loop vor; die rechte
+/// wird bei jedem linken Frame auf den passenden Frame nachgezogen.
 pub struct StereoPlaybackSystem {
     left_camera: Option<PlaybackCamera>,
     right_camera: Option<PlaybackCamera>,
+    sync: Option<StereoSync>,
+    /// Sensor-Modell (links, rechts) auf den dekodierten Frames von `poll_frames`
+    sensors: Option<[SensorModel; 2]>,
+    /// Fehler-Injektion (links, rechts) nach dem Sensor-Modell
+    faults: Option<[FaultInjector; 2]>,
+    fault_log: FaultLog,
+    /// Zuletzt übernommene Konfiguration, bezogen auf die ungetauschten Seiten
+    config: Option<SimulationConfig>,
+    /// Seiten mit `swap` getauscht; die Konfiguration gehört weiter zu ihrer Aufnahme
+    swapped: bool,
+}
+
+/// Paarung im Lock-Step: rechter Schlüssel = linker Schlüssel - `offset`
+#[derive(Debug, Clone, Copy)]
+struct StereoSync {
+    by: SyncBy,
+    /// Sekunden (Timestamp) bzw. Frames (FrameIndex)
+    offset: f64,
 }
 
+impl StereoSync {
+    /// PTS in Sekunden bzw. Frame-Index
+    fn key(&self, position: &FramePosition) -> f64 {
+        match self.by {
+            SyncBy::Timestamp => position.pts.as_secs_f64(),
+            SyncBy::FrameIndex => position.index as f64,
+        }
+    }
+
+    /// Schlüssel des Frames `index` einer Kamera
+    fn key_of_frame(&self, index: u64, fps: f64) -> f64 {
+        match self.by {
+            SyncBy::Timestamp => index as f64 / fps,
+            SyncBy::FrameIndex => index as f64,
+        }
+    }
+
+    /// Frame einer Kamera zu einem Schlüssel (abgerundet)
+    fn frame_of_key(&self, key: f64, fps: f64) -> f64 {
+        match self.by {
+            SyncBy::Timestamp => (key * fps + 1e-6).floor(),
+            SyncBy::FrameIndex => key.round(),
+        }
+    }
+}
+
+/// Liegt die rechte Kamera weiter zurück, wird gesprungen statt dekodiert
+const MAX_FOLLOW_FRAMES: u32 = 30;
+
 impl StereoPlaybackSystem {
     pub fn new() -> Self {
         Self {
             left_camera: None,
             right_camera: None,
+            sync: None,
+            sensors: None,
+            faults: None,
+            fault_log: FaultLog::new(),
+            config: None,
+            swapped: false,
         }
     }
 
-    pub fn load_from_directory(recording_dir: &Path) -> Result<Self> {
+    /// Lädt die Aufnahmen einer Session (links: kleinste Kamera-ID). Ohne zweite Kamera
+    /// bleibt die rechte Seite leer.
+    pub fn load_session(session: &RecordingSession) -> Result<Self> {
         let mut system = Self::new();
+        system.left_camera = Some(Self::open_recording(0, session.left())?);
+        if let Some(right) = session.right() {
+            system.right_camera = Some(Self::open_recording(1, right)?);
+        }
+        Ok(system)
+    }
 
-        let recordings = find_recordings_in_dir(recording_dir)?;
-
-        if recordings.len() >= 2 {
-            system.left_camera = Some(PlaybackCamera::new(0, &recordings[0], true)?);
-            system.right_camera = Some(PlaybackCamera::new(1, &recordings[1], true)?);
-
-            println!("Linke Kamera: {}", recordings[0].display());
-            println!("Rechte Kamera: {}", recordings[1].display());
-        } else if recordings.len() == 1 {
-            system.left_camera = Some(PlaybackCamera::new(0, &recordings[0], true)?);
-            system.right_camera = Some(PlaybackCamera::new(1, &recordings[0], true)?);
+    fn open_recording(camera_id: u32, recording: &Recording) -> Result<PlaybackCamera> {
+        match &recording.sequence {
+            Some(spec) => PlaybackCamera::from_image_sequence(camera_id, &recording.path, spec, true),
+            None => PlaybackCamera::new(camera_id, &recording.path, true),
+        }
+    }
 
-            println!("Nur ein Video gefunden, wird für beide Kameras verwendet");
-        } else {
-            return Err(PlaybackCameraError::OpenError(
-                "Keine Video-Dateien im Verzeichnis gefunden".to_string(),
-            ));
+    /// Tauscht linke und rechte Kamera. Sensor, Fehler und Abspiel-Einstellungen
+    /// wandern mit, der Lock-Step-Versatz wechselt das Vorzeichen und der gemeinsame
+    /// Abspielbereich wird neu bestimmt.
+    pub fn swap(&mut self) -> Result<()> {
+        std::mem::swap(&mut self.left_camera, &mut self.right_camera);
+        for (cam, id) in [(&mut self.left_camera, 0), (&mut self.right_camera, 1)] {
+            if let Some(cam) = cam {
+                cam.camera_id = id;
+            }
         }
+        self.swapped = !self.swapped;
 
-        Ok(system)
+        match self.config.take() {
+            Some(config) => self.apply_config(&config),
+            None => Ok(()),
+        }
     }
 
     pub fn set_left_camera(&mut self, video_path: &Path) -> Result<()> {
@@ -195,6 +872,7 @@ impl StereoPlaybackSystem {
         Ok(())
     }
 
+    /// Einzelne Seite ohne Lock-Step
     pub fn get_left_frame(&mut self) -> Result<Vec<u8>> {
         self.left_camera
             .as_mut()
@@ -209,9 +887,336 @@ impl StereoPlaybackSystem {
             .get_frame()
     }
 
+    /// Nicht blockierend, siehe `PlaybackCamera::poll_frame`. Im Lock-Step kommen
+    /// beide Frames zusammen, sobald der linke fällig ist.
+    pub fn poll_frames(&mut self) -> Result<(Option<Vec<u8>>, Option<Vec<u8>>)> {
+        let left = match &mut self.left_camera {
+            Some(cam) => cam.poll_frame()?,
+            None => None,
+        };
+
+        let right = if self.sync.is_some() {
+            match left {
+                Some(_) => self.follow()?,
+                None => None,
+            }
+        } else {
+            match &mut self.right_camera {
+                Some(cam) => cam.poll_frame()?,
+                None => None,
+            }
+        };
+        let (left, right) = self.apply_sensors(left, right)?;
+
+        let lock_step = self.sync.is_some();
+        let Some([left_faults, right_faults]) = &mut self.faults else {
+            return Ok((left, right));
+        };
+        let now = Instant::now();
+        let (left_pos, right_pos) = (
+            self.left_camera.as_ref().and_then(|cam| cam.get_position()),
+            self.right_camera.as_ref().and_then(|cam| cam.get_position()),
+        );
+        let push = |injector: &mut FaultInjector, data: Option<Vec<u8>>, position: Option<FramePosition>| {
+            data.map(|data| {
+                let pts = position.map(|p| p.pts).unwrap_or_default();
+                injector.push(SourceFrame { data, pts }, now)
+            })
+        };
+        let left_queued = push(left_faults, left, left_pos);
+        let right_queued = push(right_faults, right, right_pos);
+        // Im Lock-Step gehen Paare nur gemeinsam verloren
+        if lock_step {
+            match (left_queued, right_queued) {
+                (Some(true), Some(false)) => left_faults.drop_last(now),
+                (Some(false), Some(true)) => right_faults.drop_last(now),
+                _ => {}
+            }
+        }
+        Ok((
+            left_faults.poll(now).map(|frame| frame.data),
+            right_faults.poll(now).map(|frame| frame.data),
+        ))
+    }
+
+    /// Sensor-Modell auf die frisch dekodierten Frames, vor der Fehler-Injektion
+    fn apply_sensors(
+        &mut self,
+        left: Option<Vec<u8>>,
+        right: Option<Vec<u8>>,
+    ) -> Result<(Option<Vec<u8>>, Option<Vec<u8>>)> {
+        let Some(sensors) = &mut self.sensors else {
+            return Ok((left, right));
+        };
+
+        let mut frames = [left, right];
+        for ((frame, sensor), cam) in frames
+            .iter_mut()
+            .zip(sensors.iter_mut())
+            .zip([&self.left_camera, &self.right_camera])
+        {
+            if let (Some(data), Some(cam)) = (frame.as_mut(), cam) {
+                *data = sensor
+                    .apply(data, cam.info.width, cam.info.height)
+                    .map_err(|e| PlaybackCameraError::ReadError(e.to_string()))?;
+            }
+        }
+        let [left, right] = frames;
+        Ok((left, right))
+    }
+
+    /// Zieht die rechte Kamera auf den Frame nach, der zum aktuellen linken passt.
+    ///
+    /// Liegt sie wenige Frames zurück (z.B. nach einem Dekodier-Hänger), wird aufgeholt;
+    /// ist sie voraus, weit zurück oder läuft in die andere Richtung, wird gesprungen.
+    fn follow(&mut self) -> Result<Option<Vec<u8>>> {
+        let (Some(sync), Some(left), Some(right)) =
+            (self.sync, &self.left_camera, &mut self.right_camera)
+        else {
+            return Ok(None);
+        };
+        let Some(master) = left.get_position() else {
+            return Ok(None);
+        };
+
+        let target = sync.key(&master) - sync.offset;
+        let reverse = left.reverse;
+        let direction = if reverse { -1.0 } else { 1.0 };
+        let tolerance = match sync.by {
+            SyncBy::Timestamp => right.frame_interval().as_secs_f64() / 2.0,
+            SyncBy::FrameIndex => 0.5,
+        };
+        let skip_limit = tolerance * 2.0 * MAX_FOLLOW_FRAMES as f64;
+
+        let mut sought = false;
+        if right.reverse != reverse {
+            let index = sync.frame_of_key(target, right.info.fps).max(0.0) as u64;
+            right.align_to(index, reverse)?;
+            sought = true;
+        }
+
+        for _ in 0..MAX_FOLLOW_FRAMES * 2 {
+            let behind = right
+                .get_position()
+                .map(|position| (target - sync.key(&position)) * direction);
+
+            match behind {
+                Some(behind) if behind.abs() <= tolerance => break,
+                Some(behind) if behind < 0.0 || behind > skip_limit => {
+                    // Nach einem Sprung: Ziel liegt außerhalb oder zwischen zwei Frames
+                    if sought {
+                        break;
+                    }
+                    let index = sync.frame_of_key(target, right.info.fps).max(0.0) as u64;
+                    right.align_to(index, reverse)?;
+                    sought = true;
+                }
+                _ => {
+                    right.advance()?;
+                }
+            }
+        }
+
+        right.current_frame_data().map(Some)
+    }
+
+    /// Begrenzt den Abspielbereich der linken Kamera auf den Teil, zu dem es rechte
+    /// Frames gibt; beide loopen damit gemeinsam an der kürzeren Aufnahme
+    fn restrict_to_overlap(&mut self) -> Result<()> {
+        let (Some(sync), Some(left), Some(right)) =
+            (self.sync, &mut self.left_camera, &self.right_camera)
+        else {
+            return Ok(());
+        };
+        if sync.by == SyncBy::Timestamp && (left.info.fps <= 0.0 || right.info.fps <= 0.0) {
+            return Ok(());
+        }
+
+        let (right_first, right_last) = right.frame_range();
+        let low = sync.key_of_frame(right_first, right.info.fps) + sync.offset;
+        let (mut in_frame, mut out_frame) = left.frame_range();
+
+        let first = sync.frame_of_key(low, left.info.fps);
+        // Abgerundet, daher ggf. einen Frame später beginnen
+        let first = if sync.key_of_frame(first.max(0.0) as u64, left.info.fps) < low - 1e-6 {
+            first + 1.0
+        } else {
+            first
+        };
+        in_frame = in_frame.max(first.max(0.0) as u64);
+
+        if let Some(right_last) = right_last {
+            let high = sync.key_of_frame(right_last, right.info.fps) + sync.offset;
+            if high < 0.0 {
+                return Err(PlaybackCameraError::InvalidSettings(
+                    "Linke und rechte Aufnahme überlappen nicht".to_string(),
+                ));
+            }
+            let last = sync.frame_of_key(high, left.info.fps) as u64;
+            out_frame = Some(out_frame.map_or(last, |out| out.min(last)));
+        }
+
+        let mut settings = left.settings;
+        settings.in_frame = in_frame;
+        settings.out_frame = out_frame;
+        if settings != left.settings {
+            left.set_playback_settings(settings).map_err(|_| {
+                PlaybackCameraError::InvalidSettings(
+                    "Linke und rechte Aufnahme überlappen nicht".to_string(),
+                )
+            })?;
+        }
+        Ok(())
+    }
+
+    pub fn set_pacing(&mut self, pacing: Pacing) {
+        for cam in [&mut self.left_camera, &mut self.right_camera].into_iter().flatten() {
+            cam.set_pacing(pacing);
+        }
+    }
+
+    /// Übernimmt Takt, Abspiel-Einstellungen und Lock-Step aus der Konfiguration.
+    /// Nach `swap` gelten die Seiten der Konfiguration für die ursprünglichen Aufnahmen.
+    pub fn apply_config(&mut self, config: &SimulationConfig) -> Result<()> {
+        self.config = Some(config.clone());
+        let config = &if self.swapped {
+            config.swapped()
+        } else {
+            config.clone()
+        };
+
+        self.sync = None;
+        self.set_pacing(config.pacing);
+        self.sensors = config.sensor.map(|sensor| sensor.models());
+        self.faults = config.faults.map(|faults| faults.injectors(&self.fault_log));
+        if let Some(cam) = &mut self.left_camera {
+            cam.set_playback_settings(config.left)?;
+        }
+        if let Some(cam) = &mut self.right_camera {
+            let mut settings = config.right;
+            if config.lock_step {
+                // Loop und Geschwindigkeit gibt die linke Kamera vor
+                settings.loop_mode = LoopMode::Loop;
+            }
+            cam.set_playback_settings(settings)?;
+        }
+
+        if let (true, Some(left), Some(right)) =
+            (config.lock_step, &self.left_camera, &self.right_camera)
+        {
+            let offset_secs = config
+                .sync_offset_ms
+                .map(|ms| ms / 1000.0)
+                .or_else(|| {
+                    let (left_start, right_start) = (left.info.started_at?, right.info.started_at?);
+                    let offset = (right_start - left_start).num_microseconds()?;
+                    Some(offset as f64 / 1e6)
+                })
+                .unwrap_or(0.0);
+
+            let offset = match config.sync_by {
+                SyncBy::Timestamp => offset_secs,
+                SyncBy::FrameIndex => (offset_secs * left.info.fps).round(),
+            };
+            self.sync = Some(StereoSync {
+                by: config.sync_by,
+                offset,
+            });
+            self.restrict_to_overlap()?;
+        }
+        Ok(())
+    }
+
+    pub fn pause(&mut self) {
+        for cam in [&mut self.left_camera, &mut self.right_camera].into_iter().flatten() {
+            cam.pause();
+        }
+    }
+
+    pub fn resume(&mut self) {
+        for cam in [&mut self.left_camera, &mut self.right_camera].into_iter().flatten() {
+            cam.resume();
+        }
+    }
+
+    pub fn is_paused(&self) -> bool {
+        if self.sync.is_some() {
+            return self.left_camera.as_ref().is_some_and(|cam| cam.is_paused());
+        }
+        [&self.left_camera, &self.right_camera]
+            .into_iter()
+            .flatten()
+            .any(|cam| cam.is_paused())
+    }
+
+    /// Einzelschritt auf beiden Seiten, liefert (links, rechts)
+    pub fn step_forward(&mut self) -> Result<(Option<Vec<u8>>, Option<Vec<u8>>)> {
+        let left = self.left_camera.as_mut().map(|cam| cam.step_forward()).transpose()?;
+        let right = match self.sync {
+            Some(_) => self.follow()?,
+            None => self.right_camera.as_mut().map(|cam| cam.step_forward()).transpose()?,
+        };
+        Ok((left, right))
+    }
+
+    pub fn step_backward(&mut self) -> Result<(Option<Vec<u8>>, Option<Vec<u8>>)> {
+        let left = self.left_camera.as_mut().map(|cam| cam.step_backward()).transpose()?;
+        let right = match self.sync {
+            Some(_) => self.follow()?,
+            None => self.right_camera.as_mut().map(|cam| cam.step_backward()).transpose()?,
+        };
+        Ok((left, right))
+    }
+
+    /// Im Lock-Step springt nur die linke Kamera, die rechte folgt beim nächsten Frame
+    pub fn seek_to_frame(&mut self, index: u64) -> Result<()> {
+        if let Some(cam) = &mut self.left_camera {
+            cam.seek_to_frame(index)?;
+        }
+        if let (None, Some(cam)) = (self.sync, &mut self.right_camera) {
+            cam.seek_to_frame(index)?;
+        }
+        Ok(())
+    }
+
+    pub fn seek_to_time(&mut self, time: Duration) -> Result<()> {
+        if let Some(cam) = &mut self.left_camera {
+            cam.seek_to_time(time)?;
+        }
+        if let (None, Some(cam)) = (self.sync, &mut self.right_camera) {
+            cam.seek_to_time(time)?;
+        }
+        Ok(())
+    }
+
+    /// Protokoll der injizierten Fehler seit dem Laden
+    pub fn fault_log(&self) -> &FaultLog {
+        &self.fault_log
+    }
+
+    /// Eckdaten (links, rechts) der geladenen Aufnahmen
+    pub fn get_media_infos(&self) -> (Option<&MediaInfo>, Option<&MediaInfo>) {
+        (
+            self.left_camera.as_ref().map(|cam| cam.get_media_info()),
+            self.right_camera.as_ref().map(|cam| cam.get_media_info()),
+        )
+    }
+
+    /// Position (links, rechts) des zuletzt ausgelieferten Frames
+    pub fn get_positions(&self) -> (Option<FramePosition>, Option<FramePosition>) {
+        (
+            self.left_camera.as_ref().and_then(|cam| cam.get_position()),
+            self.right_camera.as_ref().and_then(|cam| cam.get_position()),
+        )
+    }
+
+    /// Im Lock-Step wird nach dem linken Frame getaktet und der rechte passend nachgezogen
     pub fn get_both_frames(&mut self) -> Result<(Vec<u8>, Vec<u8>)> {
         let left = self.get_left_frame()?;
-        let right = self.get_right_frame()?;
+        let right = match self.follow()? {
+            Some(right) => right,
+            None => self.get_right_frame()?,
+        };
         Ok((left, right))
     }
 
@@ -227,23 +1232,13 @@ impl StereoPlaybackSystem {
 
     pub fn get_status(&self) -> String {
         let left_status = if let Some(cam) = &self.left_camera {
-            format!(
-                "Links: {} ({}/{})",
-                cam.get_video_path().file_name().unwrap().to_string_lossy(),
-                cam.get_current_frame(),
-                cam.get_frame_count()
-            )
+            format!("Links: {}", cam.frame_status())
         } else {
             "Links: Nicht geladen".to_string()
         };
 
         let right_status = if let Some(cam) = &self.right_camera {
-            format!(
-                "Rechts: {} ({}/{})",
-                cam.get_video_path().file_name().unwrap().to_string_lossy(),
-                cam.get_current_frame(),
-                cam.get_frame_count()
-            )
+            format!("Rechts: {}", cam.frame_status())
         } else {
             "Rechts: Nicht geladen".to_string()
         };
@@ -251,30 +1246,3 @@ impl StereoPlaybackSystem {
         format!("{}\n{}", left_status, right_status)
     }
 }
-
-fn find_recordings_in_dir(dir: &Path) -> Result<Vec<PathBuf>> {
-    if !dir.exists() {
-        return Err(PlaybackCameraError::OpenError(format!(
-            "Verzeichnis nicht gefunden: {}",
-            dir.display()
-        )));
-    }
-
-    let mut recordings = Vec::new();
-
-    for entry in std::fs::read_dir(dir)
-        .map_err(|e| PlaybackCameraError::OpenError(e.to_string()))?
-    {
-        let entry = entry.map_err(|e| PlaybackCameraError::OpenError(e.to_string()))?;
-        let path = entry.path();
-
-        if let Some(ext) = path.extension() {
-            if ext == "mp4" || ext == "avi" || ext == "mkv" {
-                recordings.push(path);
-            }
-        }
-    }
-
-    recordings.sort();
-    Ok(recordings)
-}
@@ -1,10 +1,10 @@
 use gstreamer as gst;
 use gstreamer::prelude::*;
 use std::path::Path;
-use std::thread;
-use std::time::Duration;
 use thiserror::Error;
 
+use crate::media_info::MediaInfo;
+
 #[derive(Error, Debug)]
 pub enum PlayerError {
     #[error("Video konnte nicht geöffnet werden: {0}")]
@@ -19,7 +19,7 @@ pub type Result<T> = std::result::Result<T, PlayerError>;
 
 pub struct VideoPlayer {
     pipeline: gst::Pipeline,
-    duration: Option<gst::ClockTime>,
+    info: MediaInfo,
 }
 
 impl VideoPlayer {
@@ -34,7 +34,7 @@ impl VideoPlayer {
         gst::init().map_err(|e| PlayerError::GStreamerError(e.to_string()))?;
 
         let pipeline_str = format!(
-            "filesrc location={} ! decodebin ! videoconvert ! autovideosink",
+            "filesrc location={} ! decodebin ! videoconvert name=convert ! autovideosink",
             video_path.to_str().unwrap()
         );
 
@@ -43,10 +43,18 @@ impl VideoPlayer {
             .downcast::<gst::Pipeline>()
             .map_err(|_| PlayerError::PipelineError("Kein Pipeline Element".to_string()))?;
 
-        Ok(Self {
-            pipeline,
-            duration: None,
-        })
+        // Vorrollen, damit Caps und Dauer schon vor play() bekannt sind
+        pipeline
+            .set_state(gst::State::Paused)
+            .map_err(|e| PlayerError::PipelineError(e.to_string()))?;
+        let _ = pipeline.state(gst::ClockTime::from_seconds(5));
+
+        let pad = pipeline
+            .by_name("convert")
+            .and_then(|convert| convert.static_pad("sink"));
+        let info = MediaInfo::discover(&pipeline, pad, video_path);
+
+        Ok(Self { pipeline, info })
     }
 
     pub fn play(&mut self) -> Result<()> {
@@ -54,20 +62,6 @@ impl VideoPlayer {
             .set_state(gst::State::Playing)
             .map_err(|e| PlayerError::PipelineError(e.to_string()))?;
 
-        let mut retries = 10;
-        while self.duration.is_none() && retries > 0 {
-            if let Some(duration) = self.pipeline.query_duration::<gst::ClockTime>() {
-                self.duration = Some(duration);
-                println!(
-                    "Video-Dauer: {:.2} Sekunden",
-                    duration.seconds() as f64
-                );
-            } else {
-                thread::sleep(Duration::from_millis(100));
-                retries -= 1;
-            }
-        }
-
         let bus = self
             .pipeline
             .bus()
@@ -105,16 +99,16 @@ impl VideoPlayer {
         Ok(())
     }
 
+    pub fn get_media_info(&self) -> &MediaInfo {
+        &self.info
+    }
+
     pub fn get_fps(&self) -> f64 {
-        30.0
+        self.info.fps
     }
 
     pub fn get_frame_count(&self) -> i32 {
-        if let Some(duration) = self.duration {
-            (duration.seconds() as f64 * self.get_fps()) as i32
-        } else {
-            0
-        }
+        self.info.frame_count.unwrap_or(0) as i32
     }
 
     pub fn reset(&mut self) -> Result<()> {
@@ -1,4 +1,4 @@
-use chrono::Local;
+use chrono::{DateTime, Local, SecondsFormat};
 use gstreamer as gst;
 use gstreamer::prelude::*;
 use gstreamer_app as gst_app;
@@ -8,6 +8,12 @@ use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 use thiserror::Error;
 
+use crate::camera_watchdog::CameraOutage;
+use crate::frame_clock::FrameClockStats;
+use crate::frame_queue::{DropPolicy, QueueStats};
+use crate::frame_timestamps::{TimestampWriter, sidecar_path};
+use crate::pipeline_health::{BusWatcher, PipelineIssue};
+
 #[derive(Error, Debug)]
 pub enum RecorderError {
     #[error("GStreamer Fehler: {0}")]
@@ -16,6 +22,10 @@ pub enum RecorderError {
     PipelineError(String),
     #[error("Frame konnte nicht geschrieben werden: {0}")]
     WriteError(String),
+    #[error("Pipeline ausgefallen: {0}")]
+    PipelineFailed(String),
+    #[error("Kamera liefert das benötigte Format nicht: {0}")]
+    FormatError(String),
     #[error("Metadaten konnten nicht gespeichert werden: {0}")]
     MetadataError(String),
     #[error("IO Fehler: {0}")]
@@ -33,6 +43,96 @@ pub struct RecordingMetadata {
     pub width: i32,
     pub height: i32,
     pub filename: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub codec: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub queue_policy: Option<DropPolicy>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dropped_frames: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timing: Option<FrameClockStats>,
+    /// Zeiträume ohne Frames, weil die Kamera getrennt war
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub outages: Option<Vec<CameraOutage>>,
+    /// CSV mit PTS und Capture-Zeitpunkt pro Frame (Original-Jitter für die Wiedergabe)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamps_file: Option<String>,
+    /// Wanduhrzeit des ersten Frames (RFC 3339, µs), für den Versatz zwischen Stereo-Kameras
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub first_frame_at: Option<String>,
+}
+
+/// Wartet bis EOS den Muxer erreicht hat (max. 5s) und stoppt die Pipeline.
+///
+/// Einen Fehler, den der (bereits gestoppte) `BusWatcher` vorher vom Bus genommen hat,
+/// sieht der Bus nicht mehr; er wird hier gemeldet, ebenso ein ausbleibendes EOS.
+/// Die Datei ist dann unvollständig und bekommt keine Metadaten.
+pub(crate) fn wait_for_eos(pipeline: &gst::Pipeline, health: &BusWatcher) -> Result<()> {
+    if let Some(issue) = health.error() {
+        let _ = pipeline.set_state(gst::State::Null);
+        return Err(RecorderError::PipelineFailed(issue.to_string()));
+    }
+
+    let bus = pipeline
+        .bus()
+        .ok_or_else(|| RecorderError::PipelineError("Kein Bus".to_string()))?;
+
+    let mut eos = false;
+    for msg in bus.iter_timed(gst::ClockTime::from_seconds(5)) {
+        use gst::MessageView;
+
+        match msg.view() {
+            MessageView::Eos(..) => {
+                eos = true;
+                break;
+            }
+            MessageView::Error(_) => {
+                let _ = pipeline.set_state(gst::State::Null);
+                let issue = PipelineIssue::from_message(&pipeline.name(), &msg);
+                return Err(RecorderError::GStreamerError(
+                    issue.map(|i| i.to_string()).unwrap_or_default(),
+                ));
+            }
+            _ => (),
+        }
+    }
+
+    pipeline
+        .set_state(gst::State::Null)
+        .map_err(|e| RecorderError::PipelineError(e.to_string()))?;
+    if !eos {
+        return Err(RecorderError::PipelineFailed(
+            "Kein EOS innerhalb von 5 s, Datei ist unvollständig".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Pfad im Ausgabeverzeichnis, der noch nicht existiert (`_1`, `_2`, ... bei Kollision,
+/// z.B. bei einem Neustart innerhalb derselben Sekunde)
+pub(crate) fn unique_output_path(output_dir: &Path, stem: &str, extension: &str) -> PathBuf {
+    let mut path = output_dir.join(format!("{}.{}", stem, extension));
+    let mut n = 1;
+    while path.exists() {
+        path = output_dir.join(format!("{}_{}.{}", stem, n, extension));
+        n += 1;
+    }
+    path
+}
+
+/// Schreibt die Metadaten als JSON neben die Videodatei
+pub(crate) fn write_metadata(output_path: &Path, metadata: &RecordingMetadata) -> Result<()> {
+    let metadata_path = output_path.with_extension("json");
+    let metadata_json = serde_json::to_string_pretty(metadata)
+        .map_err(|e| RecorderError::MetadataError(e.to_string()))?;
+    fs::write(&metadata_path, metadata_json)?;
+    Ok(())
+}
+
+/// Liest die Metadaten-JSON zu einem Video, falls vorhanden
+pub(crate) fn read_metadata(video_path: &Path) -> Option<RecordingMetadata> {
+    let json = fs::read_to_string(video_path.with_extension("json")).ok()?;
+    serde_json::from_str(&json).ok()
 }
 
 pub struct VideoRecorder {
@@ -46,6 +146,12 @@ pub struct VideoRecorder {
     height: i32,
     output_path: PathBuf,
     is_recording: Arc<Mutex<bool>>,
+    queue_stats: Option<(DropPolicy, QueueStats)>,
+    timing: Option<FrameClockStats>,
+    outages: Vec<CameraOutage>,
+    timestamps: Option<TimestampWriter>,
+    first_frame_at: Option<DateTime<Local>>,
+    health: BusWatcher,
 }
 
 impl VideoRecorder {
@@ -61,8 +167,8 @@ impl VideoRecorder {
         fs::create_dir_all(output_dir)?;
 
         let timestamp = Local::now().format("%Y%m%d_%H%M%S");
-        let filename = format!("camera_{}__{}.mp4", camera_id, timestamp);
-        let output_path = output_dir.join(&filename);
+        let output_path =
+            unique_output_path(output_dir, &format!("camera_{}__{}", camera_id, timestamp), "mp4");
 
         let pipeline_str = format!(
             "appsrc name=src ! videoconvert ! video/x-raw,format=I420,width={},height={} ! \
@@ -93,11 +199,18 @@ impl VideoRecorder {
         appsrc.set_property("format", gst::Format::Time);
         appsrc.set_property("is-live", true);
         appsrc.set_property("do-timestamp", true);
+        // Begrenzt den internen Puffer auf wenige Frames; push_buffer blockiert dann
+        // statt unbegrenzt Speicher zu belegen
+        appsrc.set_property("max-bytes", (width * height * 3) as u64 * 4);
+        appsrc.set_property("block", true);
 
         pipeline
             .set_state(gst::State::Playing)
             .map_err(|e| RecorderError::PipelineError(e.to_string()))?;
 
+        let health = BusWatcher::watch(&format!("recorder camera {}", camera_id), &pipeline);
+        let timestamps = TimestampWriter::create(&output_path)?;
+
         std::thread::sleep(std::time::Duration::from_millis(100));
 
         Ok(Self {
@@ -111,14 +224,30 @@ impl VideoRecorder {
             height,
             output_path,
             is_recording: Arc::new(Mutex::new(true)),
+            queue_stats: None,
+            timing: None,
+            outages: Vec::new(),
+            timestamps: Some(timestamps),
+            first_frame_at: None,
+            health,
         })
     }
 
     pub fn write_frame(&mut self, frame_data: &[u8]) -> Result<()> {
+        self.write_frame_at(frame_data, std::time::Instant::now())
+    }
+
+    /// Wie `write_frame`, mit dem Zeitpunkt, zu dem die Kamera den Frame geliefert hat
+    /// (kann bei einer Encoder-Queue deutlich vor dem Schreiben liegen)
+    pub fn write_frame_at(&mut self, frame_data: &[u8], captured_at: std::time::Instant) -> Result<()> {
         if !*self.is_recording.lock().unwrap() {
             return Ok(());
         }
 
+        if let Some(issue) = self.health.error() {
+            return Err(RecorderError::PipelineFailed(issue.to_string()));
+        }
+
         let buffer_size = (self.width * self.height * 3) as usize;
         if frame_data.len() < buffer_size {
             return Err(RecorderError::WriteError(format!(
@@ -139,6 +268,11 @@ impl VideoRecorder {
             map.copy_from_slice(&frame_data[..buffer_size]);
         }
 
+        if self.first_frame_at.is_none() {
+            let age = chrono::Duration::from_std(captured_at.elapsed()).unwrap_or_default();
+            self.first_frame_at = Some(Local::now() - age);
+        }
+
         let mut count = self.frame_count.lock().unwrap();
         let duration_per_frame = gst::ClockTime::from_nseconds(
             (1_000_000_000.0 / self.fps) as u64
@@ -149,48 +283,66 @@ impl VideoRecorder {
         buffer_ref.set_pts(pts);
         buffer_ref.set_duration(duration_per_frame);
 
+        if let Some(timestamps) = &mut self.timestamps {
+            timestamps.write(*count, std::time::Duration::from_nanos(pts.nseconds()), captured_at)?;
+        }
+
         *count += 1;
 
-        self.appsrc
-            .push_buffer(buffer)
-            .map_err(|e| RecorderError::WriteError(e.to_string()))?;
+        self.appsrc.push_buffer(buffer).map_err(|e| match self.health.error() {
+            Some(issue) => RecorderError::PipelineFailed(issue.to_string()),
+            None => RecorderError::WriteError(e.to_string()),
+        })?;
 
         Ok(())
     }
 
-    pub fn finalize(self) -> Result<RecordingMetadata> {
+    /// Queue-Policy und verworfene Frames für die Metadaten
+    pub fn set_queue_stats(&mut self, policy: DropPolicy, stats: &QueueStats) {
+        self.queue_stats = Some((policy, *stats));
+    }
+
+    /// Erreichte Framerate und Jitter der Aufnahme für die Metadaten
+    pub fn set_timing_stats(&mut self, stats: FrameClockStats) {
+        self.timing = Some(stats);
+    }
+
+    /// Kamera-Ausfall während dieser Datei für die Metadaten
+    pub fn add_outage(&mut self, outage: CameraOutage) {
+        self.outages.push(outage);
+    }
+
+    /// Warnungen vom Pipeline-Bus seit dem letzten Aufruf
+    pub fn take_warnings(&self) -> Vec<PipelineIssue> {
+        self.health.take_warnings()
+    }
+
+    pub fn finalize(mut self) -> Result<RecordingMetadata> {
+        self.finish()
+    }
+
+    fn finish(&mut self) -> Result<RecordingMetadata> {
         *self.is_recording.lock().unwrap() = false;
+        // Der Watcher darf die EOS-Nachricht nicht vom Bus nehmen
+        self.health.stop();
 
         let duration = self.start_time.elapsed().as_secs_f64();
         let frame_count = *self.frame_count.lock().unwrap();
 
-        self.appsrc
-            .end_of_stream()
-            .map_err(|e| RecorderError::GStreamerError(e.to_string()))?;
-
-        let bus = self
-            .pipeline
-            .bus()
-            .ok_or_else(|| RecorderError::PipelineError("Kein Bus".to_string()))?;
-
-        for msg in bus.iter_timed(gst::ClockTime::from_seconds(5)) {
-            use gst::MessageView;
-
-            match msg.view() {
-                MessageView::Eos(..) => break,
-                MessageView::Error(err) => {
-                    return Err(RecorderError::GStreamerError(format!(
-                        "{:?}",
-                        err.error()
-                    )));
-                }
-                _ => (),
+        // Nach einem Ausfall schlägt schon das EOS fehl; die Ursache meldet wait_for_eos
+        let eos_sent = self.appsrc.end_of_stream();
+        wait_for_eos(&self.pipeline, &self.health)?;
+        eos_sent.map_err(|e| RecorderError::GStreamerError(e.to_string()))?;
+
+        let timestamps_file = match self.timestamps.take() {
+            Some(timestamps) => {
+                timestamps.finish()?;
+                sidecar_path(&self.output_path)
+                    .file_name()
+                    .map(|name| name.to_string_lossy().to_string())
             }
-        }
-
-        self.pipeline
-            .set_state(gst::State::Null)
-            .map_err(|e| RecorderError::PipelineError(e.to_string()))?;
+            None => None,
+        };
 
         let metadata = RecordingMetadata {
             camera_id: self.camera_id,
@@ -205,14 +357,20 @@ impl VideoRecorder {
                 .unwrap()
                 .to_string_lossy()
                 .to_string(),
+            codec: Some("h264".to_string()),
+            queue_policy: self.queue_stats.map(|(policy, _)| policy),
+            dropped_frames: self.queue_stats.map(|(_, stats)| stats.dropped),
+            timing: self.timing,
+            outages: (!self.outages.is_empty()).then(|| std::mem::take(&mut self.outages)),
+            timestamps_file,
+            first_frame_at: self
+                .first_frame_at
+                .map(|t| t.to_rfc3339_opts(SecondsFormat::Micros, false)),
         };
 
-        let metadata_path = self.output_path.with_extension("json");
-        let metadata_json = serde_json::to_string_pretty(&metadata)
-            .map_err(|e| RecorderError::MetadataError(e.to_string()))?;
-        fs::write(&metadata_path, metadata_json)?;
+        write_metadata(&self.output_path, &metadata)?;
 
-        println!(
+        eprintln!(
             "Aufnahme gespeichert: {} ({} frames, {:.2}s)",
             self.output_path.display(),
             frame_count,
@@ -222,3 +380,17 @@ impl VideoRecorder {
         Ok(metadata)
     }
 }
+
+impl Drop for VideoRecorder {
+    /// Nicht abgeschlossene Aufnahme (z.B. nach einem Panic im Encoder-Thread) beim
+    /// Aufräumen trotzdem mit EOS beenden, damit die Datei lesbar bleibt
+    fn drop(&mut self) {
+        let unfinished = self.is_recording.lock().map(|r| *r).unwrap_or(false);
+        if unfinished {
+            eprintln!("Aufnahme von Kamera {} nicht abgeschlossen, schließe beim Aufräumen ab", self.camera_id);
+            if let Err(e) = self.finish() {
+                eprintln!("Abschließen fehlgeschlagen: {}", e);
+            }
+        }
+    }
+}
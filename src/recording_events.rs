@@ -0,0 +1,128 @@
+use serde::Serialize;
+use std::path::PathBuf;
+
+/// Zustand einer Kamera im periodischen Stats-Event
+#[derive(Debug, Clone, Serialize)]
+pub struct CameraStats {
+    pub camera_id: u32,
+    pub frames: u64,
+    pub queue_depth: usize,
+    pub queue_capacity: usize,
+    pub dropped: u64,
+}
+
+/// Statusmeldungen von `DualCameraRecorder` an GUI und CLI
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum RecordingEvent {
+    Started {
+        source: String,
+        output_dir: PathBuf,
+    },
+    CameraOpened {
+        camera_id: u32,
+    },
+    Stats {
+        elapsed_secs: f64,
+        cameras: Vec<CameraStats>,
+    },
+    Warning {
+        camera_id: Option<u32>,
+        message: String,
+    },
+    Error {
+        camera_id: Option<u32>,
+        message: String,
+    },
+    Finalized {
+        camera_id: u32,
+        metadata_path: PathBuf,
+    },
+    /// Letztes Event einer Aufnahme, danach ist `is_recording()` false
+    Finished,
+}
+
+impl RecordingEvent {
+    /// Einzeilige Beschreibung für Log und Statuszeile
+    pub fn describe(&self) -> String {
+        match self {
+            RecordingEvent::Started { source, output_dir } => {
+                format!("Recording started ({}) -> {}", source, output_dir.display())
+            }
+            RecordingEvent::CameraOpened { camera_id } => format!("Camera {} opened", camera_id),
+            RecordingEvent::Stats {
+                elapsed_secs,
+                cameras,
+            } => {
+                let cams: Vec<String> = cameras
+                    .iter()
+                    .map(|c| {
+                        format!(
+                            "cam {}: {} frames, queue {}/{}, dropped {}",
+                            c.camera_id, c.frames, c.queue_depth, c.queue_capacity, c.dropped
+                        )
+                    })
+                    .collect();
+                format!("{:.0}s | {}", elapsed_secs, cams.join(" | "))
+            }
+            RecordingEvent::Warning { camera_id, message } => match camera_id {
+                Some(id) => format!("Warning (camera {}): {}", id, message),
+                None => format!("Warning: {}", message),
+            },
+            RecordingEvent::Error { camera_id, message } => match camera_id {
+                Some(id) => format!("Error (camera {}): {}", id, message),
+                None => format!("Error: {}", message),
+            },
+            RecordingEvent::Finalized {
+                camera_id,
+                metadata_path,
+            } => format!("Camera {} finalized: {}", camera_id, metadata_path.display()),
+            RecordingEvent::Finished => "Recording finished".to_string(),
+        }
+    }
+}
+
+/// Ausgabeformat für `--progress` in der CLI
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProgressFormat {
+    Text,
+    Json,
+}
+
+impl std::str::FromStr for ProgressFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "text" => Ok(ProgressFormat::Text),
+            "json" => Ok(ProgressFormat::Json),
+            other => Err(format!("Unbekanntes Format: {} (text oder json)", other)),
+        }
+    }
+}
+
+impl ProgressFormat {
+    /// Eine Zeile pro Event; JSON als JSON Lines für andere Prozesse
+    pub fn format(&self, event: &RecordingEvent) -> String {
+        match self {
+            ProgressFormat::Text => event.describe(),
+            ProgressFormat::Json => serde_json::to_string(event).unwrap_or_default(),
+        }
+    }
+}
+
+/// Sender-Seite, wird in die Aufnahme-Threads geklont
+#[derive(Clone)]
+pub struct EventSender(async_channel::Sender<RecordingEvent>);
+
+impl EventSender {
+    pub fn send(&self, event: RecordingEvent) {
+        // Unbegrenzter Kanal: schlägt nur fehl, wenn niemand mehr zuhört
+        let _ = self.0.try_send(event);
+    }
+}
+
+pub fn event_channel() -> (EventSender, async_channel::Receiver<RecordingEvent>) {
+    let (tx, rx) = async_channel::unbounded();
+    (EventSender(tx), rx)
+}
@@ -0,0 +1,196 @@
+use chrono::{DateTime, FixedOffset, Local, NaiveDateTime, TimeDelta, TimeZone};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::datasets::{detect_dataset, find_datasets};
+use crate::image_sequence::{ImageSequenceSpec, is_image_sequence};
+use crate::recorder::read_metadata;
+
+/// Dateiendungen, die als Aufnahme gelten
+pub const VIDEO_EXTENSIONS: [&str; 3] = ["mp4", "avi", "mkv"];
+
+/// Aufnahmen, deren Start höchstens so weit auseinander liegt (Sekunden), gehören zu
+/// einer Session
+const SESSION_WINDOW_SECS: i64 = 2;
+
+/// Eine Videodatei bzw. ein Bild-Verzeichnis mit den Angaben aus ihrer Metadaten-JSON (bzw. dem Dateinamen)
+#[derive(Debug, Clone)]
+pub struct Recording {
+    pub path: PathBuf,
+    pub camera_id: Option<u32>,
+    pub started_at: Option<DateTime<FixedOffset>>,
+    pub duration_secs: Option<f64>,
+    /// Bildfolge mit vorgegebener Beschreibung (Datensätze), sonst aus `sequence.json`
+    pub sequence: Option<ImageSequenceSpec>,
+}
+
+impl Recording {
+    fn from_path(path: PathBuf) -> Self {
+        let stem = path
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let metadata = read_metadata(&path);
+
+        let camera_id = metadata.as_ref().map(|m| m.camera_id).or_else(|| {
+            stem.strip_prefix("camera_")?
+                .split("__")
+                .next()?
+                .parse()
+                .ok()
+        });
+        // Bild-Verzeichnisse wie `image_0` oder `cam1`: Ziffern am Ende des Namens
+        let camera_id = camera_id.or_else(|| {
+            if !path.is_dir() {
+                return None;
+            }
+            let digits = stem.len() - stem.trim_end_matches(|c: char| c.is_ascii_digit()).len();
+            stem[stem.len() - digits..].parse().ok()
+        });
+
+        // Erster Frame, sonst Abschluss minus Dauer, sonst der Zeitstempel im Dateinamen
+        let started_at = metadata
+            .as_ref()
+            .and_then(|m| {
+                m.first_frame_at
+                    .as_deref()
+                    .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+                    .or_else(|| {
+                        let finished = DateTime::parse_from_rfc3339(&m.timestamp).ok()?;
+                        Some(finished - TimeDelta::milliseconds((m.duration_secs * 1000.0) as i64))
+                    })
+            })
+            .or_else(|| {
+                let stamp = stem.split("__").nth(1)?.get(..15)?;
+                let naive = NaiveDateTime::parse_from_str(stamp, "%Y%m%d_%H%M%S").ok()?;
+                Some(Local.from_local_datetime(&naive).single()?.fixed_offset())
+            });
+
+        Self {
+            path,
+            camera_id,
+            started_at,
+            duration_secs: metadata.map(|m| m.duration_secs),
+            sequence: None,
+        }
+    }
+}
+
+/// Gleichzeitig begonnene Aufnahmen verschiedener Kameras, nach Kamera-ID sortiert
+#[derive(Debug, Clone)]
+pub struct RecordingSession {
+    pub recordings: Vec<Recording>,
+    /// Name des erkannten Datensatzes (z.B. "EuRoC MH_01_easy (cam0 + cam1)")
+    pub dataset: Option<String>,
+    /// Mitgelieferte Kalibrier-Dateien
+    pub calibration: Vec<PathBuf>,
+}
+
+impl RecordingSession {
+    pub fn left(&self) -> &Recording {
+        &self.recordings[0]
+    }
+
+    /// None bei einer Session mit nur einer Kamera
+    pub fn right(&self) -> Option<&Recording> {
+        self.recordings.get(1)
+    }
+
+    pub fn label(&self) -> String {
+        if let Some(dataset) = &self.dataset {
+            return dataset.clone();
+        }
+        let first = &self.recordings[0];
+        let start = first
+            .started_at
+            .map(|t| t.with_timezone(&Local).format("%Y-%m-%d %H:%M:%S").to_string())
+            .unwrap_or_else(|| first.path.file_name().unwrap_or_default().to_string_lossy().to_string());
+        let cameras: Vec<String> = self
+            .recordings
+            .iter()
+            .map(|r| match r.camera_id {
+                Some(id) => format!("Kamera {}", id),
+                None => r.path.file_name().unwrap_or_default().to_string_lossy().to_string(),
+            })
+            .collect();
+        let duration = self
+            .recordings
+            .iter()
+            .filter_map(|r| r.duration_secs)
+            .reduce(f64::min)
+            .map(|d| format!(", {:.1}s", d))
+            .unwrap_or_default();
+
+        format!("{} – {}{}", start, cameras.join(" + "), duration)
+    }
+}
+
+/// Alle Aufnahmen im Verzeichnis (Videos und Unterverzeichnisse mit Bildfolgen), nach
+/// Name sortiert
+pub fn find_recordings(dir: &Path) -> io::Result<Vec<Recording>> {
+    let mut paths = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let is_video = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| VIDEO_EXTENSIONS.contains(&ext.to_lowercase().as_str()));
+        if is_video || is_image_sequence(&path) {
+            paths.push(path);
+        }
+    }
+    paths.sort();
+
+    Ok(paths.into_iter().map(Recording::from_path).collect())
+}
+
+/// Gruppiert die Aufnahmen nach Startzeit zu Sessions (neueste zuerst). Aufnahmen ohne
+/// bekannten Start bilden eine eigene Session; nur Bildfolgen verschiedener Kameras
+/// (z.B. `image_0` und `image_1`) werden auch ohne Start gepaart.
+///
+/// Ist `dir` selbst ein bekannter Datensatz (KITTI, EuRoC, TUM), gibt es nur dessen
+/// Session; Datensätze in Unterverzeichnissen folgen nach den eigenen Aufnahmen.
+pub fn find_sessions(dir: &Path) -> io::Result<Vec<RecordingSession>> {
+    if let Some(dataset) = detect_dataset(dir) {
+        return Ok(vec![dataset]);
+    }
+
+    let mut recordings = find_recordings(dir)?;
+    recordings.sort_by_key(|r| r.started_at);
+
+    let mut sessions: Vec<RecordingSession> = Vec::new();
+    for recording in recordings {
+        let joins = sessions.last().is_some_and(|session| {
+            let first = &session.recordings[0];
+            let distinct_camera = recording.camera_id.is_some()
+                && session
+                    .recordings
+                    .iter()
+                    .all(|r| r.camera_id.is_some() && r.camera_id != recording.camera_id);
+            match (first.started_at, recording.started_at) {
+                (Some(a), Some(b)) => {
+                    b - a <= TimeDelta::seconds(SESSION_WINDOW_SECS) && distinct_camera
+                }
+                (None, None) => first.path.is_dir() && recording.path.is_dir() && distinct_camera,
+                _ => false,
+            }
+        });
+
+        match sessions.last_mut() {
+            Some(session) if joins => session.recordings.push(recording),
+            _ => sessions.push(RecordingSession {
+                recordings: vec![recording],
+                dataset: None,
+                calibration: Vec::new(),
+            }),
+        }
+    }
+
+    for session in &mut sessions {
+        session.recordings.sort_by_key(|r| r.camera_id);
+    }
+    sessions.reverse();
+    sessions.extend(find_datasets(dir));
+    Ok(sessions)
+}
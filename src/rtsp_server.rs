@@ -0,0 +1,321 @@
+use gstreamer as gst;
+use gstreamer::prelude::*;
+use gstreamer_app as gst_app;
+use gstreamer_rtsp_server as gst_rtsp_server;
+use gstreamer_rtsp_server::prelude::*;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+use thiserror::Error;
+
+use crate::pipeline_health::BusWatcher;
+
+#[derive(Error, Debug)]
+pub enum RtspServerError {
+    #[error("GStreamer Fehler: {0}")]
+    GStreamerError(String),
+    #[error("Stream konnte nicht eingehängt werden: {0}")]
+    MountError(String),
+    #[error("Frame hat {0} Bytes, erwartet {1}")]
+    FrameSize(usize, usize),
+    #[error("Kein Frame empfangen: {0}")]
+    ReceiveError(String),
+}
+
+pub type Result<T> = std::result::Result<T, RtspServerError>;
+
+/// Kodierung der RTSP-Streams
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RtspCodec {
+    H264,  // x264enc, von allen Clients unterstützt
+    H265,  // x265enc, halbe Bitrate bei gleicher Qualität
+    Mjpeg, // jpegenc, kein Inter-Frame-Delay, hohe Bitrate
+}
+
+impl fmt::Display for RtspCodec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            RtspCodec::H264 => "h264",
+            RtspCodec::H265 => "h265",
+            RtspCodec::Mjpeg => "mjpeg",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl std::str::FromStr for RtspCodec {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "h264" | "avc" => Ok(RtspCodec::H264),
+            "h265" | "hevc" => Ok(RtspCodec::H265),
+            "mjpeg" | "jpeg" => Ok(RtspCodec::Mjpeg),
+            other => Err(format!("Unbekannter Codec: {} (h264, h265, mjpeg)", other)),
+        }
+    }
+}
+
+/// Einstellungen des RTSP-Servers
+#[derive(Debug, Clone, Copy)]
+pub struct RtspConfig {
+    pub port: u16,
+    pub codec: RtspCodec,
+    /// Obergrenze der Pufferung im Server und Jitterbuffer-Latenz für die Clients
+    pub latency_ms: u32,
+    /// Für H.264/H.265
+    pub bitrate_kbps: u32,
+}
+
+impl Default for RtspConfig {
+    fn default() -> Self {
+        Self {
+            port: 8554,
+            codec: RtspCodec::H264,
+            latency_ms: 200,
+            bitrate_kbps: 4000,
+        }
+    }
+}
+
+impl RtspConfig {
+    /// Launch-Zeile einer Media-Factory: appsrc mit RGB, Queue bis zur Latenz, Encoder
+    fn launch_line(&self, width: u32, height: u32, fps: f64) -> String {
+        let fps_int = fps.round().max(1.0) as u32;
+        let queued = (self.latency_ms as f64 * fps / 1000.0).ceil().max(1.0) as u32;
+        let encoder = match self.codec {
+            RtspCodec::H264 => format!(
+                "x264enc tune=zerolatency speed-preset=ultrafast bitrate={} key-int-max={} ! \
+                 rtph264pay name=pay0 pt=96 config-interval=1",
+                self.bitrate_kbps, fps_int
+            ),
+            RtspCodec::H265 => format!(
+                "x265enc tune=zerolatency speed-preset=ultrafast bitrate={} key-int-max={} ! \
+                 rtph265pay name=pay0 pt=96 config-interval=1",
+                self.bitrate_kbps, fps_int
+            ),
+            RtspCodec::Mjpeg => "jpegenc quality=85 ! rtpjpegpay name=pay0 pt=26".to_string(),
+        };
+
+        // do-timestamp: Zeitstempel beim Einspeisen, Sprünge der Quelle (Loop) stören nicht
+        format!(
+            "( appsrc name=src is-live=true format=time do-timestamp=true \
+               caps=video/x-raw,format=RGB,width={},height={},framerate={}/1 ! \
+             queue leaky=downstream max-size-buffers={} max-size-time=0 max-size-bytes=0 ! \
+             videoconvert ! video/x-raw,format=I420 ! {} )",
+            width, height, fps_int, queued, encoder
+        )
+    }
+}
+
+/// RTSP-Server auf localhost; jede Kamera ist ein Mount (`rtsp://127.0.0.1:8554/left`)
+pub struct RtspServer {
+    server: gst_rtsp_server::RTSPServer,
+    config: RtspConfig,
+    context: glib::MainContext,
+    /// Lauschender Socket, wird beim Beenden entfernt, damit der Port frei wird
+    source_id: glib::SourceId,
+    main_loop: glib::MainLoop,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl RtspServer {
+    /// Startet den Server mit eigener GLib-Hauptschleife (unabhängig von GTK)
+    pub fn start(config: RtspConfig) -> Result<Self> {
+        gst::init().map_err(|e| RtspServerError::GStreamerError(e.to_string()))?;
+
+        let server = gst_rtsp_server::RTSPServer::new();
+        server.set_address("127.0.0.1");
+        server.set_service(&config.port.to_string());
+
+        let context = glib::MainContext::new();
+        let source_id = server
+            .attach(Some(&context))
+            .map_err(|e| RtspServerError::GStreamerError(format!("Port {}: {}", config.port, e)))?;
+
+        let main_loop = glib::MainLoop::new(Some(&context), false);
+        let thread = {
+            let main_loop = main_loop.clone();
+            thread::spawn(move || main_loop.run())
+        };
+
+        Ok(Self {
+            server,
+            config,
+            context,
+            source_id,
+            main_loop,
+            thread: Some(thread),
+        })
+    }
+
+    pub fn url(&self, name: &str) -> String {
+        format!("rtsp://127.0.0.1:{}/{}", self.config.port, name)
+    }
+
+    /// Hängt einen Stream unter `/name` ein; Frames kommen über `RtspStream::publish`
+    pub fn add_stream(&self, name: &str, width: u32, height: u32, fps: f64) -> Result<RtspStream> {
+        let mounts = self
+            .server
+            .mount_points()
+            .ok_or_else(|| RtspServerError::MountError("Keine Mount-Points".to_string()))?;
+
+        let factory = gst_rtsp_server::RTSPMediaFactory::new();
+        factory.set_launch(&self.config.launch_line(width, height, fps));
+        // Alle Clients sehen dieselbe Media, die Quelle wird nur einmal kodiert
+        factory.set_shared(true);
+        factory.set_latency(self.config.latency_ms);
+
+        let appsrc: Arc<Mutex<Option<gst_app::AppSrc>>> = Arc::new(Mutex::new(None));
+        let appsrc_configure = appsrc.clone();
+        factory.connect_media_configure(move |_, media| {
+            let src = media
+                .element()
+                .downcast::<gst::Bin>()
+                .ok()
+                .and_then(|bin| bin.by_name_recurse_up("src"))
+                .and_then(|src| src.downcast::<gst_app::AppSrc>().ok());
+            *appsrc_configure.lock().unwrap() = src;
+
+            // Letzter Client weg: keine Frames mehr in die abgebaute Pipeline schieben
+            let appsrc_unprepared = appsrc_configure.clone();
+            media.connect_unprepared(move |_| {
+                *appsrc_unprepared.lock().unwrap() = None;
+            });
+        });
+
+        mounts.add_factory(&format!("/{}", name), factory);
+
+        Ok(RtspStream {
+            appsrc,
+            frame_size: (width * height * 3) as usize,
+            url: self.url(name),
+        })
+    }
+}
+
+impl Drop for RtspServer {
+    fn drop(&mut self) {
+        self.main_loop.quit();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+        if let Some(source) = self.context.find_source_by_id(&self.source_id) {
+            source.destroy();
+        }
+    }
+}
+
+/// Eingang eines eingehängten RTSP-Streams
+pub struct RtspStream {
+    appsrc: Arc<Mutex<Option<gst_app::AppSrc>>>,
+    frame_size: usize,
+    url: String,
+}
+
+impl RtspStream {
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Speist einen RGB-Frame ein; ohne verbundenen Client wird er verworfen
+    pub fn publish(&self, rgb: &[u8]) -> Result<()> {
+        if rgb.len() != self.frame_size {
+            return Err(RtspServerError::FrameSize(rgb.len(), self.frame_size));
+        }
+
+        let mut appsrc = self.appsrc.lock().unwrap();
+        let Some(src) = appsrc.as_ref() else {
+            return Ok(());
+        };
+        if src.push_buffer(gst::Buffer::from_mut_slice(rgb.to_vec())).is_err() {
+            // Flushing: Media wird gerade abgebaut
+            *appsrc = None;
+        }
+        Ok(())
+    }
+}
+
+/// Ergebnis von `receive`
+#[derive(Debug, Clone, Copy)]
+pub struct ReceiveReport {
+    pub frames: u32,
+    pub width: u32,
+    pub height: u32,
+    /// Vom Start des Clients bis zum ersten dekodierten Frame
+    pub first_frame: Duration,
+    pub elapsed: Duration,
+}
+
+impl ReceiveReport {
+    /// Empfangene Frames pro Sekunde ab dem ersten Frame
+    pub fn fps(&self) -> f64 {
+        let span = self.elapsed.saturating_sub(self.first_frame).as_secs_f64();
+        if self.frames > 1 && span > 0.0 {
+            (self.frames - 1) as f64 / span
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Empfängt `count` Frames wie ein lokaler Client (`rtspsrc ! decodebin`); schlägt fehl,
+/// wenn `timeout` lang kein Frame ankommt
+pub fn receive(url: &str, count: u32, latency_ms: u32, timeout: Duration) -> Result<ReceiveReport> {
+    gst::init().map_err(|e| RtspServerError::GStreamerError(e.to_string()))?;
+
+    let pipeline_str = format!(
+        "rtspsrc location={} latency={} ! decodebin ! videoconvert ! \
+         video/x-raw,format=RGB ! appsink name=sink sync=false",
+        url, latency_ms
+    );
+    let pipeline = gst::parse::launch(&pipeline_str)
+        .map_err(|e| RtspServerError::GStreamerError(e.to_string()))?
+        .dynamic_cast::<gst::Pipeline>()
+        .map_err(|_| RtspServerError::GStreamerError("Keine Pipeline".to_string()))?;
+    let appsink = pipeline
+        .by_name("sink")
+        .and_then(|sink| sink.dynamic_cast::<gst_app::AppSink>().ok())
+        .ok_or_else(|| RtspServerError::GStreamerError("Kein appsink".to_string()))?;
+
+    let health = BusWatcher::watch(url, &pipeline);
+    let start = Instant::now();
+    pipeline
+        .set_state(gst::State::Playing)
+        .map_err(|e| RtspServerError::GStreamerError(e.to_string()))?;
+
+    let mut report = ReceiveReport {
+        frames: 0,
+        width: 0,
+        height: 0,
+        first_frame: Duration::ZERO,
+        elapsed: Duration::ZERO,
+    };
+    let pull_timeout = gst::ClockTime::from_nseconds(timeout.as_nanos() as u64);
+    let result = loop {
+        if report.frames >= count {
+            break Ok(());
+        }
+        let Some(sample) = appsink.try_pull_sample(pull_timeout) else {
+            break Err(RtspServerError::ReceiveError(match health.error() {
+                Some(issue) => issue.to_string(),
+                None if appsink.is_eos() => format!("{}: Stream beendet", url),
+                None => format!("{}: {:.1} s ohne Frame", url, timeout.as_secs_f64()),
+            }));
+        };
+
+        if report.frames == 0 {
+            report.first_frame = start.elapsed();
+            if let Some(structure) = sample.caps().and_then(|caps| caps.structure(0)) {
+                report.width = structure.get::<i32>("width").unwrap_or_default() as u32;
+                report.height = structure.get::<i32>("height").unwrap_or_default() as u32;
+            }
+        }
+        report.frames += 1;
+    };
+    report.elapsed = start.elapsed();
+
+    let _ = pipeline.set_state(gst::State::Null);
+    result.map(|_| report)
+}
@@ -0,0 +1,35 @@
+/// SplitMix64: klein, schnell und über Versionen hinweg reproduzierbar, damit ein Seed
+/// in Tests immer dieselben Fehler bzw. dasselbe Rauschen ergibt
+#[derive(Debug, Clone)]
+pub struct SeededRng(u64);
+
+impl SeededRng {
+    pub fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    /// Eigener Strom je Frame, unabhängig davon, wie viele Zahlen vorher gezogen wurden
+    pub fn for_frame(seed: u64, frame: u64) -> Self {
+        let mut mixer = Self(seed ^ frame.wrapping_mul(0xD1B5_4A32_D192_ED03));
+        Self(mixer.next_u64())
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Gleichverteilt in [0, 1)
+    pub fn uniform(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Standardnormalverteilt (Box-Muller)
+    pub fn normal(&mut self) -> f64 {
+        let radius = (-2.0 * (1.0 - self.uniform()).ln()).sqrt();
+        radius * (2.0 * std::f64::consts::PI * self.uniform()).cos()
+    }
+}
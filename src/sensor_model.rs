@@ -0,0 +1,398 @@
+use image::codecs::jpeg::JpegEncoder;
+use image::imageops::{self, FilterType};
+use image::{ExtendedColorType, ImageFormat, RgbImage};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use thiserror::Error;
+
+use crate::frame_source::{self, FrameSource, FrameSourceError, SourceFrame};
+use crate::playback_camera::Pacing;
+use crate::seeded_rng::SeededRng;
+
+#[derive(Error, Debug)]
+pub enum SensorModelError {
+    #[error("IO Fehler: {0}")]
+    IoError(#[from] std::io::Error),
+    #[error("Ungültiges Sensor-Modell: {0}")]
+    ParseError(String),
+    #[error("JPEG-Artefakte fehlgeschlagen: {0}")]
+    JpegError(String),
+}
+
+pub type Result<T> = std::result::Result<T, SensorModelError>;
+
+/// Parameter des Sensor- und Objektiv-Modells; alle Effekte sind in der
+/// Standardeinstellung aus
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SensorConfig {
+    /// Gleicher Seed und gleiche Frame-Nummer ergeben dasselbe Rauschen
+    pub seed: u64,
+    /// Photonenrauschen: Digitalwerte pro Elektron, Varianz = Signal * Gain
+    pub shot_noise_gain: f64,
+    /// Ausleserauschen, Standardabweichung in Digitalwerten
+    pub read_noise: f64,
+    /// Randabfall der Helligkeit, 0 = keiner, 1 ≈ Ecken bei 25 %
+    pub vignetting: f64,
+    /// Radiale (k1, k2) und tangentiale (p1, p2) Verzeichnung nach Brown-Conrady,
+    /// Koordinaten normiert auf die halbe Bilddiagonale
+    pub k1: f64,
+    pub k2: f64,
+    pub p1: f64,
+    pub p2: f64,
+    /// Länge der Bewegungsunschärfe in Pixeln
+    pub motion_blur_px: f64,
+    /// Richtung der Bewegungsunschärfe, 0° = horizontal
+    pub motion_blur_angle_deg: f64,
+    /// Horizontale Verschiebung der letzten gegenüber der ersten Zeile (Rolling Shutter)
+    pub rolling_shutter_px: f64,
+    /// Effektive Auflösung des Sensors relativ zum Frame (0.5 = halbe Auflösung);
+    /// das Bild behält seine Größe
+    pub resolution_scale: f64,
+    /// JPEG-Kompression wie bei einer MJPEG-Kamera, None = aus
+    pub jpeg_quality: Option<u8>,
+}
+
+impl Default for SensorConfig {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            shot_noise_gain: 0.0,
+            read_noise: 0.0,
+            vignetting: 0.0,
+            k1: 0.0,
+            k2: 0.0,
+            p1: 0.0,
+            p2: 0.0,
+            motion_blur_px: 0.0,
+            motion_blur_angle_deg: 0.0,
+            rolling_shutter_px: 0.0,
+            resolution_scale: 1.0,
+            jpeg_quality: None,
+        }
+    }
+}
+
+impl SensorConfig {
+    /// Liest ein Sensor-Modell als JSON
+    pub fn load(path: &Path) -> Result<Self> {
+        let json = fs::read_to_string(path)?;
+        let config: Self = serde_json::from_str(&json)
+            .map_err(|e| SensorModelError::ParseError(format!("{}: {}", path.display(), e)))?;
+        config.validate().map_err(SensorModelError::ParseError)?;
+        Ok(config)
+    }
+
+    pub fn validate(&self) -> std::result::Result<(), String> {
+        for (name, value) in [
+            ("shot_noise_gain", self.shot_noise_gain),
+            ("read_noise", self.read_noise),
+            ("vignetting", self.vignetting),
+            ("motion_blur_px", self.motion_blur_px),
+        ] {
+            if value < 0.0 {
+                return Err(format!("{} = {} ist negativ", name, value));
+            }
+        }
+        if !(0.05..=1.0).contains(&self.resolution_scale) {
+            return Err(format!(
+                "resolution_scale = {} außerhalb von 0.05..1",
+                self.resolution_scale
+            ));
+        }
+        if let Some(quality) = self.jpeg_quality {
+            if !(1..=100).contains(&quality) {
+                return Err(format!("jpeg_quality = {} außerhalb von 1..100", quality));
+            }
+        }
+        Ok(())
+    }
+
+    fn has_geometry(&self) -> bool {
+        self.k1 != 0.0
+            || self.k2 != 0.0
+            || self.p1 != 0.0
+            || self.p2 != 0.0
+            || self.rolling_shutter_px != 0.0
+    }
+}
+
+/// Sensor-Modelle für links und rechts (`sensor` in `simulation.json`)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StereoSensorConfig {
+    pub left: SensorConfig,
+    pub right: SensorConfig,
+}
+
+impl Default for StereoSensorConfig {
+    fn default() -> Self {
+        Self {
+            left: SensorConfig::default(),
+            right: SensorConfig {
+                seed: 1,
+                ..SensorConfig::default()
+            },
+        }
+    }
+}
+
+impl StereoSensorConfig {
+    pub fn validate(&self) -> std::result::Result<(), String> {
+        self.left.validate().map_err(|e| format!("left: {}", e))?;
+        self.right.validate().map_err(|e| format!("right: {}", e))
+    }
+
+    /// Modelle für links und rechts; bei gleichem Seed bekäme die rechte Kamera
+    /// dasselbe Rauschen wie die linke, dann rechnet sie mit dem nächsten
+    pub fn models(&self) -> [SensorModel; 2] {
+        let mut right = self.right;
+        if right.seed == self.left.seed {
+            right.seed = right.seed.wrapping_add(1);
+        }
+        [SensorModel::new(self.left), SensorModel::new(right)]
+    }
+}
+
+/// Wendet ein `SensorConfig` auf RGB-Frames an.
+///
+/// Reihenfolge wie in der Kamera: Objektiv (Verzeichnung, Vignettierung), Bewegung
+/// (Unschärfe, Rolling Shutter), Abtastung (Auflösung), Rauschen, Kompression.
+pub struct SensorModel {
+    config: SensorConfig,
+    frame: u64,
+    /// Quellkoordinate je Pixel für Verzeichnung und Rolling Shutter, pro Auflösung
+    remap: Option<(u32, u32, Vec<(f32, f32)>)>,
+}
+
+impl SensorModel {
+    pub fn new(config: SensorConfig) -> Self {
+        Self {
+            config,
+            frame: 0,
+            remap: None,
+        }
+    }
+
+    pub fn apply(&mut self, rgb: &[u8], width: u32, height: u32) -> Result<Vec<u8>> {
+        let config = self.config;
+        let frame = self.frame;
+        self.frame += 1;
+
+        let mut image = if config.has_geometry() {
+            self.remap_geometry(rgb, width, height)
+        } else {
+            rgb.to_vec()
+        };
+
+        if config.vignetting > 0.0 {
+            vignette(&mut image, width, height, config.vignetting);
+        }
+        if config.motion_blur_px >= 1.0 {
+            image = motion_blur(&image, width, height, config.motion_blur_px, config.motion_blur_angle_deg);
+        }
+        if config.resolution_scale < 1.0 {
+            image = resample(image, width, height, config.resolution_scale)?;
+        }
+        if config.shot_noise_gain > 0.0 || config.read_noise > 0.0 {
+            add_noise(&mut image, &mut SeededRng::for_frame(config.seed, frame), &config);
+        }
+        if let Some(quality) = config.jpeg_quality {
+            image = jpeg_round_trip(image, width, height, quality)?;
+        }
+        Ok(image)
+    }
+
+    fn remap_geometry(&mut self, rgb: &[u8], width: u32, height: u32) -> Vec<u8> {
+        if !matches!(&self.remap, Some((w, h, _)) if *w == width && *h == height) {
+            self.remap = Some((width, height, geometry_map(&self.config, width, height)));
+        }
+        let (_, _, map) = self.remap.as_ref().expect("gerade berechnet");
+
+        let mut out = vec![0u8; rgb.len()];
+        for (i, &(sx, sy)) in map.iter().enumerate() {
+            out[i * 3..i * 3 + 3].copy_from_slice(&sample_bilinear(rgb, width, height, sx, sy));
+        }
+        out
+    }
+}
+
+/// Für jeden Pixel des verzeichneten Bilds der Punkt im unverzeichneten Bild
+fn geometry_map(config: &SensorConfig, width: u32, height: u32) -> Vec<(f32, f32)> {
+    let (cx, cy) = (width as f64 / 2.0, height as f64 / 2.0);
+    let norm = (cx * cx + cy * cy).sqrt();
+    let mut map = Vec::with_capacity((width * height) as usize);
+
+    for y in 0..height {
+        // Zeilen werden nacheinander belichtet, die Szene wandert dabei weiter
+        let shift = config.rolling_shutter_px * (y as f64 / height.max(2) as f64 - 0.5);
+        for x in 0..width {
+            let xd = (x as f64 + 0.5 - cx) / norm;
+            let yd = (y as f64 + 0.5 - cy) / norm;
+            let (xu, yu) = undistort(config, xd, yd);
+            map.push((
+                (xu * norm + cx - 0.5 - shift) as f32,
+                (yu * norm + cy - 0.5) as f32,
+            ));
+        }
+    }
+    map
+}
+
+/// Kehrt das Brown-Conrady-Modell per Fixpunkt-Iteration um (wie `cv::undistortPoints`)
+fn undistort(config: &SensorConfig, xd: f64, yd: f64) -> (f64, f64) {
+    let (mut x, mut y) = (xd, yd);
+    for _ in 0..8 {
+        let r2 = x * x + y * y;
+        let radial = 1.0 + config.k1 * r2 + config.k2 * r2 * r2;
+        let dx = 2.0 * config.p1 * x * y + config.p2 * (r2 + 2.0 * x * x);
+        let dy = config.p1 * (r2 + 2.0 * y * y) + 2.0 * config.p2 * x * y;
+        x = (xd - dx) / radial;
+        y = (yd - dy) / radial;
+    }
+    (x, y)
+}
+
+/// Außerhalb des Bilds schwarz, wie hinter dem Bildkreis
+fn sample_bilinear(rgb: &[u8], width: u32, height: u32, x: f32, y: f32) -> [u8; 3] {
+    if x < 0.0 || y < 0.0 || x > (width - 1) as f32 || y > (height - 1) as f32 {
+        return [0, 0, 0];
+    }
+    let (x0, y0) = (x.floor() as usize, y.floor() as usize);
+    let (x1, y1) = ((x0 + 1).min(width as usize - 1), (y0 + 1).min(height as usize - 1));
+    let (fx, fy) = (x - x0 as f32, y - y0 as f32);
+    let at = |x: usize, y: usize, c: usize| rgb[(y * width as usize + x) * 3 + c] as f32;
+
+    let mut out = [0u8; 3];
+    for (c, value) in out.iter_mut().enumerate() {
+        let top = at(x0, y0, c) * (1.0 - fx) + at(x1, y0, c) * fx;
+        let bottom = at(x0, y1, c) * (1.0 - fx) + at(x1, y1, c) * fx;
+        *value = (top * (1.0 - fy) + bottom * fy).round() as u8;
+    }
+    out
+}
+
+/// cos⁴-ähnlicher Randabfall
+fn vignette(rgb: &mut [u8], width: u32, height: u32, strength: f64) {
+    let (cx, cy) = (width as f64 / 2.0, height as f64 / 2.0);
+    let norm2 = cx * cx + cy * cy;
+    for y in 0..height as usize {
+        for x in 0..width as usize {
+            let (dx, dy) = (x as f64 + 0.5 - cx, y as f64 + 0.5 - cy);
+            let gain = 1.0 / (1.0 + strength * (dx * dx + dy * dy) / norm2).powi(2);
+            let i = (y * width as usize + x) * 3;
+            for value in &mut rgb[i..i + 3] {
+                *value = (*value as f64 * gain).round() as u8;
+            }
+        }
+    }
+}
+
+/// Mittel über eine Linie der Länge `length` in Richtung `angle_deg`
+fn motion_blur(rgb: &[u8], width: u32, height: u32, length: f64, angle_deg: f64) -> Vec<u8> {
+    let samples = length.ceil() as usize + 1;
+    let (dx, dy) = (angle_deg.to_radians().cos(), angle_deg.to_radians().sin());
+    let offsets: Vec<(isize, isize)> = (0..samples)
+        .map(|i| {
+            let t = i as f64 / (samples - 1) as f64 * length - length / 2.0;
+            ((t * dx).round() as isize, (t * dy).round() as isize)
+        })
+        .collect();
+
+    let (w, h) = (width as isize, height as isize);
+    let mut out = vec![0u8; rgb.len()];
+    for y in 0..h {
+        for x in 0..w {
+            let mut sum = [0u32; 3];
+            for (ox, oy) in &offsets {
+                let sx = (x + ox).clamp(0, w - 1);
+                let sy = (y + oy).clamp(0, h - 1);
+                let i = ((sy * w + sx) * 3) as usize;
+                for (total, value) in sum.iter_mut().zip(&rgb[i..i + 3]) {
+                    *total += *value as u32;
+                }
+            }
+            let i = ((y * w + x) * 3) as usize;
+            for (value, total) in out[i..i + 3].iter_mut().zip(sum) {
+                *value = (total / offsets.len() as u32) as u8;
+            }
+        }
+    }
+    out
+}
+
+/// Verkleinert auf die effektive Auflösung und zurück auf die Frame-Größe
+fn resample(rgb: Vec<u8>, width: u32, height: u32, scale: f64) -> Result<Vec<u8>> {
+    let image = RgbImage::from_raw(width, height, rgb)
+        .ok_or_else(|| SensorModelError::ParseError("Frame-Größe passt nicht".to_string()))?;
+    let small_width = ((width as f64 * scale).round() as u32).max(1);
+    let small_height = ((height as f64 * scale).round() as u32).max(1);
+    let small = imageops::resize(&image, small_width, small_height, FilterType::Triangle);
+    Ok(imageops::resize(&small, width, height, FilterType::Triangle).into_raw())
+}
+
+/// Photonenrauschen (signalabhängig) plus Ausleserauschen, je Kanal unabhängig
+fn add_noise(rgb: &mut [u8], rng: &mut SeededRng, config: &SensorConfig) {
+    for value in rgb.iter_mut() {
+        let signal = *value as f64;
+        let sigma = (signal * config.shot_noise_gain + config.read_noise * config.read_noise).sqrt();
+        *value = (signal + sigma * rng.normal()).round().clamp(0.0, 255.0) as u8;
+    }
+}
+
+fn jpeg_round_trip(rgb: Vec<u8>, width: u32, height: u32, quality: u8) -> Result<Vec<u8>> {
+    let mut jpeg = Vec::new();
+    JpegEncoder::new_with_quality(&mut jpeg, quality)
+        .encode(&rgb, width, height, ExtendedColorType::Rgb8)
+        .map_err(|e| SensorModelError::JpegError(e.to_string()))?;
+    let decoded = image::load_from_memory_with_format(&jpeg, ImageFormat::Jpeg)
+        .map_err(|e| SensorModelError::JpegError(e.to_string()))?;
+    Ok(decoded.to_rgb8().into_raw())
+}
+
+/// Beliebige `FrameSource` mit Sensor-Modell (für `serve` und `stream`)
+pub struct SensorSource {
+    inner: Box<dyn FrameSource>,
+    model: SensorModel,
+}
+
+impl SensorSource {
+    pub fn new(inner: Box<dyn FrameSource>, config: SensorConfig) -> Self {
+        Self {
+            inner,
+            model: SensorModel::new(config),
+        }
+    }
+}
+
+impl FrameSource for SensorSource {
+    fn name(&self) -> String {
+        self.inner.name()
+    }
+
+    fn resolution(&self) -> (u32, u32) {
+        self.inner.resolution()
+    }
+
+    fn fps(&self) -> f64 {
+        self.inner.fps()
+    }
+
+    fn next_frame(&mut self) -> frame_source::Result<SourceFrame> {
+        let frame = self.inner.next_frame()?;
+        let (width, height) = self.inner.resolution();
+        let data = self
+            .model
+            .apply(&frame.data, width, height)
+            .map_err(|e| FrameSourceError::ReadError(e.to_string()))?;
+        Ok(SourceFrame {
+            data,
+            pts: frame.pts,
+        })
+    }
+
+    fn set_pacing(&mut self, pacing: Pacing) {
+        self.inner.set_pacing(pacing);
+    }
+}
@@ -0,0 +1,94 @@
+use std::path::PathBuf;
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc, OnceLock,
+};
+use std::time::Duration;
+
+/// Obergrenze, wie lange beim Beenden auf das Abschließen der Aufnahmen gewartet wird
+/// (EOS bis zum Muxer, Metadaten schreiben)
+pub const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(15);
+
+static SHUTDOWN: OnceLock<Shutdown> = OnceLock::new();
+
+/// Zentraler Beenden-Wunsch für CLI und GUI.
+///
+/// Der Signal-Handler (SIGINT, SIGTERM) wird genau einmal installiert; jeder weitere
+/// Aufruf von `install` liefert dieselbe Instanz. Ein zweites Signal beendet den
+/// Prozess sofort, falls das Abschließen hängt.
+#[derive(Clone)]
+pub struct Shutdown {
+    requested: Arc<AtomicBool>,
+}
+
+pub fn install() -> &'static Shutdown {
+    SHUTDOWN.get_or_init(|| {
+        let requested = Arc::new(AtomicBool::new(false));
+        let flag = requested.clone();
+
+        ctrlc::set_handler(move || {
+            if flag.swap(true, Ordering::SeqCst) {
+                eprintln!("\nSofortiger Abbruch, Aufnahmen werden nicht abgeschlossen");
+                std::process::exit(130);
+            }
+            eprintln!("\nBeende und schließe Aufnahmen ab... (erneut Ctrl-C für sofortigen Abbruch)");
+        })
+        .expect("Fehler beim Setzen des Signal-Handlers");
+
+        Shutdown { requested }
+    })
+}
+
+impl Shutdown {
+    pub fn is_requested(&self) -> bool {
+        self.requested.load(Ordering::SeqCst)
+    }
+
+    /// Beenden ohne Signal, z.B. beim Schließen des Fensters
+    pub fn request(&self) {
+        self.requested.store(true, Ordering::SeqCst);
+    }
+}
+
+/// Ergebnis des Abschließens einer Aufnahmedatei
+#[derive(Debug, Clone)]
+pub struct FinalizeOutcome {
+    pub camera_id: u32,
+    /// Pfad der Metadaten bei Erfolg, sonst die Fehlermeldung
+    pub result: Result<PathBuf, String>,
+}
+
+/// Welche Dateien beim Beenden sauber abgeschlossen wurden
+#[derive(Debug, Clone, Default)]
+pub struct ShutdownReport {
+    pub outcomes: Vec<FinalizeOutcome>,
+    /// Aufnahme-Threads liefen nach dem Timeout noch
+    pub timed_out: bool,
+    /// Tatsächlich verwendeter Timeout
+    pub timeout: Duration,
+}
+
+impl ShutdownReport {
+    pub fn is_clean(&self) -> bool {
+        !self.timed_out && self.outcomes.iter().all(|o| o.result.is_ok())
+    }
+
+    pub fn lines(&self) -> Vec<String> {
+        let mut lines: Vec<String> = self
+            .outcomes
+            .iter()
+            .map(|o| match &o.result {
+                Ok(path) => format!("Kamera {}: abgeschlossen ({})", o.camera_id, path.display()),
+                Err(e) => format!("Kamera {}: NICHT abgeschlossen: {}", o.camera_id, e),
+            })
+            .collect();
+
+        if self.timed_out {
+            lines.push(format!(
+                "Timeout nach {:.1}s: nicht alle Aufnahmen wurden abgeschlossen",
+                self.timeout.as_secs_f64()
+            ));
+        }
+        lines
+    }
+}
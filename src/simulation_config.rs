@@ -0,0 +1,235 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+use crate::fault_injection::StereoFaultConfig;
+use crate::playback_camera::Pacing;
+use crate::sensor_model::StereoSensorConfig;
+
+#[derive(Error, Debug)]
+pub enum SimulationConfigError {
+    #[error("IO Fehler: {0}")]
+    IoError(#[from] std::io::Error),
+    #[error("Ungültige Simulations-Konfiguration: {0}")]
+    ParseError(String),
+}
+
+pub type Result<T> = std::result::Result<T, SimulationConfigError>;
+
+/// Dateiname der Konfiguration im Aufnahme-Verzeichnis
+pub const CONFIG_FILE: &str = "simulation.json";
+
+/// Was eine simulierte Kamera am Ende ihres Abspielbereichs macht
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum LoopMode {
+    Loop,     // Vom In-Punkt neu beginnen
+    PingPong, // Richtung umkehren
+    HoldLast, // Einmal abspielen, dann den letzten Frame stehen lassen
+    Once,     // Einmal abspielen, danach liefert get_frame einen Fehler
+}
+
+impl fmt::Display for LoopMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            LoopMode::Loop => "loop",
+            LoopMode::PingPong => "ping-pong",
+            LoopMode::HoldLast => "hold-last",
+            LoopMode::Once => "once",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl std::str::FromStr for LoopMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "loop" => Ok(LoopMode::Loop),
+            "ping-pong" | "pingpong" => Ok(LoopMode::PingPong),
+            "hold-last" | "hold" => Ok(LoopMode::HoldLast),
+            "once" => Ok(LoopMode::Once),
+            other => Err(format!(
+                "Unbekannter Loop-Modus: {} (loop, ping-pong, hold-last, once)",
+                other
+            )),
+        }
+    }
+}
+
+/// Wonach im Lock-Step-Betrieb linke und rechte Frames gepaart werden
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SyncBy {
+    Timestamp,  // Gleicher PTS (abzüglich Versatz)
+    FrameIndex, // Gleicher Frame-Index (abzüglich Versatz in Frames)
+}
+
+impl fmt::Display for SyncBy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            SyncBy::Timestamp => "timestamp",
+            SyncBy::FrameIndex => "frame-index",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl std::str::FromStr for SyncBy {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "timestamp" | "pts" => Ok(SyncBy::Timestamp),
+            "frame-index" | "index" => Ok(SyncBy::FrameIndex),
+            other => Err(format!(
+                "Unbekannte Synchronisierung: {} (timestamp oder frame-index)",
+                other
+            )),
+        }
+    }
+}
+
+/// Abspiel-Einstellungen einer simulierten Kamera
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PlaybackSettings {
+    /// 1.0 = Originalgeschwindigkeit, < 1.0 Zeitlupe, > 1.0 Zeitraffer
+    pub rate: f64,
+    /// Erster Frame des Abspielbereichs
+    pub in_frame: u64,
+    /// Letzter Frame des Abspielbereichs (einschließlich), None = bis zum Ende
+    pub out_frame: Option<u64>,
+    pub loop_mode: LoopMode,
+}
+
+impl Default for PlaybackSettings {
+    fn default() -> Self {
+        Self {
+            rate: 1.0,
+            in_frame: 0,
+            out_frame: None,
+            loop_mode: LoopMode::Loop,
+        }
+    }
+}
+
+impl PlaybackSettings {
+    pub const MIN_RATE: f64 = 0.05;
+    pub const MAX_RATE: f64 = 16.0;
+
+    pub fn validate(&self) -> std::result::Result<(), String> {
+        if !(Self::MIN_RATE..=Self::MAX_RATE).contains(&self.rate) {
+            return Err(format!(
+                "Geschwindigkeit {} außerhalb von {}..{}",
+                self.rate,
+                Self::MIN_RATE,
+                Self::MAX_RATE
+            ));
+        }
+        if let Some(out_frame) = self.out_frame {
+            if out_frame < self.in_frame {
+                return Err(format!(
+                    "Out-Punkt {} liegt vor dem In-Punkt {}",
+                    out_frame, self.in_frame
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Einstellungen der Simulation, gespeichert als `simulation.json` im Aufnahme-Verzeichnis
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SimulationConfig {
+    pub pacing: Pacing,
+    pub left: PlaybackSettings,
+    pub right: PlaybackSettings,
+    /// Rechte Kamera folgt der linken (gemeinsame Uhr, gemeinsamer Loop)
+    pub lock_step: bool,
+    pub sync_by: SyncBy,
+    /// Startet die rechte Kamera um so viel später als die linke; None = aus den
+    /// Aufnahme-Metadaten (`first_frame_at`)
+    pub sync_offset_ms: Option<f64>,
+    /// Sensor- und Objektiv-Effekte, None = Frames wie aufgenommen
+    pub sensor: Option<StereoSensorConfig>,
+    /// Fehler-Injektion für Robustheitstests, None = perfekte Frames
+    pub faults: Option<StereoFaultConfig>,
+}
+
+impl Default for SimulationConfig {
+    fn default() -> Self {
+        Self {
+            pacing: Pacing::RealTime,
+            left: PlaybackSettings::default(),
+            right: PlaybackSettings::default(),
+            lock_step: true,
+            sync_by: SyncBy::Timestamp,
+            sync_offset_ms: None,
+            sensor: None,
+            faults: None,
+        }
+    }
+}
+
+impl SimulationConfig {
+    pub fn path(recording_dir: &Path) -> PathBuf {
+        recording_dir.join(CONFIG_FILE)
+    }
+
+    /// Liest die Konfiguration des Verzeichnisses; ohne Datei gelten die Standardwerte
+    pub fn load(recording_dir: &Path) -> Result<Self> {
+        let path = Self::path(recording_dir);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let json = fs::read_to_string(&path)?;
+        let config: Self = serde_json::from_str(&json)
+            .map_err(|e| SimulationConfigError::ParseError(format!("{}: {}", path.display(), e)))?;
+
+        for settings in [&config.left, &config.right] {
+            settings
+                .validate()
+                .map_err(SimulationConfigError::ParseError)?;
+        }
+        if let Some(sensor) = &config.sensor {
+            sensor
+                .validate()
+                .map_err(|e| SimulationConfigError::ParseError(format!("sensor: {}", e)))?;
+        }
+        if let Some(faults) = &config.faults {
+            faults
+                .validate()
+                .map_err(|e| SimulationConfigError::ParseError(format!("faults: {}", e)))?;
+        }
+        Ok(config)
+    }
+
+    /// Dieselbe Konfiguration mit getauschten Seiten: Einstellungen, Sensor und Fehler
+    /// wandern mit ihrer Aufnahme, Versätze wechseln das Vorzeichen
+    pub fn swapped(&self) -> Self {
+        let mut config = self.clone();
+        std::mem::swap(&mut config.left, &mut config.right);
+        config.sync_offset_ms = self.sync_offset_ms.map(|ms| -ms);
+        if let Some(sensor) = &mut config.sensor {
+            std::mem::swap(&mut sensor.left, &mut sensor.right);
+        }
+        if let Some(faults) = &mut config.faults {
+            std::mem::swap(&mut faults.left, &mut faults.right);
+            faults.desync_ms = -faults.desync_ms;
+        }
+        config
+    }
+
+    pub fn save(&self, recording_dir: &Path) -> Result<()> {
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| SimulationConfigError::ParseError(e.to_string()))?;
+        fs::write(Self::path(recording_dir), json)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,363 @@
+use std::f64::consts::PI;
+use std::fmt;
+
+use crate::seeded_rng::SeededRng;
+
+/// Testbilder der virtuellen Kameras
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TestPattern {
+    Stripes,          // Laufende Farbstreifen auf Grau (Farbe nach Kamera-ID)
+    ColorBars,        // SMPTE-Farbbalken
+    Checkerboard,     // Schachbrett über das ganze Bild
+    CalibrationBoard, // Schachbrett 10x7 mit Rand, wandert durchs Bild
+    Gradient,         // Grau-, Rot-, Grün- und Blaurampe
+    ZonePlate,        // Konzentrische Ringe bis zur Nyquist-Frequenz
+    Solid([u8; 3]),   // Einfarbig
+    Noise,            // Gleichverteiltes RGB-Rauschen, Seed = Kamera-ID
+}
+
+impl TestPattern {
+    /// Namen für Auswahllisten (GUI); `solid` steht hier für Grau
+    pub const NAMES: [&'static str; 8] = [
+        "stripes",
+        "smpte",
+        "checkerboard",
+        "calibration",
+        "gradient",
+        "zoneplate",
+        "solid",
+        "noise",
+    ];
+}
+
+impl fmt::Display for TestPattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TestPattern::Stripes => write!(f, "stripes"),
+            TestPattern::ColorBars => write!(f, "smpte"),
+            TestPattern::Checkerboard => write!(f, "checkerboard"),
+            TestPattern::CalibrationBoard => write!(f, "calibration"),
+            TestPattern::Gradient => write!(f, "gradient"),
+            TestPattern::ZonePlate => write!(f, "zoneplate"),
+            TestPattern::Solid([r, g, b]) => write!(f, "solid-{:02x}{:02x}{:02x}", r, g, b),
+            TestPattern::Noise => write!(f, "noise"),
+        }
+    }
+}
+
+impl std::str::FromStr for TestPattern {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let lower = s.to_lowercase();
+        if let Some(color) = lower.strip_prefix("solid") {
+            return parse_color(color.trim_start_matches('-')).map(TestPattern::Solid);
+        }
+        match lower.as_str() {
+            "stripes" => Ok(TestPattern::Stripes),
+            "smpte" | "bars" | "colorbars" => Ok(TestPattern::ColorBars),
+            "checkerboard" | "checker" => Ok(TestPattern::Checkerboard),
+            "calibration" | "calib" => Ok(TestPattern::CalibrationBoard),
+            "gradient" | "ramp" => Ok(TestPattern::Gradient),
+            "zoneplate" | "zone" => Ok(TestPattern::ZonePlate),
+            "noise" => Ok(TestPattern::Noise),
+            other => Err(format!(
+                "Unbekanntes Testbild: {} (stripes, smpte, checkerboard, calibration, gradient, zoneplate, solid-FARBE, noise)",
+                other
+            )),
+        }
+    }
+}
+
+/// `red`, `white` … oder Hex `ff8000`; leer = Grau
+fn parse_color(s: &str) -> std::result::Result<[u8; 3], String> {
+    let color = match s {
+        "" | "gray" | "grey" => [128, 128, 128],
+        "black" => [0, 0, 0],
+        "white" => [255, 255, 255],
+        "red" => [255, 0, 0],
+        "green" => [0, 255, 0],
+        "blue" => [0, 0, 255],
+        hex if hex.len() == 6 && hex.chars().all(|c| c.is_ascii_hexdigit()) => {
+            let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).unwrap_or(0);
+            [channel(0), channel(2), channel(4)]
+        }
+        other => return Err(format!("Unbekannte Farbe: {} (Name oder RRGGBB)", other)),
+    };
+    Ok(color)
+}
+
+/// Testbild samt Bewegung und Einblendung, auf der Kommandozeile z.B.
+/// `calibration,speed=120,overlay`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PatternConfig {
+    pub pattern: TestPattern,
+    /// Bewegung in Pixeln pro Sekunde (Streifen, Rampen, Kalibrierbrett, Zonenplatte)
+    pub speed: f64,
+    /// Frame-Zähler und Zeitstempel oben links einbrennen
+    pub overlay: bool,
+    /// Maschinenlesbarer Marker (Kamera, Sequenz, Erzeugungszeit) am unteren Rand,
+    /// siehe `frame_markers`
+    pub markers: bool,
+}
+
+pub const DEFAULT_SPEED: f64 = 50.0;
+
+impl Default for PatternConfig {
+    fn default() -> Self {
+        Self {
+            pattern: TestPattern::Stripes,
+            speed: DEFAULT_SPEED,
+            overlay: false,
+            markers: false,
+        }
+    }
+}
+
+impl fmt::Display for PatternConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.pattern)?;
+        if self.speed != DEFAULT_SPEED {
+            write!(f, ",speed={}", self.speed)?;
+        }
+        if self.overlay {
+            write!(f, ",overlay")?;
+        }
+        if self.markers {
+            write!(f, ",markers")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::str::FromStr for PatternConfig {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let mut parts = s.split(',');
+        let mut config = PatternConfig {
+            pattern: parts.next().unwrap_or_default().parse()?,
+            ..PatternConfig::default()
+        };
+        for option in parts {
+            match option.split_once('=') {
+                Some(("speed", value)) => {
+                    config.speed = value
+                        .parse()
+                        .ok()
+                        .filter(|speed: &f64| speed.is_finite() && *speed >= 0.0)
+                        .ok_or_else(|| {
+                            format!("Ungültige Geschwindigkeit: {} (Pixel/s, mindestens 0)", value)
+                        })?;
+                }
+                None if option == "overlay" => config.overlay = true,
+                None if option == "markers" => config.markers = true,
+                _ => {
+                    return Err(format!(
+                        "Unbekannte Testbild-Option: {} (speed=PX, overlay, markers)",
+                        option
+                    ));
+                }
+            }
+        }
+        Ok(config)
+    }
+}
+
+/// Zeichnet Frame `frame` zum Zeitpunkt `t` (Sekunden); `id` wählt die Streifenfarbe
+/// und den Seed des Rauschens
+pub fn render(config: &PatternConfig, id: u32, frame: u64, t: f64, width: u32, height: u32) -> Vec<u8> {
+    let (w, h) = (width as usize, height as usize);
+    let mut out = vec![0u8; w * h * 3];
+    let shift = (t * config.speed).max(0.0) as usize;
+
+    match config.pattern {
+        TestPattern::Stripes => {
+            let color = match id % 3 {
+                0 => [255, 0, 0],
+                1 => [0, 255, 0],
+                _ => [0, 0, 255],
+            };
+            let period = (w / 4).max(1);
+            fill(&mut out, w, |x, _| {
+                if (x + shift) % period < 10 { color } else { [50, 50, 50] }
+            });
+        }
+        TestPattern::ColorBars => fill(&mut out, w, |x, y| color_bar(x, y, w, h)),
+        TestPattern::Checkerboard => {
+            let square = (w.min(h) / 8).max(1);
+            fill(&mut out, w, |x, y| {
+                if (x / square + y / square).is_multiple_of(2) { [255, 255, 255] } else { [0, 0, 0] }
+            });
+        }
+        TestPattern::CalibrationBoard => draw_calibration_board(&mut out, w, h, t, config.speed),
+        TestPattern::Gradient => {
+            let band = h.div_ceil(4).max(1);
+            fill(&mut out, w, |x, y| {
+                let v = (((x + shift) % w) * 255 / (w - 1).max(1)) as u8;
+                match y / band {
+                    0 => [v, v, v],
+                    1 => [v, 0, 0],
+                    2 => [0, v, 0],
+                    _ => [0, 0, v],
+                }
+            });
+        }
+        TestPattern::ZonePlate => {
+            // Lokale Frequenz k*r/π erreicht 0,5 Perioden/Pixel in der Bildecke; die Ringe
+            // laufen bei halber Bildbreite mit `speed` Pixel/s nach außen
+            let (cx, cy) = (w as f64 / 2.0, h as f64 / 2.0);
+            let k = PI / (2.0 * (cx * cx + cy * cy).sqrt().max(1.0));
+            let phase = t * config.speed * k * 2.0 * cx;
+            fill(&mut out, w, |x, y| {
+                let (dx, dy) = (x as f64 - cx, y as f64 - cy);
+                let v = (127.5 + 127.5 * (k * (dx * dx + dy * dy) - phase).cos()) as u8;
+                [v, v, v]
+            });
+        }
+        TestPattern::Solid(color) => fill(&mut out, w, |_, _| color),
+        TestPattern::Noise => {
+            let mut rng = SeededRng::for_frame(id as u64, frame);
+            for pixel in out.chunks_exact_mut(3) {
+                pixel.copy_from_slice(&rng.next_u64().to_le_bytes()[..3]);
+            }
+        }
+    }
+
+    if config.overlay {
+        let millis = (t * 1000.0) as u64;
+        let text = format!(
+            "#{:06} {:02}:{:02}:{:02}.{:03}",
+            frame,
+            millis / 3_600_000,
+            millis / 60_000 % 60,
+            millis / 1000 % 60,
+            millis % 1000
+        );
+        draw_text(&mut out, w, h, &text);
+    }
+
+    out
+}
+
+fn fill(out: &mut [u8], w: usize, color: impl Fn(usize, usize) -> [u8; 3]) {
+    for (i, pixel) in out.chunks_exact_mut(3).enumerate() {
+        pixel.copy_from_slice(&color(i % w, i / w));
+    }
+}
+
+/// SMPTE-Balken (75 %): sieben Balken, Gegenbalken, unten -I, Weiß, +Q und PLUGE
+fn color_bar(x: usize, y: usize, w: usize, h: usize) -> [u8; 3] {
+    const TOP: [[u8; 3]; 7] = [
+        [191, 191, 191],
+        [191, 191, 0],
+        [0, 191, 191],
+        [0, 191, 0],
+        [191, 0, 191],
+        [191, 0, 0],
+        [0, 0, 191],
+    ];
+    const MIDDLE: [[u8; 3]; 7] = [
+        [0, 0, 191],
+        [19, 19, 19],
+        [191, 0, 191],
+        [19, 19, 19],
+        [0, 191, 191],
+        [19, 19, 19],
+        [191, 191, 191],
+    ];
+
+    let bar = x * 7 / w;
+    if y < h * 2 / 3 {
+        return TOP[bar];
+    }
+    if y < h * 3 / 4 {
+        return MIDDLE[bar];
+    }
+
+    // Untere Zeile in Viertel-Balken (-I, Weiß, +Q), PLUGE in Drittel von Balken 5
+    match (x * 28 / w, x * 21 / w) {
+        (0..=4, _) => [0, 33, 76],
+        (5..=9, _) => [255, 255, 255],
+        (10..=14, _) => [50, 0, 106],
+        (_, 15) => [9, 9, 9],
+        (_, 17) => [29, 29, 29],
+        _ => [19, 19, 19],
+    }
+}
+
+/// 10x7 Felder (9x6 innere Ecken) mit weißem Rand auf Grau; das Brett läuft auf einer
+/// Lissajous-Bahn, `speed` ist die höchste Geschwindigkeit in x
+fn draw_calibration_board(out: &mut [u8], w: usize, h: usize, t: f64, speed: f64) {
+    const COLS: usize = 10;
+    const ROWS: usize = 7;
+
+    let square = (h / 12).max(2);
+    let margin = square / 2;
+    let (board_w, board_h) = (COLS * square + 2 * margin, ROWS * square + 2 * margin);
+
+    let amplitude_x = w.saturating_sub(board_w) as f64 / 2.0;
+    let amplitude_y = h.saturating_sub(board_h) as f64 / 2.0;
+    let omega = if amplitude_x > 0.0 { speed / amplitude_x } else { 0.0 };
+    let left = (amplitude_x + amplitude_x * (omega * t).sin()) as isize;
+    let top = (amplitude_y + amplitude_y * (omega * 0.7 * t).sin()) as isize;
+
+    fill(out, w, |x, y| {
+        let (bx, by) = (x as isize - left, y as isize - top);
+        if bx < 0 || by < 0 || bx >= board_w as isize || by >= board_h as isize {
+            return [128, 128, 128];
+        }
+        let (fx, fy) = (bx as usize, by as usize);
+        if fx < margin || fy < margin || fx >= board_w - margin || fy >= board_h - margin {
+            return [255, 255, 255];
+        }
+        if ((fx - margin) / square + (fy - margin) / square).is_multiple_of(2) {
+            [0, 0, 0]
+        } else {
+            [255, 255, 255]
+        }
+    });
+}
+
+/// 5x7-Bitmap für Ziffern und die Zeichen des Overlays, Bit 4 = linke Spalte
+fn glyph(c: char) -> [u8; 7] {
+    match c {
+        '0' => [0x0E, 0x11, 0x13, 0x15, 0x19, 0x11, 0x0E],
+        '1' => [0x04, 0x0C, 0x04, 0x04, 0x04, 0x04, 0x0E],
+        '2' => [0x0E, 0x11, 0x01, 0x02, 0x04, 0x08, 0x1F],
+        '3' => [0x1F, 0x02, 0x04, 0x02, 0x01, 0x11, 0x0E],
+        '4' => [0x02, 0x06, 0x0A, 0x12, 0x1F, 0x02, 0x02],
+        '5' => [0x1F, 0x10, 0x1E, 0x01, 0x01, 0x11, 0x0E],
+        '6' => [0x06, 0x08, 0x10, 0x1E, 0x11, 0x11, 0x0E],
+        '7' => [0x1F, 0x01, 0x02, 0x04, 0x08, 0x08, 0x08],
+        '8' => [0x0E, 0x11, 0x11, 0x0E, 0x11, 0x11, 0x0E],
+        '9' => [0x0E, 0x11, 0x11, 0x0F, 0x01, 0x02, 0x0C],
+        ':' => [0x00, 0x0C, 0x0C, 0x00, 0x0C, 0x0C, 0x00],
+        '.' => [0x00, 0x00, 0x00, 0x00, 0x00, 0x0C, 0x0C],
+        '#' => [0x0A, 0x0A, 0x1F, 0x0A, 0x1F, 0x0A, 0x0A],
+        _ => [0; 7],
+    }
+}
+
+/// Weißer Text auf schwarzem Kasten oben links, dreifach vergrößert
+fn draw_text(out: &mut [u8], w: usize, h: usize, text: &str) {
+    const SCALE: usize = 3;
+    const PAD: usize = 4;
+    let origin = 8;
+    let box_w = text.chars().count() * 6 * SCALE + 2 * PAD;
+    let box_h = 7 * SCALE + 2 * PAD;
+
+    for y in origin..(origin + box_h).min(h) {
+        for x in origin..(origin + box_w).min(w) {
+            let (tx, ty) = (x - origin, y - origin);
+            let lit = tx >= PAD && ty >= PAD && {
+                let (col, row) = ((tx - PAD) / SCALE, (ty - PAD) / SCALE);
+                let c = text.chars().nth(col / 6).unwrap_or(' ');
+                row < 7 && col % 6 < 5 && glyph(c)[row] & (0x10 >> (col % 6)) != 0
+            };
+            let v = if lit { 255 } else { 0 };
+            let i = (y * w + x) * 3;
+            out[i..i + 3].copy_from_slice(&[v, v, v]);
+        }
+    }
+}
@@ -0,0 +1,221 @@
+use serde::Serialize;
+use std::fmt;
+use std::fs::OpenOptions;
+use std::io::{self, BufWriter, Write};
+use std::os::unix::fs::FileTypeExt;
+use std::path::Path;
+use std::process::Command;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum VideoPipeError {
+    #[error("IO Fehler: {0}")]
+    IoError(#[from] io::Error),
+    #[error("FIFO konnte nicht angelegt werden: {0}")]
+    FifoError(String),
+    #[error("Frame hat {0} Bytes, erwartet {1}")]
+    FrameSize(usize, usize),
+}
+
+pub type Result<T> = std::result::Result<T, VideoPipeError>;
+
+/// Format des Byte-Stroms für `stream`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PipeFormat {
+    Y4m, // YUV4MPEG2, 4:2:0 BT.601 (ffmpeg, mpv, x264 lesen es direkt)
+    Rgb, // JSON-Kopfzeile, danach RGB-Frames ohne Trenner
+    Raw, // Nur RGB-Frames (ffmpeg -f rawvideo -pix_fmt rgb24)
+}
+
+impl fmt::Display for PipeFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            PipeFormat::Y4m => "y4m",
+            PipeFormat::Rgb => "rgb",
+            PipeFormat::Raw => "raw",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl std::str::FromStr for PipeFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "y4m" | "yuv4mpeg" | "yuv4mpeg2" => Ok(PipeFormat::Y4m),
+            "rgb" | "rgb24" => Ok(PipeFormat::Rgb),
+            "raw" | "rawvideo" => Ok(PipeFormat::Raw),
+            other => Err(format!("Unbekanntes Ausgabeformat: {} (y4m, rgb oder raw)", other)),
+        }
+    }
+}
+
+/// Kopfzeile von `PipeFormat::Rgb`
+#[derive(Serialize)]
+struct RgbHeader {
+    format: &'static str,
+    width: u32,
+    height: u32,
+    fps: f64,
+    frame_size: usize,
+}
+
+/// Schreibt RGB-Frames im gewählten Format; der Kopf geht vor dem ersten Frame raus
+pub struct VideoPipeWriter<W: Write> {
+    out: W,
+    format: PipeFormat,
+    width: u32,
+    height: u32,
+    fps: f64,
+    header_written: bool,
+}
+
+impl<W: Write> VideoPipeWriter<W> {
+    pub fn new(out: W, format: PipeFormat, width: u32, height: u32, fps: f64) -> Self {
+        Self {
+            out,
+            format,
+            width,
+            height,
+            fps,
+            header_written: false,
+        }
+    }
+
+    pub fn write_frame(&mut self, rgb: &[u8]) -> Result<()> {
+        let expected = (self.width * self.height * 3) as usize;
+        if rgb.len() != expected {
+            return Err(VideoPipeError::FrameSize(rgb.len(), expected));
+        }
+
+        if !self.header_written {
+            self.write_header()?;
+            self.header_written = true;
+        }
+
+        match self.format {
+            PipeFormat::Y4m => {
+                self.out.write_all(b"FRAME\n")?;
+                self.out.write_all(&rgb_to_i420(rgb, self.width, self.height))?;
+            }
+            PipeFormat::Rgb | PipeFormat::Raw => self.out.write_all(rgb)?,
+        }
+        // Leser sollen jeden Frame sofort sehen, nicht erst bei vollem Puffer
+        self.out.flush()?;
+        Ok(())
+    }
+
+    fn write_header(&mut self) -> Result<()> {
+        match self.format {
+            PipeFormat::Y4m => {
+                let (num, den) = frame_rate_ratio(self.fps);
+                writeln!(
+                    self.out,
+                    "YUV4MPEG2 W{} H{} F{}:{} Ip A1:1 C420jpeg",
+                    self.width, self.height, num, den
+                )?;
+            }
+            PipeFormat::Rgb => {
+                let header = RgbHeader {
+                    format: "rgb24",
+                    width: self.width,
+                    height: self.height,
+                    fps: self.fps,
+                    frame_size: (self.width * self.height * 3) as usize,
+                };
+                let line = serde_json::to_string(&header).map_err(io::Error::other)?;
+                writeln!(self.out, "{}", line)?;
+            }
+            PipeFormat::Raw => {}
+        }
+        Ok(())
+    }
+}
+
+/// stdout (bei `None` oder `-`) oder eine FIFO; eine fehlende FIFO wird mit `mkfifo` angelegt.
+/// Das Öffnen einer FIFO blockiert, bis ein Leser verbunden ist.
+pub fn open_output(path: Option<&Path>) -> Result<Box<dyn Write>> {
+    let path = match path {
+        None => return Ok(Box::new(BufWriter::new(io::stdout()))),
+        Some(path) if path == Path::new("-") => return Ok(Box::new(BufWriter::new(io::stdout()))),
+        Some(path) => path,
+    };
+
+    if !path.exists() {
+        let status = Command::new("mkfifo")
+            .arg(path)
+            .status()
+            .map_err(|e| VideoPipeError::FifoError(e.to_string()))?;
+        if !status.success() {
+            return Err(VideoPipeError::FifoError(format!("mkfifo {}: {}", path.display(), status)));
+        }
+    }
+    if !path.metadata()?.file_type().is_fifo() {
+        return Err(VideoPipeError::FifoError(format!(
+            "{} existiert und ist keine FIFO",
+            path.display()
+        )));
+    }
+
+    let file = OpenOptions::new().write(true).open(path)?;
+    Ok(Box::new(BufWriter::new(file)))
+}
+
+/// Der Leser hat die Pipe geschlossen (z.B. ffmpeg beendet) – normales Ende
+pub fn is_closed_pipe(error: &VideoPipeError) -> bool {
+    matches!(error, VideoPipeError::IoError(e) if e.kind() == io::ErrorKind::BrokenPipe)
+}
+
+/// Framerate als Bruch für Y4M, NTSC-Raten als x000:1001
+fn frame_rate_ratio(fps: f64) -> (u64, u64) {
+    if (fps - fps.round()).abs() < 0.001 {
+        return (fps.round().max(1.0) as u64, 1);
+    }
+    let ntsc = fps * 1.001;
+    if (ntsc - ntsc.round()).abs() < 0.001 {
+        return (ntsc.round() as u64 * 1000, 1001);
+    }
+    ((fps * 1000.0).round().max(1.0) as u64, 1000)
+}
+
+/// RGB nach I420 (BT.601, begrenzter Bereich); Chroma als Mittel je 2x2-Block
+fn rgb_to_i420(rgb: &[u8], width: u32, height: u32) -> Vec<u8> {
+    let (w, h) = (width as usize, height as usize);
+    let (cw, ch) = (w.div_ceil(2), h.div_ceil(2));
+    let mut out = vec![0u8; w * h + 2 * cw * ch];
+    let (y_plane, chroma) = out.split_at_mut(w * h);
+    let (u_plane, v_plane) = chroma.split_at_mut(cw * ch);
+
+    let pixel = |x: usize, y: usize| {
+        let i = (y * w + x) * 3;
+        (rgb[i] as i32, rgb[i + 1] as i32, rgb[i + 2] as i32)
+    };
+
+    for y in 0..h {
+        for x in 0..w {
+            let (r, g, b) = pixel(x, y);
+            y_plane[y * w + x] = (((66 * r + 129 * g + 25 * b + 128) >> 8) + 16) as u8;
+        }
+    }
+
+    for cy in 0..ch {
+        for cx in 0..cw {
+            let (mut r, mut g, mut b, mut n) = (0, 0, 0, 0);
+            for y in (cy * 2)..(cy * 2 + 2).min(h) {
+                for x in (cx * 2)..(cx * 2 + 2).min(w) {
+                    let (pr, pg, pb) = pixel(x, y);
+                    r += pr;
+                    g += pg;
+                    b += pb;
+                    n += 1;
+                }
+            }
+            let (r, g, b) = (r / n, g / n, b / n);
+            u_plane[cy * cw + cx] = (((-38 * r - 74 * g + 112 * b + 128) >> 8) + 128) as u8;
+            v_plane[cy * cw + cx] = (((112 * r - 94 * g - 18 * b + 128) >> 8) + 128) as u8;
+        }
+    }
+
+    out
+}
@@ -1,7 +1,11 @@
 use std::sync::{Arc, Mutex};
-use std::time::{Duration, Instant};
+use std::time::Instant;
 use thiserror::Error;
 
+use crate::frame_clock::{FrameClock, FrameClockStats, TimingSource};
+use crate::frame_markers::{self, FrameMarker};
+use crate::test_patterns::{self, PatternConfig};
+
 #[derive(Error, Debug)]
 pub enum VirtualCameraError {
     #[error("Virtual camera could not be created")]
@@ -16,66 +20,52 @@ pub struct VirtualCamera {
     id: u32,
     width: u32,
     height: u32,
-    fps: u32,
+    /// 0 = ohne Takt
+    fps: f64,
+    pattern: PatternConfig,
     frame_count: Arc<Mutex<u64>>,
     start_time: Instant,
+    frame_clock: Mutex<FrameClock>,
 }
 
 impl VirtualCamera {
-    pub fn new(id: u32, width: u32, height: u32, fps: u32) -> Self {
+    pub fn new(id: u32, width: u32, height: u32, fps: f64) -> Self {
         Self {
             id,
             width,
             height,
             fps,
+            pattern: PatternConfig::default(),
             frame_count: Arc::new(Mutex::new(0)),
             start_time: Instant::now(),
+            frame_clock: Mutex::new(FrameClock::new(fps, TimingSource::Clock)),
         }
     }
 
+    /// Testbild, Bewegung und Einblendung (Standard: laufende Streifen)
+    pub fn with_pattern(mut self, pattern: PatternConfig) -> Self {
+        self.pattern = pattern;
+        self
+    }
+
     pub fn get_frame(&self) -> Result<Vec<u8>> {
         let mut count = self.frame_count.lock().unwrap();
+        let frame = *count;
         *count += 1;
 
-        let frame_size = (self.width * self.height * 3) as usize;
-        let mut frame = vec![0u8; frame_size];
-
-        let elapsed = self.start_time.elapsed().as_secs_f32();
-        let offset = ((elapsed * 50.0) as u32) % self.width;
-
-        for y in 0..self.height {
-            for x in 0..self.width {
-                let idx = ((y * self.width + x) * 3) as usize;
-
-                let pattern = (x + offset) % (self.width / 4);
-
-                if pattern < 10 {
-                    match self.id % 3 {
-                        0 => {
-                            frame[idx] = 255;
-                            frame[idx + 1] = 0;
-                            frame[idx + 2] = 0;
-                        }
-                        1 => {
-                            frame[idx] = 0;
-                            frame[idx + 1] = 255;
-                            frame[idx + 2] = 0;
-                        }
-                        _ => {
-                            frame[idx] = 0;
-                            frame[idx + 1] = 0;
-                            frame[idx + 2] = 255;
-                        }
-                    }
-                } else {
-                    frame[idx] = 50;
-                    frame[idx + 1] = 50;
-                    frame[idx + 2] = 50;
-                }
-            }
+        // Bewegung nach Frame-Nummer, damit jeder Takt (auch "fast") dieselben Bilder liefert
+        let t = if self.fps > 0.0 {
+            frame as f64 / self.fps
+        } else {
+            self.start_time.elapsed().as_secs_f64()
+        };
+
+        let mut data = test_patterns::render(&self.pattern, self.id, frame, t, self.width, self.height);
+        if self.pattern.markers {
+            let marker = FrameMarker::now(self.id as u8, frame as u32);
+            frame_markers::embed(&mut data, self.width, self.height, marker);
         }
-
-        Ok(frame)
+        Ok(data)
     }
 
     pub fn get_id(&self) -> u32 {
@@ -86,19 +76,23 @@ impl VirtualCamera {
         (self.width, self.height)
     }
 
-    pub fn get_fps(&self) -> u32 {
+    pub fn get_fps(&self) -> f64 {
         self.fps
     }
 
+    /// Wartet bis zur nächsten Frame-Deadline (bei fps 0 ohne Wartezeit)
     pub fn wait_for_next_frame(&self) {
-        let frame_duration = Duration::from_millis(1000 / self.fps as u64);
-        std::thread::sleep(frame_duration);
+        self.frame_clock.lock().unwrap().wait();
+    }
+
+    pub fn get_timing_stats(&self) -> FrameClockStats {
+        self.frame_clock.lock().unwrap().stats()
     }
 }
 
 pub fn create_virtual_cameras() -> Vec<VirtualCamera> {
     vec![
-        VirtualCamera::new(0, 640, 480, 30),
-        VirtualCamera::new(1, 640, 480, 30),
+        VirtualCamera::new(0, 640, 480, 30.0),
+        VirtualCamera::new(1, 640, 480, 30.0),
     ]
 }